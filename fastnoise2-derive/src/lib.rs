@@ -0,0 +1,340 @@
+//! Procedural macros for [fastnoise2](https://docs.rs/fastnoise2): a derive macro for composite generators, and
+//! encoded node trees checked at compile time.
+//!
+//! Enable the `derive` and `macros` features of fastnoise2 and use them as `fastnoise2::generator::Generator` and
+//! `fastnoise2::encoded_tree!`, rather than depending on this crate directly.
+use std::sync::LazyLock;
+
+use fastnoise2_sys::{
+    encoded::{self, DecodeError, NodeLayout},
+    FASTNOISE2_BUILD_KIND,
+};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `Generator` for a struct describing a reusable subtree, a `new` constructor returning it wrapped in a
+/// `GeneratorWrapper`, and a `with_*` builder method on the wrapper for each named field.
+///
+/// The struct must derive [`Clone`] and [`Debug`], and have a `tree(&self)` method returning the subtree it stands
+/// for as an `impl Generator`. Building the struct builds the tree returned by `tree`, and visiting it visits that
+/// tree. Folding it returns it unchanged, since its fields are not members of a node.
+///
+/// `new` takes the fields in order, like `perlin()` it returns a `GeneratorWrapper` so that the combinators of the
+/// typed generators, such as `fbm` or `+`, apply to it directly.
+///
+/// Builder methods take the wrapper by value and return it with the field set, like `with_feature_scale` on a Perlin
+/// generator. Rust only allows inherent methods on `GeneratorWrapper` in fastnoise2, where it is defined, so they are
+/// declared by a trait named after the struct with a `Builders` suffix, e.g. `SnowyMountainsBuilders`. Import it next
+/// to the struct to call them, as the prelude can't re-export it. The trait and `new` have the visibility of the
+/// struct, and builder methods the documentation of the field or `Sets <field>.` if it has none. Mark a field with
+/// `#[generator(skip)]` to not generate its builder method.
+///
+/// # Examples
+///
+/// ```rust
+/// mod terrain {
+///     use fastnoise2::generator::prelude::*;
+///
+///     /// Mountain range with snow caps above `snow_line`.
+///     #[derive(Clone, Debug, Generator)]
+///     pub struct SnowyMountains {
+///         /// Height of the peaks.
+///         pub height: f32,
+///         pub snow_line: f32,
+///         #[generator(skip)]
+///         pub octaves: i32,
+///     }
+///
+///     impl SnowyMountains {
+///         fn tree(&self) -> impl Generator {
+///             let mountains = perlin().ridged(0.5, 0.0, self.octaves, 2.0) * self.height;
+///             let snow = (mountains.clone() - self.snow_line).max(0.0);
+///             mountains + snow
+///         }
+///     }
+/// }
+///
+/// use fastnoise2::generator::prelude::*;
+/// // `with_height` is declared by `SnowyMountainsBuilders`.
+/// use terrain::{SnowyMountains, SnowyMountainsBuilders};
+///
+/// let node = SnowyMountains::new(1.0, 0.5, 4)
+///     .with_height(2.0)
+///     .domain_scale(0.5)
+///     .build();
+/// ```
+#[proc_macro_derive(Generator, attributes(generator))]
+pub fn derive_generator(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Checks an encoded node tree exported by the Node Editor at compile time, and expands to a `&'static SafeNode`
+/// decoding it on first use.
+///
+/// The tree is decoded at compile time against the node table of the linked FastNoise2 library, so a tree with invalid
+/// text, a node id the library doesn't have, a member a node doesn't have, a missing node lookup, an enum value out of
+/// range or data left over doesn't compile, with an error pointing at the string. It is decoded again into nodes once
+/// per call site, by `SafeNode::from_encoded_node_tree`, which panics if FastNoise2 rejects it anyway. Only the text is
+/// checked for docs.rs builds, where no library is linked.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use fastnoise2::{encoded_tree, SafeNode};
+///
+/// let node: &'static SafeNode = encoded_tree!(
+///     "E@BBZEG@BD8JFgIECArXIzwECiQIw/UoPwkuAAE@BJDQAH@BC@AIEAJBw@ABZEED0KV78YZmZmPwQDmpkZPwsAAIA/HAMAAHBCBA=="
+/// );
+/// let value = node.gen_single_2d(0.0, 0.0, 1337);
+/// ```
+///
+/// Strings pasted with a typo or whitespace don't compile:
+///
+/// ```rust,compile_fail
+/// let node = fastnoise2::encoded_tree!("E@BBZEG@BD8JFgIECArXIzwECiQIw/UoPwkuAAE@BJDQAH@BC@AIEAJBw ");
+/// ```
+///
+/// Nor do truncated ones:
+///
+/// ```rust,compile_fail
+/// let node = fastnoise2::encoded_tree!("E@BBZEG@BD8JFgIECArXIzwECiQIw/UoPwkuAAE@BJDQAH@BC@AIEAJBw@ABZEED0KV78YZmZmPwQDm");
+/// ```
+///
+/// Nor trees of nodes the library doesn't have, here the node id 255:
+///
+/// ```rust,compile_fail
+/// let node = fastnoise2::encoded_tree!("/wQ=");
+/// ```
+#[proc_macro]
+pub fn encoded_tree(input: TokenStream) -> TokenStream {
+    let encoded = parse_macro_input!(input as LitStr);
+    if let Err(error) = check_encoded_tree(&encoded.value()) {
+        return Error::new(encoded.span(), error)
+            .into_compile_error()
+            .into();
+    }
+    quote! {
+        {
+            static NODE: ::std::sync::LazyLock<::fastnoise2::SafeNode> = ::std::sync::LazyLock::new(|| {
+                ::fastnoise2::SafeNode::from_encoded_node_tree(#encoded)
+                    .unwrap_or_else(|error| ::core::panic!("{error}"))
+            });
+            &*NODE
+        }
+    }
+    .into()
+}
+
+/// Node table of the linked FastNoise2 library, read once per compiler process.
+static NODES: LazyLock<Vec<NodeLayout>> = LazyLock::new(encoded::library_layouts);
+
+/// Decodes an encoded node tree against the node table of the linked library.
+fn check_encoded_tree(encoded: &str) -> Result<(), DecodeError> {
+    match FASTNOISE2_BUILD_KIND {
+        // Nothing is linked for docs.rs, only the text can be checked.
+        "docs.rs" => encoded::decode_bytes(encoded).map(drop),
+        _ => encoded::decode(encoded, &NODES).map(drop),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(
+            input.ident.span(),
+            "`Generator` can only be derived for structs",
+        ));
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let constructor = constructor(input, &data.fields);
+    let builders = builders(&data.fields)?;
+    let builders = (!builders.is_empty()).then(|| {
+        let vis = &input.vis;
+        let name_builders = format_ident!("{}Builders", name);
+        let doc = format!("`with_*` builder methods of a [`{name}`] in a `GeneratorWrapper`, derived by `Generator`.");
+        let (signatures, methods): (Vec<_>, Vec<_>) = builders.into_iter().unzip();
+        quote! {
+            #[doc = #doc]
+            #vis trait #name_builders #impl_generics #where_clause {
+                #(#signatures)*
+            }
+
+            impl #impl_generics #name_builders #ty_generics
+                for ::fastnoise2::generator::GeneratorWrapper<#name #ty_generics>
+            #where_clause
+            {
+                #(#methods)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::fastnoise2::generator::Generator for #name #ty_generics #where_clause {
+            fn try_build(
+                &self,
+            ) -> ::core::result::Result<
+                ::fastnoise2::generator::GeneratorWrapper<::fastnoise2::SafeNode>,
+                ::fastnoise2::FastNoiseError,
+            > {
+                ::fastnoise2::generator::Generator::try_build(&self.tree())
+            }
+
+            fn visit<__V: ::fastnoise2::GeneratorVisitor + ?::core::marker::Sized>(
+                &self,
+                visitor: &mut __V,
+            ) {
+                ::fastnoise2::generator::Generator::visit(&self.tree(), visitor);
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #constructor
+        }
+
+        #builders
+    })
+}
+
+/// `new` constructor taking the fields in order and returning the struct in a `GeneratorWrapper`.
+fn constructor(input: &DeriveInput, fields: &Fields) -> TokenStream2 {
+    let vis = &input.vis;
+    let doc = format!(
+        "Creates a [`{}`] wrapped in a `GeneratorWrapper`, so that combinators apply to it.",
+        input.ident
+    );
+    let (params, body) = match fields {
+        Fields::Named(fields) => {
+            let idents: Vec<_> = fields.named.iter().map(|field| &field.ident).collect();
+            let types = fields.named.iter().map(|field| &field.ty);
+            (
+                quote! { #(#idents: #types),* },
+                quote! { Self { #(#idents),* } },
+            )
+        }
+        Fields::Unnamed(fields) => {
+            let idents: Vec<_> = (0..fields.unnamed.len())
+                .map(|index| format_ident!("field_{}", index))
+                .collect();
+            let types = fields.unnamed.iter().map(|field| &field.ty);
+            (
+                quote! { #(#idents: #types),* },
+                quote! { Self(#(#idents),*) },
+            )
+        }
+        Fields::Unit => (quote! {}, quote! { Self }),
+    };
+    quote! {
+        #[doc = #doc]
+        #[allow(clippy::too_many_arguments)]
+        #vis fn new(#params) -> ::fastnoise2::generator::GeneratorWrapper<Self> {
+            ::fastnoise2::generator::GeneratorWrapper(#body)
+        }
+    }
+}
+
+/// Signatures and implementations of the builder methods of the named fields not marked `#[generator(skip)]`.
+fn builders(fields: &Fields) -> Result<Vec<(TokenStream2, TokenStream2)>, Error> {
+    let Fields::Named(fields) = fields else {
+        return Ok(Vec::new());
+    };
+    let mut builders = Vec::new();
+    for field in &fields.named {
+        if is_skipped(field)? {
+            continue;
+        }
+        let ident = field
+            .ident
+            .as_ref()
+            .expect("named fields have an identifier");
+        let ty = &field.ty;
+        let field_name = ident.to_string();
+        let field_name = field_name.trim_start_matches("r#");
+        let method = format_ident!("with_{}", field_name, span = ident.span());
+        let docs: Vec<_> = field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"))
+            .collect();
+        let docs = if docs.is_empty() {
+            let doc = format!("Sets `{field_name}`.");
+            quote! { #[doc = #doc] }
+        } else {
+            quote! { #(#docs)* }
+        };
+        builders.push((
+            quote! {
+                #docs
+                fn #method(self, #ident: #ty) -> Self;
+            },
+            quote! {
+                fn #method(mut self, #ident: #ty) -> Self {
+                    self.0.#ident = #ident;
+                    self
+                }
+            },
+        ));
+    }
+    Ok(builders)
+}
+
+/// Returns `true` if the field is marked `#[generator(skip)]`.
+fn is_skipped(field: &syn::Field) -> Result<bool, Error> {
+    let mut skip = false;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("generator") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown `generator` attribute, expected `skip`"))
+            }
+        })?;
+    }
+    Ok(skip)
+}
+
+#[cfg(test)]
+mod tests {
+    use fastnoise2_sys::encoded::EncodedNode;
+
+    use super::*;
+
+    #[test]
+    fn test_check_encoded_tree() {
+        let (id, _) = NODES
+            .iter()
+            .enumerate()
+            .find(|(_, node)| node.node_lookups.is_empty())
+            .unwrap();
+        let node = EncodedNode {
+            id,
+            variables: Vec::new(),
+            node_lookups: Vec::new(),
+            hybrids: Vec::new(),
+        };
+        assert_eq!(check_encoded_tree(&encoded::encode(&node)), Ok(()));
+    }
+
+    #[test]
+    fn test_check_encoded_tree_errors() {
+        assert_eq!(
+            check_encoded_tree("/wQ="),
+            Err(DecodeError::UnknownNode { id: 255, offset: 0 })
+        );
+        assert_eq!(
+            check_encoded_tree("DQAF AAAA"),
+            Err(DecodeError::InvalidCharacter {
+                character: ' ',
+                index: 4
+            })
+        );
+    }
+}
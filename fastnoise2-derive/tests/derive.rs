@@ -0,0 +1,115 @@
+use fastnoise2::generator::{perlin::Perlin, prelude::*};
+
+/// Mountain range with snow caps above `snow_line`.
+#[derive(Clone, Debug, Generator)]
+struct SnowyMountains {
+    /// Height of the peaks.
+    height: f32,
+    snow_line: f32,
+    #[generator(skip)]
+    octaves: i32,
+}
+
+impl Default for SnowyMountains {
+    fn default() -> Self {
+        Self {
+            height: 1.0,
+            snow_line: 0.5,
+            octaves: 4,
+        }
+    }
+}
+
+impl SnowyMountains {
+    fn tree(&self) -> impl Generator {
+        let mountains = perlin().ridged(0.5, 0.0, self.octaves, 2.0) * self.height;
+        let snow = (mountains.clone() - self.snow_line).max(0.0);
+        mountains + snow
+    }
+}
+
+/// Generic over its source, like the typed generators.
+#[derive(Clone, Debug, Generator)]
+struct Plateau<S: Generator> {
+    source: S,
+    level: f32,
+}
+
+impl<S: Generator> Plateau<S> {
+    fn tree(&self) -> impl Generator {
+        GeneratorWrapper(self.source.clone()).min(self.level)
+    }
+}
+
+#[derive(Clone, Debug, Generator)]
+struct Wrapped(GeneratorWrapper<Perlin>);
+
+impl Wrapped {
+    fn tree(&self) -> impl Generator {
+        self.0.clone()
+    }
+}
+
+#[test]
+fn test_builds_tree() {
+    let mountains = SnowyMountains::default();
+    assert!(mountains.same_tree(&mountains.tree()));
+    let node = mountains.build();
+    let mut output = [0.0f32; 16];
+    let min_max = node.gen_uniform_grid_2d(&mut output, 0.0, 0.0, 4, 4, 0.1, 0.1, 1337);
+    assert!(min_max.min.is_finite());
+    assert!(min_max.max.is_finite());
+}
+
+#[test]
+fn test_builder_methods() {
+    let mountains = SnowyMountains::new(1.0, 0.5, 6)
+        .with_height(2.0)
+        .with_snow_line(0.75);
+    assert_eq!(mountains.height, 2.0);
+    assert_eq!(mountains.snow_line, 0.75);
+    assert_eq!(mountains.octaves, 6);
+    assert!(!mountains.same_tree(&SnowyMountains::default()));
+}
+
+#[test]
+fn test_generic_struct() {
+    let plateau = Plateau::new(perlin(), 0.5)
+        .with_level(0.25)
+        .with_source(perlin().with_feature_scale(10.0));
+    assert!(plateau.same_tree(&perlin().with_feature_scale(10.0).min(0.25)));
+}
+
+#[test]
+fn test_composes_with_combinators() {
+    let mountains = SnowyMountains::new(1.0, 0.5, 4);
+    let node = (mountains.clone().domain_scale(0.5) + 1.0).build();
+    assert_eq!(
+        node.0,
+        (GeneratorWrapper(mountains.tree()).domain_scale(0.5) + 1.0)
+            .build()
+            .0
+    );
+    assert!(Wrapped::new(perlin()).same_tree(&perlin()));
+}
+
+#[test]
+fn test_visits_tree() {
+    /// Counts the visited nodes.
+    struct Count(usize);
+
+    impl fastnoise2::GeneratorVisitor for Count {
+        fn enter_node(&mut self, _name: &str) -> bool {
+            self.0 += 1;
+            true
+        }
+    }
+
+    let plateau = Plateau {
+        source: perlin(),
+        level: 0.5,
+    };
+    let mut count = Count(0);
+    plateau.visit(&mut count);
+    assert_eq!(count.0, 2);
+}
@@ -0,0 +1,19 @@
+use fastnoise2::{encoded_tree, SafeNode};
+
+/// "Mountain Terrain" tree integrated into FastNoise2 Node Editor.
+fn mountain_terrain() -> &'static SafeNode {
+    encoded_tree!(
+        "E@BBZEG@BD8JFgIECArXIzwECiQIw/UoPwkuAAE@BJDQAH@BC@AIEAJBw@ABZEED0KV78YZmZmPwQDmpkZPwsAAIA/HAMAAHBCBA=="
+    )
+}
+
+#[test]
+fn test_encoded_tree() {
+    let node = mountain_terrain();
+    // Decoded once per call site.
+    assert!(std::ptr::eq(node, mountain_terrain()));
+    let mut output = [0.0f32; 16];
+    let min_max = node.gen_uniform_grid_2d(&mut output, 0.0, 0.0, 4, 4, 0.1, 0.1, 1337);
+    assert!(min_max.min.is_finite());
+    assert!(min_max.max.is_finite());
+}
@@ -0,0 +1,43 @@
+// This example prints typed generators generated from the metadata of the linked FastNoise2 library, for the nodes
+// given as arguments, or a whole module for every node if there are none. The output is copied into the `generator`
+// modules by hand, and the drift test of `codegen` fails until they match it.
+//
+// cargo run --example codegen -- FractalFBm Remap > generated.rs
+//
+// With `tree`, it prints instead a function building the given encoded node tree, see `codegen::tree_source`.
+//
+// cargo run --example codegen -- tree "E@BBZEG@BD8J..." terrain > terrain.rs
+use fastnoise2::{
+    codegen::{tree_source, typed_generator_source, typed_generators_source},
+    FastNoiseError, SafeNode,
+};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        None => {
+            print!("{}", typed_generators_source());
+            Ok(())
+        }
+        Some("tree") => print_tree(&args[1..]),
+        Some(_) => args
+            .iter()
+            .try_for_each(|node| typed_generator_source(node).map(|source| print!("{source}"))),
+    };
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+fn print_tree(args: &[String]) -> Result<(), FastNoiseError> {
+    let Some(encoded) = args.first() else {
+        eprintln!("usage: codegen tree <encoded node tree> [function name]");
+        std::process::exit(2);
+    };
+    let function_name = args.get(1).map_or("tree", String::as_str);
+    let node = SafeNode::from_encoded_node_tree(encoded)?;
+    print!("{}", tree_source(&node, function_name)?);
+    Ok(())
+}
@@ -14,17 +14,17 @@ fn create_node() -> Result<Node, FastNoiseError> {
 
     let mut fractal = Node::from_name("FractalFBm")?;
     let simplex = Node::from_name("Simplex")?;
-    fractal.set("Source", &simplex)?;
+    fractal.set("Source", simplex)?;
     fractal.set("Gain", 3.0)?;
     fractal.set("Lacunarity", 0.6)?;
 
     let mut add_dim = Node::from_name("AddDimension")?;
-    add_dim.set("Source", &cellular)?;
+    add_dim.set("Source", cellular)?;
     add_dim.set("NewDimensionPosition", 0.5)?;
 
     let mut max_smooth = Node::from_name("MaxSmooth")?;
-    max_smooth.set("LHS", &fractal)?;
-    max_smooth.set("RHS", &add_dim)?;
+    max_smooth.set("LHS", fractal)?;
+    max_smooth.set("RHS", add_dim)?;
 
     Ok(max_smooth)
 }
@@ -7,6 +7,8 @@ fn main() {
     .unwrap();
 
     // A SafeNode is an Arc wrapping a Node. Thus, cloning a SafeNode does not reinstantiate it.
+    // Trees that may keep state between calls (GeneratorCache), which includes all encoded trees, are transparently
+    // instantiated once per thread when used from another thread than the one that created them.
     let n1 = node.clone();
     let t1 = std::thread::spawn(move || {
         for i in 0..50 {
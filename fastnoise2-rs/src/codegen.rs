@@ -0,0 +1,716 @@
+//! Generation of typed generator source code from the metadata of the linked FastNoise2 library.
+//!
+//! The typed generators in [`crate::generator`] are written by hand, with their combinators and documentation, and
+//! nothing in `src/` is generated. When FastNoise2 adds or renames nodes and members, this module writes the structs,
+//! [`Generator`][crate::generator::Generator] implementations and enums matching the metadata, to be copied into the
+//! `generator` modules by hand and completed there.
+//!
+//! Two tests of this module keep the committed generators in sync with the metadata:
+//!
+//! - The coverage test builds one instance of every typed generator, listed by hand in the test, and fails if a node
+//!   or member of the metadata is set by none of them. A new typed generator must be added to that list.
+//! - The drift test compares the members set, visited and folded by each committed `Generator` implementation, and
+//!   the variants of the enums, with the generated source, and fails on any difference.
+//!
+//! Run the `codegen` example to print the source of some or all nodes:
+//!
+//! ```sh
+//! cargo run --example codegen -- FractalFBm Remap > generated.rs && rustfmt generated.rs
+//! ```
+//!
+//! [`tree_source`] writes the source building a given tree with the typed generators, e.g. to turn a tree assembled
+//! with [`Node::set`][crate::Node::set] into reviewable code.
+use std::{collections::BTreeMap, fmt::Write};
+
+use crate::{
+    metadata::{format_lookup, metadata_id, Member, Metadata, METADATA_NAME_LOOKUP, NODE_METADATA},
+    FastNoiseError, MemberType,
+};
+
+mod tree;
+
+pub use tree::tree_source;
+
+const HEADER: &str = "\
+use std::fmt::Display;
+
+use super::{
+    content_eq, enum_member_value, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper, Hybrid,
+    NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
+";
+
+/// Returns the source of a typed generator for the node named `node_name`, followed by the enums of its members.
+///
+/// # Errors
+/// Returns [`FastNoiseError::MetadataNameNotFound`] if the linked library has no such node.
+pub fn typed_generator_source(node_name: &str) -> Result<String, FastNoiseError> {
+    let metadata = find_metadata(node_name)?;
+    let mut enums = BTreeMap::new();
+    let mut source = String::new();
+    write_node(&mut source, metadata, &mut enums);
+    write_enums(&mut source, &enums);
+    Ok(source)
+}
+
+/// Returns the source of typed generators for every node of the linked library, as a module. Enums shared by several
+/// nodes, such as `DistanceFunction`, are written once.
+pub fn typed_generators_source() -> String {
+    let mut enums = BTreeMap::new();
+    let mut source = String::from(HEADER);
+    let mut nodes: Vec<_> = NODE_METADATA.iter().collect();
+    nodes.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    for metadata in nodes {
+        write_node(&mut source, metadata, &mut enums);
+    }
+    write_enums(&mut source, &enums);
+    source
+}
+
+pub(crate) fn find_metadata(node_name: &str) -> Result<&'static Metadata, FastNoiseError> {
+    metadata_id(node_name).map(|id| &NODE_METADATA[id as usize])
+}
+
+/// Members of a node in the order of the generated fields: node lookups, hybrids, then variables.
+pub(crate) fn ordered_members(metadata: &Metadata) -> Vec<&Member> {
+    let mut members: Vec<_> = metadata.members.values().collect();
+    members.sort_by_key(|member| {
+        let kind = match member.member_type {
+            MemberType::NodeLookup => 0,
+            MemberType::Hybrid => 1,
+            _ => 2,
+        };
+        (kind, member.index)
+    });
+    members
+}
+
+/// Generated field of a typed generator.
+struct Field<'a> {
+    member: &'a Member,
+    name: String,
+    /// Type of the field: a generic parameter, `f32`, `i32`, `bool` or an enum.
+    ty: String,
+}
+
+fn fields<'a>(metadata: &'a Metadata, enums: &mut BTreeMap<String, Vec<String>>) -> Vec<Field<'a>> {
+    let mut generics: Vec<String> = Vec::new();
+    ordered_members(metadata)
+        .into_iter()
+        .map(|member| {
+            let ty = match member.member_type {
+                MemberType::Float => "f32".to_string(),
+                MemberType::Int => "i32".to_string(),
+                MemberType::Enum if member.enum_display_names == ["False", "True"] => {
+                    "bool".to_string()
+                }
+                MemberType::Enum => enum_name(metadata, member, enums),
+                MemberType::NodeLookup | MemberType::Hybrid => {
+                    let initial = match member.dimension {
+                        Some(dimension) => ['X', 'Y', 'Z', 'W'][dimension].to_string(),
+                        None => camel_case(&member.display_name)[..1].to_string(),
+                    };
+                    let generic = if generics.contains(&initial) {
+                        camel_case(&member.set_name())
+                    } else {
+                        initial
+                    };
+                    generics.push(generic.clone());
+                    generic
+                }
+            };
+            Field {
+                member,
+                name: field_name(member),
+                ty,
+            }
+        })
+        .collect()
+}
+
+/// Returns the name of the enum for `member`, registering its variants. Enums named like a node get a `Type` suffix,
+/// and enums with the same name but different variants are prefixed with the node name.
+fn enum_name(
+    metadata: &Metadata,
+    member: &Member,
+    enums: &mut BTreeMap<String, Vec<String>>,
+) -> String {
+    let mut name = camel_case(&member.display_name);
+    if METADATA_NAME_LOOKUP.contains_key(&format_lookup(&name)) {
+        name.push_str("Type");
+    }
+    if enums
+        .get(&name)
+        .is_some_and(|variants| *variants != member.enum_display_names)
+    {
+        name = format!("{}{name}", camel_case(&metadata.display_name));
+    }
+    enums.insert(name.clone(), member.enum_display_names.clone());
+    name
+}
+
+fn write_node(out: &mut String, metadata: &Metadata, enums: &mut BTreeMap<String, Vec<String>>) {
+    let name = camel_case(&metadata.display_name);
+    let fields = fields(metadata, enums);
+    let bounds: Vec<String> = fields
+        .iter()
+        .filter_map(|field| match field.member.member_type {
+            MemberType::NodeLookup => Some(format!("    {}: Generator,\n", field.ty)),
+            MemberType::Hybrid => Some(format!("    {}: Hybrid,\n", field.ty)),
+            _ => None,
+        })
+        .collect();
+    let generics: Vec<&str> = fields
+        .iter()
+        .filter(|field| {
+            matches!(
+                field.member.member_type,
+                MemberType::NodeLookup | MemberType::Hybrid
+            )
+        })
+        .map(|field| field.ty.as_str())
+        .collect();
+    let (params, where_clause) = if generics.is_empty() {
+        (String::new(), " ".to_string())
+    } else {
+        (
+            format!("<{}>", generics.join(", ")),
+            format!("\nwhere\n{}", bounds.concat()),
+        )
+    };
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "/// `{}` node, generated from the FastNoise2 metadata.",
+        metadata.display_name
+    );
+    let _ = writeln!(out, "#[derive(Clone, Debug)]");
+    if fields.is_empty() {
+        let _ = writeln!(out, "pub struct {name};");
+    } else {
+        let _ = writeln!(out, "pub struct {name}{params}{where_clause}{{");
+        for field in &fields {
+            let _ = writeln!(out, "    pub {}: {},", field.name, field.ty);
+        }
+        let _ = writeln!(out, "}}");
+    }
+
+    let bounded: Vec<String> = bounds
+        .iter()
+        .map(|bound| bound.trim().trim_end_matches(',').to_string())
+        .collect();
+    let _ = writeln!(out);
+    if bounded.is_empty() {
+        let _ = writeln!(out, "content_eq!({name});");
+    } else {
+        let _ = writeln!(out, "content_eq!({name}<{}>);", bounded.join(", "));
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "impl{params} Generator for {name}{params}{where_clause}{{"
+    );
+    let _ = writeln!(
+        out,
+        "    #[cfg_attr(feature = \"trace\", tracing::instrument(level = \"trace\"))]"
+    );
+    let _ = writeln!(
+        out,
+        "    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {{"
+    );
+    let _ = writeln!(
+        out,
+        "        {}node = NodeBuilder::new(\"{}\")?;",
+        if fields.is_empty() {
+            "let "
+        } else {
+            "let mut "
+        },
+        metadata.display_name
+    );
+    for field in &fields {
+        let value = match field.member.member_type {
+            MemberType::Float | MemberType::Int => format!("self.{}", field.name),
+            MemberType::Enum if field.ty == "bool" => format!("self.{}", field.name),
+            MemberType::Enum | MemberType::NodeLookup => format!("&self.{}", field.name),
+            MemberType::Hybrid => format!("self.{}.clone()", field.name),
+        };
+        let _ = writeln!(
+            out,
+            "        node.set(\"{}\", {value})?;",
+            field.member.set_name()
+        );
+    }
+    let _ = writeln!(out, "        Ok(node.build())");
+    let _ = writeln!(out, "    }}");
+    write_visit(out, metadata, &fields, &generics);
+    let _ = writeln!(out, "}}");
+}
+
+/// Writes the `visit` and `fold` methods walking the fields, see [`crate::GeneratorVisitor`].
+fn write_visit(out: &mut String, metadata: &Metadata, fields: &[Field], generics: &[&str]) {
+    let method = |field: &Field| match field.member.member_type {
+        MemberType::NodeLookup => "node",
+        MemberType::Hybrid => "hybrid",
+        _ => "variable",
+    };
+    // Type parameters of the methods, renamed if the struct has one with the same name.
+    let [visitor, folder] = [("V", "Vis"), ("F", "Fol")].map(|(name, other)| {
+        if generics.contains(&name) {
+            other
+        } else {
+            name
+        }
+    });
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "    fn visit<{visitor}: GeneratorVisitor + ?Sized>(&self, visitor: &mut {visitor}) {{"
+    );
+    let _ = writeln!(
+        out,
+        "        TypedVisit::new(\"{}\", visitor)",
+        metadata.display_name
+    );
+    for field in fields {
+        let _ = writeln!(
+            out,
+            "            .{}(\"{}\", &self.{})",
+            method(field),
+            field.member.set_name(),
+            field.name
+        );
+    }
+    let _ = writeln!(out, "            .leave();");
+    let _ = writeln!(out, "    }}");
+    if fields.is_empty() {
+        return;
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "    fn fold<{folder}: GeneratorFolder + ?Sized>(&self, folder: &mut {folder}) -> Result<Self, FastNoiseError> {{"
+    );
+    let _ = writeln!(
+        out,
+        "        let mut fold = TypedFold::new(\"{}\", folder);",
+        metadata.display_name
+    );
+    let _ = writeln!(out, "        Ok(Self {{");
+    for field in fields {
+        let _ = writeln!(
+            out,
+            "            {}: fold.{}(\"{}\", &self.{})?,",
+            field.name,
+            method(field),
+            field.member.set_name(),
+            field.name
+        );
+    }
+    let _ = writeln!(out, "        }})");
+    let _ = writeln!(out, "    }}");
+}
+
+fn write_enums(out: &mut String, enums: &BTreeMap<String, Vec<String>>) {
+    for (name, variants) in enums {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "#[derive(Clone, Debug, Default)]");
+        let _ = writeln!(out, "pub enum {name} {{");
+        for (i, variant) in variants.iter().enumerate() {
+            if i == 0 {
+                let _ = writeln!(out, "    #[default]");
+            }
+            let _ = writeln!(out, "    {},", variant_name(variant));
+        }
+        let _ = writeln!(out, "}}");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "impl Display for {name} {{");
+        let _ = writeln!(
+            out,
+            "    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{"
+        );
+        let _ = writeln!(out, "        match self {{");
+        for variant in variants {
+            let _ = writeln!(
+                out,
+                "            {name}::{} => f.write_str({variant:?}),",
+                variant_name(variant)
+            );
+        }
+        let _ = writeln!(out, "        }}");
+        let _ = writeln!(out, "    }}");
+        let _ = writeln!(out, "}}");
+        let _ = writeln!(out);
+        let variants: Vec<String> = variants
+            .iter()
+            .map(|variant| variant_name(variant))
+            .collect();
+        let _ = writeln!(
+            out,
+            "enum_member_value!({name} {{ {} }});",
+            variants.join(", ")
+        );
+    }
+}
+
+/// `Feature Scale` or `FeatureScale` to `feature_scale`, with the dimension suffix, e.g. `offset_x`.
+pub(crate) fn field_name(member: &Member) -> String {
+    let mut name = snake_case(&member.display_name);
+    if let Some(dimension) = member.dimension {
+        name.push('_');
+        name.push(['x', 'y', 'z', 'w'][dimension]);
+    }
+    match name.as_str() {
+        "as" | "box" | "const" | "crate" | "enum" | "fn" | "impl" | "in" | "loop" | "match"
+        | "mod" | "move" | "ref" | "self" | "static" | "struct" | "super" | "trait" | "type"
+        | "use" | "where" => format!("r#{name}"),
+        _ => name,
+    }
+}
+
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if previous.is_some_and(|p| p != '_') {
+                out.push('_');
+                previous = Some('_');
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase()
+            && previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+        {
+            out.push('_');
+        }
+        out.push(c.to_ascii_lowercase());
+        previous = Some(c);
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+pub(crate) fn camel_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().expect("words are not empty");
+            first.to_ascii_uppercase().to_string() + chars.as_str()
+        })
+        .collect()
+}
+
+pub(crate) fn variant_name(name: &str) -> String {
+    let name = camel_case(name);
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("V{name}")
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::{
+        generator::{
+            cellular::CellularDistanceReturnType, prelude::*, Dimension, DistanceFunction,
+        },
+        metadata::{MemberSlot, RecordedValue},
+        Node, SafeNode,
+    };
+
+    #[test]
+    fn test_names() {
+        assert_eq!(snake_case("Feature Scale"), "feature_scale");
+        assert_eq!(snake_case("WeightedStrength"), "weighted_strength");
+        assert_eq!(snake_case("LHS"), "lhs");
+        assert_eq!(snake_case("Distance Index 0"), "distance_index_0");
+        assert_eq!(camel_case("Euclidean Squared"), "EuclideanSquared");
+        assert_eq!(camel_case("FractalFBm"), "FractalFBm");
+        assert_eq!(variant_name("2D"), "V2D");
+    }
+
+    #[test]
+    fn test_typed_generator_source() {
+        let source = typed_generator_source("FractalFBm").unwrap();
+        assert!(source.contains("pub struct FractalFBm<S, G, W>"));
+        assert!(source.contains("    S: Generator,"));
+        assert!(source.contains("content_eq!(FractalFBm<S: Generator, G: Hybrid, W: Hybrid>);"));
+        assert!(source.contains("    G: Hybrid,"));
+        assert!(source.contains("    pub octaves: i32,"));
+        assert!(source.contains("        node.set(\"Source\", &self.source)?;"));
+        assert!(source.contains("        node.set(\"Gain\", self.gain.clone())?;"));
+        assert!(source.contains("            .hybrid(\"Gain\", &self.gain)"));
+        assert!(source.contains("            octaves: fold.variable(\"Octaves\", &self.octaves)?,"));
+
+        let source = typed_generator_source("DomainOffset").unwrap();
+        assert!(source.contains("pub struct DomainOffset<S, X, Y, Z, W>"));
+        assert!(source.contains("    pub offset_w: W,"));
+        assert!(source.contains("        node.set(\"OffsetY\", self.offset_y.clone())?;"));
+
+        assert!(matches!(
+            typed_generator_source("Unknown"),
+            Err(FastNoiseError::MetadataNameNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_shared_enums() {
+        let source = typed_generators_source();
+        assert_eq!(source.matches("pub enum DistanceFunction {").count(), 1);
+        assert!(source.contains("pub distance_function: DistanceFunction,"));
+        assert!(
+            source.contains("        node.set(\"DistanceFunction\", &self.distance_function)?;")
+        );
+        assert!(source.contains("enum_member_value!(DistanceFunction { Euclidean, "));
+        assert!(source.contains("    pub clamp_output: bool,"));
+        assert!(!source.contains("pub enum ClampOutput"));
+    }
+
+    /// One instance of every typed generator, to be extended along with [`crate::generator`]: missing generators make
+    /// `test_metadata_coverage` fail.
+    fn typed_generators() -> Vec<SafeNode> {
+        let source = || perlin();
+        vec![
+            perlin().build().0,
+            simplex().build().0,
+            supersimplex().build().0,
+            value().build().0,
+            white().build().0,
+            checkerboard(1.0).build().0,
+            sinewave(1.0).build().0,
+            gradient().build().0,
+            constant(1.0).build().0,
+            distance_to_point().build().0,
+            cellular_value(1.0, DistanceFunction::default(), 0)
+                .build()
+                .0,
+            cellular_distance(
+                1.0,
+                DistanceFunction::default(),
+                0,
+                1,
+                CellularDistanceReturnType::Index0,
+            )
+            .build()
+            .0,
+            cellular_lookup(source(), 1.0, DistanceFunction::default())
+                .build()
+                .0,
+            (source() + 1.0).build().0,
+            (source() - 1.0).build().0,
+            (source() * 1.0).build().0,
+            (source() / 1.0).build().0,
+            (source() % 1.0).build().0,
+            source().min(1.0).build().0,
+            source().max(1.0).build().0,
+            source().min_smooth(1.0, 0.1).build().0,
+            source().max_smooth(1.0, 0.1).build().0,
+            source().fade(simplex(), 0.5).build().0,
+            source().powf(2.0).build().0,
+            source().powi(2).build().0,
+            source().fbm(0.5, 0.0, 3, 2.0).build().0,
+            source().ridged(0.5, 0.0, 3, 2.0).build().0,
+            source().domain_warp_gradient(1.0, 1.0).build().0,
+            source().domain_warp_simplex(1.0, 1.0).build().0,
+            source().domain_warp_super_simplex(1.0, 1.0).build().0,
+            source()
+                .domain_warp_gradient(1.0, 1.0)
+                .domain_warp_progressive(0.5, 0.0, 3, 2.0)
+                .build()
+                .0,
+            source()
+                .domain_warp_gradient(1.0, 1.0)
+                .domain_warp_independent(0.5, 0.0, 3, 2.0)
+                .build()
+                .0,
+            source().domain_scale(2.0).build().0,
+            source().domain_offset(1.0, 1.0, 1.0, 1.0).build().0,
+            source().domain_rotate(1.0, 1.0, 1.0).build().0,
+            source().seed_offset(1).build().0,
+            source().remap(-1.0, 1.0, 0.0, 1.0).build().0,
+            source().convert_rgba8(-1.0, 1.0).build().0,
+            source().terrace(4.0, 0.1).build().0,
+            source().domain_axis_scale([1.0; 4]).build().0,
+            source().add_dimension(0.5).build().0,
+            source().remove_dimension(Dimension::Z).build().0,
+            source().cache().build().0,
+            source().ping_pong(1.0).build().0,
+            source().abs().build().0,
+            source().signed_sqrt().build().0,
+            source().domain_rotate_plane().build().0,
+        ]
+    }
+
+    fn collect_members(node: &Node, members: &mut HashMap<i32, HashSet<MemberSlot>>) {
+        let slots = members.entry(node.metadata_id).or_default();
+        slots.extend(node.members.keys().copied());
+        for value in node.members.values() {
+            if let RecordedValue::Node(child) = value {
+                collect_members(child, members);
+            }
+        }
+    }
+
+    /// Fails if a node or member of the linked FastNoise2 library has no typed equivalent.
+    #[test]
+    fn test_metadata_coverage() {
+        let mut covered = HashMap::new();
+        for node in typed_generators() {
+            collect_members(&node.0, &mut covered);
+        }
+
+        let mut missing = Vec::new();
+        for metadata in NODE_METADATA.iter() {
+            let Some(slots) = covered.get(&metadata.id) else {
+                missing.push(typed_generator_source(&metadata.display_name).unwrap());
+                continue;
+            };
+            for member in ordered_members(metadata) {
+                if !slots.contains(&member.slot()) {
+                    missing.push(format!(
+                        "member '{}' of '{}'",
+                        member.set_name(),
+                        metadata.display_name
+                    ));
+                }
+            }
+        }
+        assert!(
+            missing.is_empty(),
+            "FastNoise2 nodes or members without typed generator:\n{}",
+            missing.join("\n")
+        );
+    }
+
+    /// Sources of the typed generators, compared with the generated source by `test_committed_generators`.
+    const GENERATOR_SOURCES: [&str; 13] = [
+        include_str!("generator/basic.rs"),
+        include_str!("generator/blend.rs"),
+        include_str!("generator/cellular.rs"),
+        include_str!("generator/context.rs"),
+        include_str!("generator/dimensioned.rs"),
+        include_str!("generator/domain_warp.rs"),
+        include_str!("generator/domain_warp_fractal.rs"),
+        include_str!("generator/fractal.rs"),
+        include_str!("generator/mod.rs"),
+        include_str!("generator/modifier.rs"),
+        include_str!("generator/perlin.rs"),
+        include_str!("generator/simplex.rs"),
+        include_str!("generator/value.rs"),
+    ];
+
+    /// Returns the member calls of the `try_build`, `visit` and `fold` implementations of the node `name` in
+    /// `source`, e.g. `visit.hybrid("Gain")`, ignoring formatting. The calls of `try_build` are sorted, as members
+    /// can be set in any order. Empty if `source` doesn't implement the node.
+    fn member_calls(source: &str, name: &str) -> Vec<String> {
+        let source: String = source.split_whitespace().collect();
+        let bodies = [
+            (
+                "build",
+                format!("NodeBuilder::new(\"{name}\")?;"),
+                "Ok(node.build())",
+            ),
+            (
+                "visit",
+                format!("TypedVisit::new(\"{name}\",visitor)"),
+                ".leave();",
+            ),
+            ("fold", format!("TypedFold::new(\"{name}\",folder);"), "})"),
+        ];
+        let mut calls = Vec::new();
+        for (kind, start, end) in bodies {
+            let Some(at) = source.find(&start) else {
+                continue;
+            };
+            let body = &source[at + start.len()..];
+            let body = &body[..body.find(end).unwrap_or(body.len())];
+            for piece in body.split('.') {
+                let Some((method, rest)) = piece.split_once("(\"") else {
+                    continue;
+                };
+                if method
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                {
+                    let member = &rest[..rest.find('"').unwrap_or(rest.len())];
+                    calls.push(format!("{kind}.{method}(\"{member}\")"));
+                }
+            }
+            if kind == "build" {
+                calls.sort();
+            }
+        }
+        calls
+    }
+
+    /// Returns the variants of the enums declared by `enum_member_value!` in `source`, e.g. `{X,Y,Z,W}`. Their names
+    /// are left out, as the committed enums are named after their node or use, e.g. `FadeInterpolation`.
+    fn member_value_enums(source: &str) -> Vec<String> {
+        let source: String = source.split_whitespace().collect();
+        let source = source.replace(",}", "}");
+        source
+            .split("enum_member_value!(")
+            .skip(1)
+            .flat_map(|invocation| {
+                let invocation = &invocation[..invocation.find(");").unwrap_or(invocation.len())];
+                invocation
+                    .trim_end_matches(',')
+                    .split_inclusive("},")
+                    .map(|entry| {
+                        let entry = entry.trim_end_matches(',');
+                        entry[entry.find('{').unwrap_or(0)..].to_string()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Fails if the typed generators committed in [`crate::generator`] set, visit or fold other members than the
+    /// source generated from the metadata of the linked library, or declare other enum variants.
+    #[test]
+    fn test_committed_generators() {
+        let mut drift = Vec::new();
+        for metadata in NODE_METADATA.iter() {
+            let generated = member_calls(
+                &typed_generator_source(&metadata.display_name).unwrap(),
+                &metadata.display_name,
+            );
+            let committed: Vec<String> = GENERATOR_SOURCES
+                .iter()
+                .map(|source| member_calls(source, &metadata.display_name))
+                .find(|calls| !calls.is_empty())
+                .unwrap_or_default();
+            if committed != generated {
+                drift.push(format!(
+                    "'{}' generated as {generated:?}, committed as {committed:?}",
+                    metadata.display_name
+                ));
+            }
+        }
+
+        let committed: Vec<String> = GENERATOR_SOURCES
+            .iter()
+            .flat_map(|source| member_value_enums(source))
+            .collect();
+        for generated in member_value_enums(&typed_generators_source()) {
+            if !committed.contains(&generated) {
+                drift.push(format!("no enum with the variants {generated}"));
+            }
+        }
+        assert!(
+            drift.is_empty(),
+            "typed generators differing from the FastNoise2 metadata:\n{}",
+            drift.join("\n")
+        );
+    }
+}
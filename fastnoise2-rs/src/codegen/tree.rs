@@ -0,0 +1,914 @@
+//! Rust source building a given node tree with the typed generators.
+use std::{collections::BTreeSet, fmt::Write};
+
+use super::variant_name;
+use crate::{
+    encoded,
+    metadata::{Member, RecordedValue, NODE_METADATA},
+    FastNoiseError, Node, SafeNode,
+};
+
+const MAX_WIDTH: usize = 100;
+/// Widths past which rustfmt breaks method chains and call arguments over several lines.
+const CHAIN_WIDTH: usize = 60;
+const FN_CALL_WIDTH: usize = 60;
+const INDENT: &str = "    ";
+
+/// Returns Rust source defining a function named `function_name` that returns a
+/// [`Generator`][crate::generator::Generator] equivalent to `node`, written with the combinators of
+/// [`generator::prelude`][crate::generator::prelude], e.g. `perlin().fbm(0.5, 0.0, 3, 2.0)`.
+///
+/// The tree is read from the members recorded when it was built, so every tree built from typed generators or with
+/// [`Node::set`] can be written. Optional members equal to the defaults of the typed generators are left out, and so
+/// are those never set, which are assumed to hold these defaults.
+///
+/// Trees decoded from an encoded node tree are decoded again in Rust against the node table of the linked library,
+/// since the FastNoise2 C API has no getter for members, and written with the typed generators like the others. The
+/// members they leave out hold the FastNoise2 defaults, which are known for the members the combinators need; members
+/// set after decoding replace those of the encoded node tree.
+///
+/// Nodes used several times in the tree are written each time they are used. Their output is the same, and
+/// [`BuildContext`][crate::generator::BuildContext] shares them again when building.
+///
+/// # Errors
+/// Returns [`FastNoiseError::SourceGenerationFailed`] if a node has no typed generator, if a member needed by its
+/// combinator was never set, or if a member holds a node where the combinator only takes a float.
+///
+/// # Examples
+///
+/// ```rust
+/// use fastnoise2::{codegen::tree_source, generator::prelude::*};
+///
+/// let node = perlin().with_feature_scale(50.0).fbm(0.5, 0.0, 3, 2.0).build();
+/// let source = tree_source(&node, "terrain").unwrap();
+/// assert!(source.contains(".fbm(0.5, 0.0, 3, 2.0)"));
+/// ```
+pub fn tree_source(node: &SafeNode, function_name: &str) -> Result<String, FastNoiseError> {
+    let mut writer = TreeWriter::default();
+    let expr = writer.expr(&node.0)?;
+
+    let mut source = String::from("use fastnoise2::generator::prelude::*;\n");
+    for import in &writer.imports {
+        let _ = writeln!(source, "use fastnoise2::{import};");
+    }
+    let _ = writeln!(source);
+    let _ = writeln!(source, "pub fn {function_name}() -> impl Generator {{");
+    let _ = writeln!(source, "{INDENT}{}", expr.render(1));
+    let _ = writeln!(source, "}}");
+    Ok(source)
+}
+
+/// Expression of the generated source.
+enum Expr {
+    /// Literal, path or any other expression written as is.
+    Lit(String),
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Method {
+        receiver: Box<Expr>,
+        name: &'static str,
+        args: Vec<Expr>,
+    },
+    Binary {
+        op: &'static str,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+impl Expr {
+    fn call(name: impl Into<String>, args: Vec<Expr>) -> Self {
+        Self::Call {
+            name: name.into(),
+            args,
+        }
+    }
+
+    fn method(self, name: &'static str, args: Vec<Expr>) -> Self {
+        Self::Method {
+            receiver: Box::new(self),
+            name,
+            args,
+        }
+    }
+
+    fn binary(lhs: Expr, op: &'static str, rhs: Expr) -> Self {
+        Self::Binary {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        }
+    }
+
+    fn inline(&self) -> String {
+        match self {
+            Self::Lit(lit) => lit.clone(),
+            Self::Call { name, args } => format!("{name}({})", inline_args(args)),
+            Self::Method {
+                receiver,
+                name,
+                args,
+            } => format!("{}.{name}({})", receiver.operand(), inline_args(args)),
+            Self::Binary { op, lhs, rhs } => format!("{} {op} {}", lhs.operand(), rhs.operand()),
+        }
+    }
+
+    /// Inline form, in parentheses if it is an operation.
+    fn operand(&self) -> String {
+        match self {
+            Self::Binary { .. } => format!("({})", self.inline()),
+            _ => self.inline(),
+        }
+    }
+
+    /// Writes the expression starting at `indent` levels of indentation, breaking method chains, arguments and
+    /// operations over several lines when they don't fit.
+    fn render(&self, indent: usize) -> String {
+        let inline = self.inline();
+        let width = match self {
+            Self::Method { .. } => CHAIN_WIDTH,
+            Self::Call { .. } => FN_CALL_WIDTH,
+            _ => MAX_WIDTH,
+        };
+        if inline.len() <= width && indent * INDENT.len() + inline.len() <= MAX_WIDTH {
+            return inline;
+        }
+        match self {
+            Self::Lit(lit) => lit.clone(),
+            Self::Call { name, args } => format!("{name}({})", render_args(args, indent)),
+            Self::Method { .. } => {
+                let mut calls = Vec::new();
+                let mut expr = self;
+                while let Self::Method {
+                    receiver,
+                    name,
+                    args,
+                } = expr
+                {
+                    calls.push((name, args));
+                    expr = receiver;
+                }
+                let mut out = match expr {
+                    Self::Binary { .. } => format!("({})", expr.render(indent + 1)),
+                    _ => expr.render(indent),
+                };
+                let line = INDENT.repeat(indent + 1);
+                for (name, args) in calls.into_iter().rev() {
+                    let _ = write!(out, "\n{line}.{name}({})", render_args(args, indent + 1));
+                }
+                out
+            }
+            Self::Binary { op, lhs, rhs } => {
+                let render = |expr: &Expr| match expr {
+                    Self::Binary { .. } => format!("({})", expr.render(indent + 1)),
+                    _ => expr.render(indent + 1),
+                };
+                format!(
+                    "{}\n{}{op} {}",
+                    render(lhs),
+                    INDENT.repeat(indent + 1),
+                    render(rhs)
+                )
+            }
+        }
+    }
+}
+
+fn inline_args(args: &[Expr]) -> String {
+    args.iter().map(Expr::inline).collect::<Vec<_>>().join(", ")
+}
+
+/// Arguments of a call at `indent`, one per line if any of them spans several lines, except for a single call which
+/// is continued on the same line.
+fn render_args(args: &[Expr], indent: usize) -> String {
+    if let [arg @ Expr::Call { .. }] = args {
+        return arg.render(indent);
+    }
+    let inline = inline_args(args);
+    if args.iter().all(|arg| !arg.render(indent).contains('\n'))
+        && inline.len() <= FN_CALL_WIDTH
+        && indent * INDENT.len() + inline.len() <= MAX_WIDTH
+    {
+        return inline;
+    }
+    let line = INDENT.repeat(indent + 1);
+    let mut out = String::from("\n");
+    for arg in args {
+        let _ = writeln!(out, "{line}{},", arg.render(indent + 1));
+    }
+    out + &INDENT.repeat(indent)
+}
+
+fn float_literal(value: f32) -> Expr {
+    Expr::Lit(if value.is_nan() {
+        "f32::NAN".to_string()
+    } else if value == f32::INFINITY {
+        "f32::INFINITY".to_string()
+    } else if value == f32::NEG_INFINITY {
+        "f32::NEG_INFINITY".to_string()
+    } else {
+        format!("{value:?}")
+    })
+}
+
+/// Typed enums of enum members, by member name.
+const ENUMS: &[(&str, &str)] = &[
+    ("Distance Function", "generator::DistanceFunction"),
+    ("Interpolation", "generator::FadeInterpolation"),
+    ("Remove Dimension", "generator::Dimension"),
+    (
+        "Return Type",
+        "generator::cellular::CellularDistanceReturnType",
+    ),
+    (
+        "Vectorization Scheme",
+        "generator::domain_warp::VectorizationScheme",
+    ),
+    ("Rotation Type", "generator::modifier::PlaneRotationType"),
+];
+
+/// Writes the expression of each node of a tree, collecting the imports it needs.
+#[derive(Default)]
+struct TreeWriter {
+    /// Paths relative to the crate root, in addition to the prelude.
+    imports: BTreeSet<&'static str>,
+}
+
+impl TreeWriter {
+    fn expr(&mut self, node: &Node) -> Result<Expr, FastNoiseError> {
+        let metadata = &NODE_METADATA[node.metadata_id as usize];
+        let name = metadata.display_name.as_str();
+
+        if let Some(encoded) = &node.encoded {
+            let decoded = encoded::decode(encoded).map_err(|error| {
+                unsupported(
+                    name,
+                    &format!("its encoded node tree can't be decoded: {error}"),
+                )
+            })?;
+            let mut decoded = encoded::decoded_tree(&decoded)?;
+            // Members set after decoding replace those of the encoded node tree.
+            for (&slot, value) in &node.members {
+                decoded.apply_recorded(slot, value.clone())?;
+            }
+            return self.expr(&decoded);
+        }
+
+        let expr = match name {
+            "Constant" => Expr::call("constant", vec![self.float(node, "Value")?]),
+            "White" => {
+                let expr = Expr::call("white", vec![]);
+                let expr = self.with_int(node, expr, "with_seed_offset", "SeedOffset")?;
+                self.with_output_range(node, expr)?
+            }
+            "Checkerboard" | "SineWave" => {
+                let function = if name == "Checkerboard" {
+                    "checkerboard"
+                } else {
+                    "sinewave"
+                };
+                let expr = Expr::call(function, vec![self.float(node, "FeatureScale")?]);
+                self.with_output_range(node, expr)?
+            }
+            "Perlin" | "Simplex" | "SuperSimplex" | "Value" => {
+                let function = match name {
+                    "Perlin" => "perlin",
+                    "Simplex" => "simplex",
+                    "SuperSimplex" => "supersimplex",
+                    _ => "value",
+                };
+                let mut expr = Expr::call(function, vec![]);
+                if !node.is_default("FeatureScale")? {
+                    expr = expr.method(
+                        "with_feature_scale",
+                        vec![self.float(node, "FeatureScale")?],
+                    );
+                }
+                let expr = self.with_int(node, expr, "with_seed_offset", "SeedOffset")?;
+                self.with_output_range(node, expr)?
+            }
+            "Gradient" => {
+                let mut expr = Expr::call("gradient", vec![]);
+                if !node.is_default("Multiplier")? {
+                    expr = expr.method("with_multipliers", vec![self.floats(node, "Multiplier")?]);
+                }
+                if !node.is_default("Offset")? {
+                    expr = expr.method("with_offsets", vec![self.floats(node, "Offset")?]);
+                }
+                expr
+            }
+            "DistanceToPoint" => {
+                let mut expr = Expr::call("distance_to_point", vec![]);
+                if !node.is_default("DistanceFunction")? {
+                    let distance_function = self.enum_value(node, "DistanceFunction")?;
+                    expr = expr.method("with_distance_function", vec![distance_function]);
+                }
+                if !node.is_default("Point")? {
+                    expr = expr.method("with_point", vec![self.floats(node, "Point")?]);
+                }
+                if !node.is_default("MinkowskiP")? {
+                    expr = expr.method("with_minkowski_p", vec![self.hybrid(node, "MinkowskiP")?]);
+                }
+                expr
+            }
+            "CellularValue" | "CellularDistance" | "CellularLookup" => {
+                let mut args = Vec::new();
+                if name == "CellularLookup" {
+                    args.push(self.generator(node, "Lookup")?);
+                }
+                args.push(self.hybrid(node, "GridJitter")?);
+                args.push(self.enum_value(node, "DistanceFunction")?);
+                match name {
+                    "CellularValue" => args.push(self.int(node, "ValueIndex")?),
+                    "CellularDistance" => {
+                        args.push(self.int(node, "DistanceIndex0")?);
+                        args.push(self.int(node, "DistanceIndex1")?);
+                        args.push(self.enum_value(node, "ReturnType")?);
+                    }
+                    _ => {}
+                }
+                let function = match name {
+                    "CellularValue" => "cellular_value",
+                    "CellularDistance" => "cellular_distance",
+                    _ => "cellular_lookup",
+                };
+                // The short form uses the FastNoise2 defaults for the last members.
+                if node.is_default("MinkowskiP")? && node.is_default("SizeJitter")? {
+                    Expr::call(function, args)
+                } else {
+                    args.push(self.hybrid(node, "MinkowskiP")?);
+                    args.push(self.hybrid(node, "SizeJitter")?);
+                    self.imports.insert(match name {
+                        "CellularValue" => "generator::cellular::cellular_value_full",
+                        "CellularDistance" => "generator::cellular::cellular_distance_full",
+                        _ => "generator::cellular::cellular_lookup_full",
+                    });
+                    Expr::call(format!("{function}_full"), args)
+                }
+            }
+            "Add" | "Multiply" => {
+                let op = if name == "Add" { "+" } else { "*" };
+                Expr::binary(self.generator(node, "LHS")?, op, self.hybrid(node, "RHS")?)
+            }
+            "Subtract" | "Divide" | "Modulus" => {
+                let op = match name {
+                    "Subtract" => "-",
+                    "Divide" => "/",
+                    _ => "%",
+                };
+                Expr::binary(
+                    self.hybrid_receiver(node, "LHS")?,
+                    op,
+                    self.hybrid(node, "RHS")?,
+                )
+            }
+            "Min" | "Max" => {
+                let method = if name == "Min" { "min" } else { "max" };
+                self.generator(node, "LHS")?
+                    .method(method, vec![self.hybrid(node, "RHS")?])
+            }
+            "MinSmooth" | "MaxSmooth" => {
+                let method = if name == "MinSmooth" {
+                    "min_smooth"
+                } else {
+                    "max_smooth"
+                };
+                let args = vec![self.hybrid(node, "RHS")?, self.hybrid(node, "Smoothness")?];
+                self.generator(node, "LHS")?.method(method, args)
+            }
+            "Fade" => {
+                let a = self.generator(node, "A")?;
+                let mut args = vec![self.generator(node, "B")?, self.hybrid(node, "Fade")?];
+                if node.is_default("FadeMin")?
+                    && node.is_default("FadeMax")?
+                    && node.is_default("Interpolation")?
+                {
+                    a.method("fade", args)
+                } else {
+                    args.push(self.hybrid(node, "FadeMin")?);
+                    args.push(self.hybrid(node, "FadeMax")?);
+                    args.push(self.enum_value(node, "Interpolation")?);
+                    a.method("fade_with_range", args)
+                }
+            }
+            "PowFloat" => self
+                .hybrid_receiver(node, "Value")?
+                .method("powf", vec![self.hybrid(node, "Pow")?]),
+            "PowInt" => self
+                .generator(node, "Value")?
+                .method("powi", vec![self.int(node, "Pow")?]),
+            "FractalFBm" | "FractalRidged" => {
+                let method = if name == "FractalFBm" {
+                    "fbm"
+                } else {
+                    "ridged"
+                };
+                let args = self.fractal_args(node)?;
+                self.generator(node, "Source")?.method(method, args)
+            }
+            "DomainWarpFractalProgressive" | "DomainWarpFractalIndependent" => {
+                let method = if name == "DomainWarpFractalProgressive" {
+                    "domain_warp_progressive"
+                } else {
+                    "domain_warp_independent"
+                };
+                let source = self.domain_warp_source(node)?;
+                source.method(method, self.fractal_args(node)?)
+            }
+            "DomainWarpGradient" => {
+                let args = vec![
+                    self.hybrid(node, "WarpAmplitude")?,
+                    self.float(node, "FeatureScale")?,
+                ];
+                self.generator(node, "Source")?
+                    .method("domain_warp_gradient", args)
+            }
+            "DomainWarpSimplex" | "DomainWarpSuperSimplex" => {
+                let mut args = vec![
+                    self.hybrid(node, "WarpAmplitude")?,
+                    self.float(node, "FeatureScale")?,
+                ];
+                let default_scheme = node.is_default("VectorizationScheme")?;
+                let method = match (name, default_scheme) {
+                    ("DomainWarpSimplex", true) => "domain_warp_simplex",
+                    ("DomainWarpSimplex", false) => "domain_warp_simplex_with_scheme",
+                    (_, true) => "domain_warp_super_simplex",
+                    (_, false) => "domain_warp_super_simplex_with_scheme",
+                };
+                if !default_scheme {
+                    args.push(self.enum_value(node, "VectorizationScheme")?);
+                }
+                self.generator(node, "Source")?.method(method, args)
+            }
+            "DomainScale" => self.source_method(node, "domain_scale", |w| {
+                Ok(vec![w.float(node, "Scaling")?])
+            })?,
+            "DomainOffset" => self.source_method(node, "domain_offset", |w| {
+                ["OffsetX", "OffsetY", "OffsetZ", "OffsetW"]
+                    .into_iter()
+                    .map(|member| w.hybrid(node, member))
+                    .collect()
+            })?,
+            "DomainRotate" => self.source_method(node, "domain_rotate", |w| {
+                Ok(vec![
+                    w.float(node, "Yaw")?,
+                    w.float(node, "Pitch")?,
+                    w.float(node, "Roll")?,
+                ])
+            })?,
+            "DomainAxisScale" => self.source_method(node, "domain_axis_scale", |w| {
+                Ok(vec![w.floats(node, "Scaling")?])
+            })?,
+            "DomainRotatePlane" => {
+                if node.is_default("RotationType")? {
+                    self.source_method(node, "domain_rotate_plane", |_| Ok(vec![]))?
+                } else {
+                    self.source_method(node, "domain_rotate_plane_with_type", |w| {
+                        Ok(vec![w.enum_value(node, "RotationType")?])
+                    })?
+                }
+            }
+            "SeedOffset" => self.source_method(node, "seed_offset", |w| {
+                Ok(vec![w.int(node, "SeedOffset")?])
+            })?,
+            "Remap" => {
+                let clamped = !node.is_default("ClampOutput")?;
+                let method = if clamped { "remap_clamped" } else { "remap" };
+                self.source_method(node, method, |w| {
+                    let mut args = vec![
+                        w.float(node, "FromMin")?,
+                        w.float(node, "FromMax")?,
+                        w.float(node, "ToMin")?,
+                        w.float(node, "ToMax")?,
+                    ];
+                    if clamped {
+                        args.push(w.enum_value(node, "ClampOutput")?);
+                    }
+                    Ok(args)
+                })?
+            }
+            "ConvertRgba8" => self.source_method(node, "convert_rgba8", |w| {
+                Ok(vec![w.float(node, "Min")?, w.float(node, "Max")?])
+            })?,
+            "Terrace" => self.source_method(node, "terrace", |w| {
+                Ok(vec![
+                    w.float(node, "StepCount")?,
+                    w.hybrid(node, "Smoothness")?,
+                ])
+            })?,
+            "AddDimension" => self.source_method(node, "add_dimension", |w| {
+                Ok(vec![w.hybrid(node, "NewDimensionPosition")?])
+            })?,
+            "RemoveDimension" => self.source_method(node, "remove_dimension", |w| {
+                Ok(vec![w.enum_value(node, "RemoveDimension")?])
+            })?,
+            "PingPong" => self.source_method(node, "ping_pong", |w| {
+                Ok(vec![w.hybrid(node, "PingPongStrength")?])
+            })?,
+            "GeneratorCache" => self.source_method(node, "cache", |_| Ok(vec![]))?,
+            "Abs" => self.source_method(node, "abs", |_| Ok(vec![]))?,
+            "SignedSquareRoot" => self.source_method(node, "signed_sqrt", |_| Ok(vec![]))?,
+            _ => return Err(unsupported(name, "the node has no typed generator")),
+        };
+        Ok(expr)
+    }
+
+    /// Method called on the `Source` member.
+    fn source_method(
+        &mut self,
+        node: &Node,
+        method: &'static str,
+        args: impl FnOnce(&mut Self) -> Result<Vec<Expr>, FastNoiseError>,
+    ) -> Result<Expr, FastNoiseError> {
+        let source = self.generator(node, "Source")?;
+        Ok(source.method(method, args(self)?))
+    }
+
+    fn fractal_args(&mut self, node: &Node) -> Result<Vec<Expr>, FastNoiseError> {
+        Ok(vec![
+            self.hybrid(node, "Gain")?,
+            self.hybrid(node, "WeightedStrength")?,
+            self.int(node, "Octaves")?,
+            self.float(node, "Lacunarity")?,
+        ])
+    }
+
+    /// Source of a domain warp fractal, which must be a typed domain warp.
+    fn domain_warp_source(&mut self, node: &Node) -> Result<Expr, FastNoiseError> {
+        let member = node.member("DomainWarpSource")?;
+        let child = node.child(member).ok_or_else(|| not_set(node, member))?;
+        let child_name = &NODE_METADATA[child.metadata_id as usize].display_name;
+        if !child_name.starts_with("DomainWarp") {
+            return Err(unsupported(
+                &NODE_METADATA[node.metadata_id as usize].display_name,
+                "the domain warp source isn't a typed domain warp node",
+            ));
+        }
+        self.expr(child)
+    }
+
+    fn with_int(
+        &mut self,
+        node: &Node,
+        expr: Expr,
+        method: &'static str,
+        member: &str,
+    ) -> Result<Expr, FastNoiseError> {
+        Ok(if !node.is_default(member)? {
+            expr.method(method, vec![self.int(node, member)?])
+        } else {
+            expr
+        })
+    }
+
+    fn with_output_range(&mut self, node: &Node, expr: Expr) -> Result<Expr, FastNoiseError> {
+        Ok(
+            if !node.is_default("OutputMin")? || !node.is_default("OutputMax")? {
+                let args = vec![
+                    self.float(node, "OutputMin")?,
+                    self.float(node, "OutputMax")?,
+                ];
+                expr.method("with_output_range", args)
+            } else {
+                expr
+            },
+        )
+    }
+
+    fn generator(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let member = node.member(member_name)?;
+        let child = node.child(member).ok_or_else(|| not_set(node, member))?;
+        self.expr(child)
+    }
+
+    fn hybrid(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let member = node.member(member_name)?;
+        match node.members.get(&member.slot()) {
+            Some(RecordedValue::Float(value)) => Ok(float_literal(*value)),
+            Some(RecordedValue::Node(child)) => self.expr(child),
+            _ => Err(not_set(node, member)),
+        }
+    }
+
+    /// Hybrid member used as the receiver of an operator or a method, which must be a
+    /// [`GeneratorWrapper`][crate::generator::GeneratorWrapper].
+    fn hybrid_receiver(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let expr = self.hybrid(node, member_name)?;
+        Ok(match expr {
+            Expr::Lit(_) => Expr::call("GeneratorWrapper::from", vec![expr]),
+            expr => expr,
+        })
+    }
+
+    fn float(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let member = node.member(member_name)?;
+        match node.members.get(&member.slot()) {
+            Some(RecordedValue::Float(value)) => Ok(float_literal(*value)),
+            Some(RecordedValue::Node(_)) => Err(unsupported(
+                &NODE_METADATA[node.metadata_id as usize].display_name,
+                &format!(
+                    "'{}' is set to a node, but its combinator only takes a float",
+                    member.set_name()
+                ),
+            )),
+            _ => Err(not_set(node, member)),
+        }
+    }
+
+    /// Per-dimension float members as an array, e.g. `[1.0, 2.0, 1.0, 1.0]` for `Scaling`.
+    fn floats(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let values = ['X', 'Y', 'Z', 'W']
+            .into_iter()
+            .map(|dimension| {
+                Ok(self
+                    .float(node, &format!("{member_name}{dimension}"))?
+                    .inline())
+            })
+            .collect::<Result<Vec<_>, FastNoiseError>>()?;
+        Ok(Expr::Lit(format!("[{}]", values.join(", "))))
+    }
+
+    fn int(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let member = node.member(member_name)?;
+        match node.members.get(&member.slot()) {
+            Some(RecordedValue::Int(value)) => Ok(Expr::Lit(value.to_string())),
+            _ => Err(not_set(node, member)),
+        }
+    }
+
+    /// Enum member as a variant of its typed enum, or as a `bool` for `False`/`True` enums.
+    fn enum_value(&mut self, node: &Node, member_name: &str) -> Result<Expr, FastNoiseError> {
+        let member = node.member(member_name)?;
+        let Some(RecordedValue::Int(index)) = node.members.get(&member.slot()) else {
+            return Err(not_set(node, member));
+        };
+        let value = usize::try_from(*index)
+            .ok()
+            .and_then(|index| member.enum_display_names.get(index))
+            .ok_or_else(|| not_set(node, member))?;
+        if member.enum_display_names == ["False", "True"] {
+            return Ok(Expr::Lit((value == "True").to_string()));
+        }
+        let (_, path) = ENUMS
+            .iter()
+            .find(|(name, _)| *name == member.display_name)
+            .ok_or_else(|| {
+                unsupported(
+                    &NODE_METADATA[node.metadata_id as usize].display_name,
+                    &format!("'{}' has no typed enum", member.set_name()),
+                )
+            })?;
+        self.imports.insert(path);
+        let ty = path.rsplit("::").next().expect("paths are not empty");
+        Ok(Expr::Lit(format!("{ty}::{}", variant_name(value))))
+    }
+}
+
+impl Node {
+    /// Returns whether `member_name`, or every member of the per-dimension group `member_name`, holds the default of
+    /// the typed generators, see [`Member::is_default`].
+    fn is_default(&self, member_name: &str) -> Result<bool, FastNoiseError> {
+        let members = match self.dimension_members(member_name) {
+            Some(members) => members,
+            None => vec![self.member(member_name)?],
+        };
+        Ok(members
+            .into_iter()
+            .all(|member| member.is_default(self.members.get(&member.slot()))))
+    }
+}
+
+fn unsupported(node: &str, reason: &str) -> FastNoiseError {
+    FastNoiseError::SourceGenerationFailed {
+        node: node.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn not_set(node: &Node, member: &Member) -> FastNoiseError {
+    unsupported(
+        &NODE_METADATA[node.metadata_id as usize].display_name,
+        &format!("'{}' was never set", member.set_name()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{
+        cellular::{cellular_distance_full, CellularDistanceReturnType},
+        modifier::PlaneRotationType,
+        prelude::*,
+        Dimension, DistanceFunction,
+    };
+
+    /// Output of both trees on a small grid.
+    fn assert_same_output(a: &SafeNode, b: &SafeNode) {
+        let mut a_out = vec![0.0; 64];
+        let mut b_out = vec![0.0; 64];
+        a.gen_uniform_grid_2d(&mut a_out, 0.0, 0.0, 8, 8, 0.1, 0.1, 1337);
+        b.gen_uniform_grid_2d(&mut b_out, 0.0, 0.0, 8, 8, 0.1, 0.1, 1337);
+        assert_eq!(a_out, b_out);
+    }
+
+    #[test]
+    fn test_chain_source() {
+        let node = perlin()
+            .with_feature_scale(50.0)
+            .fbm(0.5, 0.0, 3, 2.0)
+            .domain_warp_gradient(0.2, 100.0)
+            .build();
+        assert_eq!(
+            tree_source(&node, "terrain").unwrap(),
+            "\
+use fastnoise2::generator::prelude::*;
+
+pub fn terrain() -> impl Generator {
+    perlin()
+        .with_feature_scale(50.0)
+        .fbm(0.5, 0.0, 3, 2.0)
+        .domain_warp_gradient(0.2, 100.0)
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_operators_and_enums_source() {
+        let node = ((simplex() - 0.5) * constant(2.0))
+            .min(cellular_distance_full(
+                1.0,
+                DistanceFunction::Euclidean,
+                0,
+                1,
+                CellularDistanceReturnType::Index0Sub1,
+                2.0,
+                0.0,
+            ))
+            .remove_dimension(Dimension::Z)
+            .remap_clamped(-1.0, 1.0, 0.0, 1.0, true)
+            .build();
+        let source = tree_source(&node, "generator").unwrap();
+        assert!(source.contains("use fastnoise2::generator::DistanceFunction;"));
+        assert!(source.contains("use fastnoise2::generator::cellular::cellular_distance_full;"));
+        assert!(source.contains("((simplex()"));
+        assert!(source.contains(" - 0.5) * constant(2.0))"));
+        assert!(source.contains(".min(cellular_distance_full(\n"));
+        assert!(source.contains("            DistanceFunction::Euclidean,\n"));
+        assert!(source.contains("        ))\n"));
+        assert!(source.contains("CellularDistanceReturnType::Index0Sub1,"));
+        assert!(source.contains(".remove_dimension(Dimension::Z)"));
+        assert!(source.contains(".remap_clamped(-1.0, 1.0, 0.0, 1.0, true)"));
+        assert!(source
+            .lines()
+            .all(|line| line.len() <= MAX_WIDTH && !line.ends_with(' ')));
+    }
+
+    fn node(name: &str, members: impl FnOnce(&mut Node)) -> SafeNode {
+        let mut node = Node::from_name(name).unwrap();
+        members(&mut node);
+        SafeNode(node.into())
+    }
+
+    /// Writes the source of a tree set member by member, and compares the output of that source with the tree.
+    #[test]
+    fn test_generated_code_output() {
+        // Every member is set, values equal to the defaults of the typed generators are left out of the source.
+        let output_range = |n: &mut Node| {
+            n.set("OutputMin", -1.0).unwrap();
+            n.set("OutputMax", 1.0).unwrap();
+        };
+        let source = node("Perlin", |n| {
+            n.set("FeatureScale", 100.0).unwrap();
+            n.set("SeedOffset", 3).unwrap();
+            output_range(n);
+        });
+        let fbm = node("FractalFBm", |n| {
+            n.set("Source", source).unwrap();
+            n.set("Gain", 0.6).unwrap();
+            n.set("WeightedStrength", 0.1).unwrap();
+            n.set("Octaves", 4).unwrap();
+            n.set("Lacunarity", 2.5).unwrap();
+        });
+        let inverted = node("Subtract", |n| {
+            n.set("LHS", 1.0).unwrap();
+            let white = node("White", |n| {
+                n.set("SeedOffset", 0).unwrap();
+                output_range(n);
+            });
+            n.set("RHS", white).unwrap();
+        });
+        let fade = node("Fade", |n| {
+            n.set("A", fbm).unwrap();
+            n.set("B", inverted).unwrap();
+            n.set("Fade", 0.3).unwrap();
+            n.set("FadeMin", -1.0).unwrap();
+            n.set("FadeMax", 1.0).unwrap();
+            n.set("Interpolation", "Linear").unwrap();
+        });
+        let tree = node("DomainRotatePlane", |n| {
+            n.set("Source", fade).unwrap();
+            n.set("RotationType", "Improve XZ Planes").unwrap();
+        });
+
+        assert_eq!(
+            tree_source(&tree, "generator").unwrap(),
+            "\
+use fastnoise2::generator::prelude::*;
+use fastnoise2::generator::modifier::PlaneRotationType;
+
+pub fn generator() -> impl Generator {
+    perlin()
+        .with_seed_offset(3)
+        .fbm(0.6, 0.1, 4, 2.5)
+        .fade(GeneratorWrapper::from(1.0) - white(), 0.3)
+        .domain_rotate_plane_with_type(PlaneRotationType::ImproveXZPlanes)
+}
+"
+        );
+
+        // The generated function, as written above.
+        let generated = perlin()
+            .with_seed_offset(3)
+            .fbm(0.6, 0.1, 4, 2.5)
+            .fade(GeneratorWrapper::from(1.0) - white(), 0.3)
+            .domain_rotate_plane_with_type(PlaneRotationType::ImproveXZPlanes)
+            .build();
+        assert_same_output(&tree, &generated);
+    }
+
+    /// Tree whose encoded node tree leaves out `ScalingX`, `ScalingZ` and `ScalingW`, at the FastNoise2 default.
+    fn scaled_fbm() -> SafeNode {
+        node("DomainAxisScale", |n| {
+            n.set("Source", perlin().fbm(0.6, 0.1, 4, 2.5).build().0)
+                .unwrap();
+            n.set("ScalingY", 2.0).unwrap();
+        })
+    }
+
+    #[test]
+    fn test_encoded_subtree() {
+        // Decoded in Rust only, as if FastNoise2 had decoded it.
+        let mut decoded = Node::from_name("DomainAxisScale").unwrap();
+        decoded.encoded = Some(encoded::encode_tree(&scaled_fbm().0).into());
+        let tree = (GeneratorWrapper(SafeNode(decoded.into())) + 1.0).build();
+        assert_eq!(
+            tree_source(&tree, "generator").unwrap(),
+            "\
+use fastnoise2::generator::prelude::*;
+
+pub fn generator() -> impl Generator {
+    perlin().fbm(0.6, 0.1, 4, 2.5).domain_axis_scale([1.0, 2.0, 1.0, 1.0]) + 1.0
+}
+"
+        );
+
+        let mut decoded = Node::from_name("DomainAxisScale").unwrap();
+        decoded.encoded = Some(encoded::encode_tree(&scaled_fbm().0).into());
+        decoded.set("ScalingW", 3.0).unwrap();
+        let source = tree_source(&SafeNode(decoded.into()), "generator").unwrap();
+        assert!(source.contains(".domain_axis_scale([1.0, 2.0, 1.0, 3.0])"));
+
+        let mut decoded = Node::from_name("DomainAxisScale").unwrap();
+        decoded.encoded = Some("/wQ=".into());
+        let error = tree_source(&SafeNode(decoded.into()), "generator").unwrap_err();
+        assert!(error.to_string().contains("unknown node id 255"), "{error}");
+    }
+
+    /// Writes the source of a tree decoded by FastNoise2, and compares the output of that source with the tree.
+    #[test]
+    fn test_encoded_tree_output() {
+        let encoded = encoded::encode_tree(&scaled_fbm().0);
+        let decoded = SafeNode::from_encoded_node_tree(&encoded).unwrap();
+        let source = tree_source(&decoded, "generator").unwrap();
+        assert!(source.contains("perlin()\n        .fbm(0.6, 0.1, 4, 2.5)\n"));
+        assert!(source.contains(".domain_axis_scale([1.0, 2.0, 1.0, 1.0])"));
+
+        // The generated function, as written above.
+        let generated = perlin()
+            .fbm(0.6, 0.1, 4, 2.5)
+            .domain_axis_scale([1.0, 2.0, 1.0, 1.0])
+            .build();
+        assert_same_output(&decoded, &generated);
+    }
+
+    #[test]
+    fn test_unsupported_trees() {
+        let mut node = Node::from_name("FractalFBm").unwrap();
+        node.set("Source", perlin()).unwrap();
+        let error = tree_source(&SafeNode(node.into()), "generator").unwrap_err();
+        assert!(
+            error.to_string().contains("'Gain' was never set"),
+            "{error}"
+        );
+        assert_eq!(error.node_type(), Some("FractalFBm"));
+
+        let node = perlin().domain_scale(2.0).build();
+        let mut scale = Node::from_name("DomainScale").unwrap();
+        scale.set("Source", node).unwrap();
+        scale.set("Scaling", 2.0).unwrap();
+        assert!(tree_source(&SafeNode(scale.into()), "generator").is_ok());
+    }
+}
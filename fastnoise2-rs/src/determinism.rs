@@ -0,0 +1,132 @@
+//! Checks that a node tree produces the same output everywhere.
+//!
+//! FastNoise2 dispatches every node to an implementation specialised for the SIMD instructions available on the
+//! running CPU. The same seed must give the same world on every machine, so outputs of all SIMD levels are expected
+//! to match, up to [`DETERMINISM_TOLERANCE`] to account for fused multiply-add and approximated instructions
+//! (e.g. reciprocal square roots) that only exist on some levels.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use fastnoise2::{determinism::assert_deterministic, generator::prelude::*};
+//!
+//! assert_deterministic(&perlin().fbm(0.5, 0.0, 4, 2.0));
+//! ```
+use crate::{generator::Generator, Node, SafeNode};
+
+/// Maximum absolute difference allowed between two outputs of the same tree.
+///
+/// Applies between SIMD levels and against reference data. Outputs of a tree at a given SIMD level must be
+/// bit-identical between runs.
+pub const DETERMINISM_TOLERANCE: f32 = 1e-4;
+
+/// Seed used by [`reference_samples`].
+pub const REFERENCE_SEED: i32 = 1337;
+
+/// Returns every SIMD level the linked FastNoise2 library can run on this CPU, lowest first.
+///
+/// FastSIMD feature set identifiers depend on the library version, so they are discovered by requesting
+/// single-bit and cumulative masks and keeping the levels FastNoise2 actually picked.
+pub fn available_simd_levels() -> Vec<u32> {
+    let mut levels: Vec<u32> = (0..u32::BITS)
+        .flat_map(|bit| [1 << bit, u32::MAX >> (u32::BITS - 1 - bit)])
+        .filter_map(|requested| Node::from_name_with_simd_level("Constant", requested).ok())
+        .map(|node| node.get_simd_level())
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+    levels
+}
+
+/// Samples `node` over a fixed 16x16 2D grid followed by a fixed 8x8x8 3D grid.
+///
+/// These are the values compared by [`assert_deterministic`] and stored as reference data.
+pub fn reference_samples(node: &SafeNode) -> Vec<f32> {
+    let mut samples = vec![0.0; 16 * 16 + 8 * 8 * 8];
+    let (grid_2d, grid_3d) = samples.split_at_mut(16 * 16);
+    node.gen_uniform_grid_2d(grid_2d, -64.0, -64.0, 16, 16, 8.0, 8.0, REFERENCE_SEED);
+    node.gen_uniform_grid_3d(
+        grid_3d,
+        -64.0,
+        -64.0,
+        -64.0,
+        8,
+        8,
+        8,
+        16.0,
+        16.0,
+        16.0,
+        REFERENCE_SEED,
+    );
+    samples
+}
+
+/// Asserts that `generator` outputs the same values at every available SIMD level.
+///
+/// Also asserts that building the tree twice gives bit-identical outputs, i.e. that nothing in the tree depends on
+/// state outside of its parameters and the seed.
+///
+/// # Panics
+/// Panics with the SIMD level and sample index of the first mismatch.
+pub fn assert_deterministic<G: Generator>(generator: &G) {
+    let reference_node = generator.build();
+    let reference = reference_samples(&reference_node);
+    let rebuilt = reference_samples(&generator.build());
+    assert!(
+        reference
+            .iter()
+            .zip(&rebuilt)
+            .all(|(a, b)| a.to_bits() == b.to_bits()),
+        "building the same tree twice gave different outputs"
+    );
+
+    for simd_level in available_simd_levels() {
+        let node = generator.build_with_simd_level(simd_level);
+        let samples = reference_samples(&node);
+        if let Some(index) = first_mismatch(&reference, &samples) {
+            panic!(
+                "output at SIMD level {} differs from SIMD level {} at sample {index}: {} != {}",
+                node.get_simd_level(),
+                reference_node.get_simd_level(),
+                samples[index],
+                reference[index],
+            );
+        }
+    }
+}
+
+/// Returns the index of the first pair of values further apart than [`DETERMINISM_TOLERANCE`].
+///
+/// Two NaNs are considered equal, so trees that legitimately produce NaN stay comparable.
+pub fn first_mismatch(expected: &[f32], actual: &[f32]) -> Option<usize> {
+    assert_eq!(expected.len(), actual.len(), "sample counts differ");
+    expected.iter().zip(actual).position(|(&e, &a)| {
+        !(e == a || (e - a).abs() <= DETERMINISM_TOLERANCE || (e.is_nan() && a.is_nan()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::perlin::perlin;
+
+    #[test]
+    fn test_available_simd_levels() {
+        let levels = available_simd_levels();
+        assert!(!levels.is_empty());
+        assert!(levels.contains(&perlin().build().get_simd_level()));
+    }
+
+    #[test]
+    fn test_assert_deterministic() {
+        assert_deterministic(&perlin().fbm(0.5, 0.0, 4, 2.0));
+    }
+
+    #[test]
+    fn test_first_mismatch() {
+        assert_eq!(first_mismatch(&[0.0, 1.0], &[0.0, 1.0]), None);
+        assert_eq!(first_mismatch(&[f32::NAN], &[f32::NAN]), None);
+        assert_eq!(first_mismatch(&[0.0, 1.0], &[0.0, 1.1]), Some(1));
+        assert_eq!(first_mismatch(&[f32::INFINITY], &[f32::INFINITY]), None);
+    }
+}
@@ -0,0 +1,198 @@
+//! Encoded node trees read in Rust against the node table of the linked library, see [`fastnoise2_sys::encoded`].
+use std::sync::LazyLock;
+
+use fastnoise2_sys::encoded::{self, EncodedNode, HybridValue, NodeLayout, VariableValue};
+
+use crate::{
+    metadata::{format_lookup, DefaultValue, Member, MemberSlot, RecordedValue, NODE_METADATA},
+    FastNoiseError, Node,
+};
+
+/// Layouts of the nodes of the linked library, indexed by metadata id.
+pub(crate) static NODE_LAYOUTS: LazyLock<Vec<NodeLayout>> = LazyLock::new(encoded::library_layouts);
+
+/// Defaults of FastNoise2 for members that are required by the typed generators, by node and member name formatted for
+/// lookups, without the dimension suffix. Encoded node trees leave out the members at these defaults.
+const LIBRARY_DEFAULTS: &[(&str, &[(&str, DefaultValue)])] = &[
+    ("constant", &[("value", DefaultValue::Float(1.0))]),
+    ("cellularvalue", CELLULAR_DEFAULTS),
+    ("cellulardistance", CELLULAR_DEFAULTS),
+    ("cellularlookup", CELLULAR_DEFAULTS),
+    ("fractalfbm", FRACTAL_DEFAULTS),
+    ("fractalridged", FRACTAL_DEFAULTS),
+    ("domainwarpfractalprogressive", FRACTAL_DEFAULTS),
+    ("domainwarpfractalindependent", FRACTAL_DEFAULTS),
+    ("domainwarpgradient", DOMAIN_WARP_DEFAULTS),
+    ("domainwarpsimplex", DOMAIN_WARP_DEFAULTS),
+    ("domainwarpsupersimplex", DOMAIN_WARP_DEFAULTS),
+    ("domainscale", &[("scaling", DefaultValue::Float(1.0))]),
+    ("domainaxisscale", &[("scaling", DefaultValue::Float(1.0))]),
+    ("domainoffset", &[("offset", DefaultValue::Float(0.0))]),
+    (
+        "domainrotate",
+        &[
+            ("yaw", DefaultValue::Float(0.0)),
+            ("pitch", DefaultValue::Float(0.0)),
+            ("roll", DefaultValue::Float(0.0)),
+        ],
+    ),
+    ("seedoffset", &[("seedoffset", DefaultValue::Int(1))]),
+    (
+        "remap",
+        &[
+            ("frommin", DefaultValue::Float(-1.0)),
+            ("frommax", DefaultValue::Float(1.0)),
+            ("tomin", DefaultValue::Float(0.0)),
+            ("tomax", DefaultValue::Float(1.0)),
+        ],
+    ),
+    (
+        "convertrgba8",
+        &[
+            ("min", DefaultValue::Float(-1.0)),
+            ("max", DefaultValue::Float(1.0)),
+        ],
+    ),
+    (
+        "adddimension",
+        &[("newdimensionposition", DefaultValue::Float(0.0))],
+    ),
+];
+
+const CELLULAR_DEFAULTS: &[(&str, DefaultValue)] = &[
+    ("gridjitter", DefaultValue::Float(1.0)),
+    (
+        "distancefunction",
+        DefaultValue::Variant("euclideansquared"),
+    ),
+    ("valueindex", DefaultValue::Int(0)),
+    ("distanceindex0", DefaultValue::Int(0)),
+    ("distanceindex1", DefaultValue::Int(1)),
+    ("returntype", DefaultValue::Variant("index0")),
+];
+
+const FRACTAL_DEFAULTS: &[(&str, DefaultValue)] = &[
+    ("gain", DefaultValue::Float(0.5)),
+    ("weightedstrength", DefaultValue::Float(0.0)),
+    ("octaves", DefaultValue::Int(3)),
+    ("lacunarity", DefaultValue::Float(2.0)),
+];
+
+const DOMAIN_WARP_DEFAULTS: &[(&str, DefaultValue)] = &[
+    ("warpamplitude", DefaultValue::Float(1.0)),
+    ("featurescale", DefaultValue::Float(100.0)),
+];
+
+/// Decodes an encoded node tree against the node table of the linked library.
+pub(crate) fn decode(encoded_node_tree: &str) -> Result<EncodedNode, encoded::DecodeError> {
+    encoded::decode(encoded_node_tree, &NODE_LAYOUTS)
+}
+
+/// Creates the nodes of a decoded tree, recording their members like [`Node::set`].
+///
+/// The members left out of the tree hold the FastNoise2 defaults. They are recorded too when the default is known: the
+/// default of the typed generators for optional members, see [`Member::default_value`], and [`LIBRARY_DEFAULTS`] for
+/// required ones. Others are not recorded.
+pub(crate) fn decoded_tree(decoded: &EncodedNode) -> Result<Node, FastNoiseError> {
+    let metadata = &NODE_METADATA[decoded.id];
+    let mut node = Node::from_name(&metadata.display_name)?;
+    for member in metadata.members.values() {
+        let value = match member.slot() {
+            MemberSlot::Variable(index) => decoded
+                .variables
+                .iter()
+                .find(|(set, _)| *set == index as usize)
+                .map(|(_, value)| match value {
+                    VariableValue::Float(value) => RecordedValue::Float(*value),
+                    VariableValue::Int(value) => RecordedValue::Int(*value),
+                }),
+            MemberSlot::NodeLookup(index) => {
+                let child = decoded_tree(&decoded.node_lookups[index as usize])?;
+                Some(RecordedValue::Node(child.into()))
+            }
+            MemberSlot::Hybrid(index) => match decoded
+                .hybrids
+                .iter()
+                .find(|(set, _)| *set == index as usize)
+            {
+                Some((_, HybridValue::Float(value))) => Some(RecordedValue::Float(*value)),
+                Some((_, HybridValue::Node(child))) => {
+                    Some(RecordedValue::Node(decoded_tree(child)?.into()))
+                }
+                None => None,
+            },
+        };
+        if let Some(value) = value.or_else(|| library_default(member)) {
+            node.apply_recorded(member.slot(), value)?;
+        }
+    }
+    Ok(node)
+}
+
+/// Returns the FastNoise2 default of `member`, if known.
+fn library_default(member: &Member) -> Option<RecordedValue> {
+    let name = format_lookup(&member.display_name);
+    let default = member.default_value().or_else(|| {
+        LIBRARY_DEFAULTS
+            .iter()
+            .find(|(node, _)| *node == member.node)
+            .and_then(|(_, defaults)| defaults.iter().find(|(member, _)| *member == name))
+            .map(|(_, default)| default)
+    })?;
+    match default {
+        DefaultValue::Float(value) => Some(RecordedValue::Float(*value)),
+        DefaultValue::Int(value) => Some(RecordedValue::Int(*value)),
+        DefaultValue::Variant(variant) => member
+            .enum_names
+            .get(*variant)
+            .copied()
+            .map(RecordedValue::Int),
+    }
+}
+
+/// Encodes the members recorded in a tree built with [`Node::set`] or typed generators.
+///
+/// # Panics
+/// Panics if a node of the tree was decoded from an encoded node tree, or misses a node lookup.
+pub(crate) fn encode_tree(node: &Node) -> String {
+    encoded::encode(&encoded_node(node))
+}
+
+fn encoded_node(node: &Node) -> EncodedNode {
+    assert!(
+        node.encoded.is_none(),
+        "decoded nodes can't be encoded again"
+    );
+    let mut encoded = EncodedNode {
+        id: node.metadata_id as usize,
+        variables: Vec::new(),
+        node_lookups: Vec::new(),
+        hybrids: Vec::new(),
+    };
+    for (slot, value) in &node.members {
+        match (slot, value) {
+            (MemberSlot::Variable(index), RecordedValue::Float(value)) => encoded
+                .variables
+                .push((*index as usize, VariableValue::Float(*value))),
+            (MemberSlot::Variable(index), RecordedValue::Int(value)) => encoded
+                .variables
+                .push((*index as usize, VariableValue::Int(*value))),
+            (MemberSlot::NodeLookup(index), RecordedValue::Node(child)) => {
+                assert_eq!(
+                    *index as usize,
+                    encoded.node_lookups.len(),
+                    "node lookup is not set"
+                );
+                encoded.node_lookups.push(encoded_node(child));
+            }
+            (MemberSlot::Hybrid(index), RecordedValue::Float(value)) => encoded
+                .hybrids
+                .push((*index as usize, HybridValue::Float(*value))),
+            (MemberSlot::Hybrid(index), RecordedValue::Node(child)) => encoded
+                .hybrids
+                .push((*index as usize, HybridValue::Node(encoded_node(child)))),
+            _ => unreachable!("recorded values match the type of their member"),
+        }
+    }
+    encoded
+}
@@ -1,3 +1,4 @@
+use fastnoise2_sys::encoded::DecodeError;
 use thiserror::Error;
 
 use crate::metadata::MemberType;
@@ -5,42 +6,86 @@ use crate::metadata::MemberType;
 /// Errors that can occur when interacting with [`Node`][`crate::Node`].
 ///
 /// This enum covers various failure scenarios including metadata issues, value setting problems, and node creation errors.
+/// Use [`FastNoiseError::kind`] to handle them by category, and [`FastNoiseError::path`] to find the node of a tree
+/// that failed to build.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum FastNoiseError {
     /// Indicates that the provided metadata name was not found.
     ///
     /// FastNoise2 uses metadata to manage node names and parameters. This error occurs if the given metadata name is not recognized.
     #[error(
-        "metadata name not found (expected one of {}, found '{found}')",
-        format_slice(expected)
+        "metadata name '{found}' not found{}",
+        format_alternatives(suggestions, expected)
     )]
     MetadataNameNotFound {
         /// A list of valid metadata names.
         expected: Vec<String>,
         /// The metadata name that was not found.
         found: String,
+        /// The valid metadata names closest to `found`, closest first.
+        suggestions: Vec<String>,
     },
 
     /// Indicates a failure to create a [`CString`][`std::ffi::CString`] from the provided encoded node tree string.
     #[error("failed to create CString from encoded node tree")]
     CStringCreationFailed(#[from] std::ffi::NulError),
 
-    /// Indicates a failure to create a node from the encoded node tree.
-    #[error("failed to create noise node from the encoded node tree")]
+    /// Indicates a failure to create a node, either from the encoded node tree or at the requested SIMD level.
+    #[error("failed to create noise node")]
     NodeCreationFailed,
 
+    /// Indicates that FastNoise2 failed to decode an encoded node tree.
+    ///
+    /// This error occurs if FastNoise2 rejects an encoded node tree that matches its node table, see
+    /// [`FastNoiseError::InvalidEncodedNodeTree`], e.g. if it was exported by a Node Editor whose FastNoise2 version is
+    /// incompatible with the linked library, see [`library_info`][crate::library_info].
+    #[error("failed to decode encoded node tree with {library}, it may have been exported by an incompatible Node Editor version")]
+    EncodedNodeTreeDecodeFailed {
+        /// Description of the linked library, see [`LibraryInfo`][crate::LibraryInfo].
+        library: String,
+    },
+
+    /// Indicates that an encoded node tree doesn't match the node table of the linked library.
+    ///
+    /// Encoded node trees are decoded in Rust against the nodes of the linked library before FastNoise2 decodes them,
+    /// see [`fastnoise2_sys::encoded`], to report what doesn't match: invalid text, a node or member the library doesn't
+    /// have, or a missing node lookup. Trees exported by a Node Editor whose FastNoise2 version is incompatible with the
+    /// linked library usually fail here, see [`library_info`][crate::library_info].
+    #[error("invalid encoded node tree for {library}: {error}")]
+    InvalidEncodedNodeTree {
+        /// Description of the linked library, see [`LibraryInfo`][crate::LibraryInfo].
+        library: String,
+        /// What doesn't match the node table.
+        error: DecodeError,
+    },
+
+    /// Indicates that an encoded node tree can't be read as exported before fastnoise2 0.4.0.
+    ///
+    /// This error occurs if [`migrate_encoded_node_tree`][crate::migration::migrate_encoded_node_tree] is given a tree
+    /// that doesn't match the node table of FastNoise2 0.10, e.g. one exported by a recent Node Editor.
+    #[error("invalid encoded node tree for FastNoise2 0.10: {error}")]
+    InvalidLegacyEncodedNodeTree {
+        /// What doesn't match the node table.
+        error: DecodeError,
+    },
+
     /// Indicates that the provided member name was not found.
     ///
     /// This error occurs if the member name specified is not available for the node.
     #[error(
-        "member name not found (expected one of {}, found '{found}')",
-        format_slice(expected)
+        "member name '{found}' not found on '{node}'{}",
+        format_alternatives(suggestions, expected)
     )]
     MemberNameNotFound {
+        /// The type of the node, e.g. `FractalFBm`.
+        node: String,
         /// A list of valid member names.
         expected: Vec<String>,
         /// The member name that was not found.
         found: String,
+        /// The valid member names closest to `found`, closest first.
+        suggestions: Vec<String>,
     },
 
     /// Indicates that the member type does not match the expected type.
@@ -56,6 +101,33 @@ pub enum FastNoiseError {
         found: MemberType,
     },
 
+    /// Indicates that a value can't be converted to the type of the member without overflowing.
+    ///
+    /// This error occurs when setting a [`u32`] larger than [`i32::MAX`], or an [`f64`] too large for an [`f32`].
+    #[error("value out of range for '{member_name}' (expected {expected}, found {found})")]
+    ValueOutOfRange {
+        /// The name of the member.
+        member_name: String,
+        /// The member type the value is converted to.
+        expected: MemberType,
+        /// The value that was given.
+        found: String,
+    },
+
+    /// Indicates that the number of values doesn't match the number of dimensions of the member.
+    ///
+    /// This error occurs when setting an array on a member that isn't a group of per-dimension members such as
+    /// `Offset`, or a single value or an array of the wrong length on such a group.
+    #[error("invalid number of values for '{member_name}' (expected {expected}, found {found})")]
+    InvalidDimensionCount {
+        /// The name of the member or group of members.
+        member_name: String,
+        /// The number of dimensions of the member.
+        expected: usize,
+        /// The number of values that were given.
+        found: usize,
+    },
+
     /// Indicates a failure to set a float value for a member.
     #[error("failed to set float value")]
     SetFloatFailed,
@@ -72,14 +144,18 @@ pub enum FastNoiseError {
     ///
     /// This error occurs if the provided enum value does not match any of the expected enum values.
     #[error(
-        "enum value not found (expected one of {}, found '{found}')",
-        format_slice(expected)
+        "enum value '{found}' not found for '{member_name}'{}",
+        format_alternatives(suggestions, expected)
     )]
     EnumValueNotFound {
+        /// The name of the enum member.
+        member_name: String,
         /// A list of valid enum values.
         expected: Vec<String>,
         /// The enum value that was not found.
         found: String,
+        /// The valid enum values closest to `found`, closest first.
+        suggestions: Vec<String>,
     },
 
     /// Indicates a failure to set an enum value for a member.
@@ -93,6 +169,212 @@ pub enum FastNoiseError {
     /// Indicates a failure to set a hybrid node lookup for a member.
     #[error("failed to set hybrid node lookup")]
     SetHybridNodeLookupFailed,
+
+    /// Indicates that a member path doesn't lead to a member of the tree.
+    ///
+    /// This error occurs if the path is malformed, or if one of its nodes is not known on the Rust side, see
+    /// [`SafeNode::param`][crate::SafeNode::param].
+    #[error("invalid member path '{path}': {reason}")]
+    InvalidPath {
+        /// The path that was given.
+        path: String,
+        /// Why the path is invalid.
+        reason: String,
+    },
+
+    /// Indicates that a tree can't be written as typed generators by
+    /// [`codegen::tree_source`][crate::codegen::tree_source].
+    #[error("failed to write source for '{node}': {reason}")]
+    SourceGenerationFailed {
+        /// The name of the node that can't be written.
+        node: String,
+        /// Why the node can't be written.
+        reason: String,
+    },
+
+    /// Indicates that a [`SampleRegion`][crate::stats::SampleRegion] can't be sampled.
+    ///
+    /// This error occurs if one of its counts is negative, if its number of samples is above
+    /// [`MAX_SAMPLES`][crate::stats::MAX_SAMPLES], or if its output can't be allocated.
+    #[error("invalid sample region: {reason}")]
+    InvalidSampleRegion {
+        /// Why the region is invalid.
+        reason: String,
+    },
+
+    /// Indicates that a node tree document can't be read by [`SafeNode::from_json`][crate::SafeNode::from_json].
+    ///
+    /// This error occurs if the document is not valid JSON, or if its values don't have the shape described in
+    /// [`schema`][crate::schema], e.g. a number out of the range of an [`f32`] or a missing node member.
+    #[error("invalid node tree document: {reason}")]
+    InvalidDocument {
+        /// Why the document is invalid.
+        reason: String,
+    },
+
+    /// Indicates a failure to build a typed generator.
+    ///
+    /// This error occurs if the linked FastNoise2 library does not match the node or member names used by
+    /// [`Generator`][crate::generator::Generator] implementations, e.g. after a submodule bump that renames them.
+    /// Errors of child nodes are nested, so the outermost error names the root node.
+    #[error("failed to build '{node}'{}: {error}", format_member(member.as_deref()))]
+    BuildFailed {
+        /// The name of the node being built.
+        node: String,
+        /// The name of the member being set, or `None` if the node itself could not be created.
+        member: Option<String>,
+        /// The error returned by FastNoise2.
+        error: Box<FastNoiseError>,
+    },
+}
+
+/// Category of a [`FastNoiseError`], returned by [`FastNoiseError::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A node, member, enum value or member path doesn't exist, e.g. a typo in a configuration file.
+    NotFound,
+    /// A value doesn't fit the member it is set on, or an encoded node tree is malformed.
+    InvalidValue,
+    /// FastNoise2 failed to create a node or to set a member.
+    Library,
+}
+
+impl FastNoiseError {
+    /// Returns the category of the error. Errors of a tree that failed to build have the kind of their
+    /// [root cause][FastNoiseError::root_cause].
+    pub fn kind(&self) -> ErrorKind {
+        match self.root_cause() {
+            Self::MetadataNameNotFound { .. }
+            | Self::MemberNameNotFound { .. }
+            | Self::EnumValueNotFound { .. }
+            | Self::InvalidPath { .. } => ErrorKind::NotFound,
+            Self::CStringCreationFailed(_)
+            | Self::EncodedNodeTreeDecodeFailed { .. }
+            | Self::InvalidEncodedNodeTree { .. }
+            | Self::InvalidLegacyEncodedNodeTree { .. }
+            | Self::InvalidMemberType { .. }
+            | Self::ValueOutOfRange { .. }
+            | Self::InvalidDimensionCount { .. }
+            | Self::SourceGenerationFailed { .. }
+            | Self::InvalidSampleRegion { .. }
+            | Self::InvalidDocument { .. } => ErrorKind::InvalidValue,
+            Self::NodeCreationFailed
+            | Self::SetFloatFailed
+            | Self::SetHybridFloatFailed
+            | Self::SetIntFailed
+            | Self::SetEnumFailed
+            | Self::SetNodeLookupFailed
+            | Self::SetHybridNodeLookupFailed => ErrorKind::Library,
+            Self::BuildFailed { .. } => unreachable!("root causes are not build errors"),
+        }
+    }
+
+    /// Returns the error that caused a tree to fail to build, or this error itself.
+    pub fn root_cause(&self) -> &Self {
+        match self {
+            Self::BuildFailed { error, .. } => error.root_cause(),
+            error => error,
+        }
+    }
+
+    /// Returns the path of the node that failed to build within its tree, e.g. `root.Source`, in the syntax of
+    /// [`SafeNode::param`][crate::SafeNode::param]. Returns `None` for errors that didn't occur while building a tree.
+    pub fn path(&self) -> Option<String> {
+        let Self::BuildFailed { .. } = self else {
+            return None;
+        };
+        let mut path = String::from("root");
+        let mut error = self;
+        while let Self::BuildFailed {
+            member: Some(member),
+            error: child,
+            ..
+        } = error
+        {
+            if !matches!(**child, Self::BuildFailed { .. }) {
+                break;
+            }
+            path.push('.');
+            path.push_str(member);
+            error = child;
+        }
+        Some(path)
+    }
+
+    /// Returns the type of the node that failed, e.g. `FractalFBm`, if known.
+    pub fn node_type(&self) -> Option<&str> {
+        match self {
+            Self::BuildFailed { node, error, .. } => error.node_type().or(Some(node)),
+            Self::MemberNameNotFound { node, .. } | Self::SourceGenerationFailed { node, .. } => {
+                Some(node)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the names of `candidates` closest to `found` by edit distance, ignoring case and spaces, closest first.
+///
+/// Only close enough names are returned, so a name unrelated to every candidate gives no suggestions.
+pub(crate) fn suggestions<'a>(
+    found: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let found = normalize(found);
+    let max_distance = (found.chars().count() / 3).max(2);
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&found, &normalize(candidate)), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .collect();
+    matches.sort();
+    matches.dedup_by(|a, b| a.1 == b.1);
+    matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Lists of valid names up to this length are shown when there is no suggestion.
+const MAX_LISTED: usize = 8;
+
+fn normalize(name: &str) -> String {
+    name.replace(' ', "").to_lowercase()
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn format_alternatives(suggestions: &[String], expected: &[String]) -> String {
+    match suggestions {
+        [] if expected.len() <= MAX_LISTED => {
+            format!(" (expected one of {})", format_slice(expected))
+        }
+        [] => String::new(),
+        [suggestion] => format!(" (did you mean '{suggestion}'?)"),
+        [rest @ .., last] => format!(" (did you mean {} or '{last}'?)", format_slice(rest)),
+    }
+}
+
+fn format_member(member: Option<&str>) -> String {
+    member.map_or_else(String::new, |member| format!(" member '{member}'"))
 }
 
 fn format_slice(slice: &[String]) -> String {
@@ -102,3 +384,64 @@ fn format_slice(slice: &[String]) -> String {
         .collect::<Vec<String>>()
         .join(", ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generator::DistanceFunction, Node};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("perlin", "perlin"), 0);
+        assert_eq!(edit_distance("perln", "perlin"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let candidates = ["Perlin", "Simplex", "SuperSimplex", "Value"];
+        assert_eq!(suggestions("perln", candidates), ["Perlin"]);
+        assert_eq!(suggestions("Super Simplx", candidates), ["SuperSimplex"]);
+        assert_eq!(
+            suggestions("Mim", ["Max", "Min", "Mix"]),
+            ["Min", "Mix", "Max"]
+        );
+        assert!(suggestions("Checkerboard", candidates).is_empty());
+    }
+
+    #[test]
+    fn test_not_found_messages() {
+        let error = Node::from_name("FractalFbn").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert_eq!(
+            error.to_string(),
+            "metadata name 'FractalFbn' not found (did you mean 'FractalFBm'?)"
+        );
+
+        let mut node = Node::from_name("FractalFBm").unwrap();
+        let error = node.set("Gian", 0.5).unwrap_err();
+        assert_eq!(error.node_type(), Some("FractalFBm"));
+        assert_eq!(
+            error.to_string(),
+            "member name 'Gian' not found on 'FractalFBm' (did you mean 'Gain'?)"
+        );
+
+        let mut node = Node::from_name("CellularValue").unwrap();
+        let error = node.set("DistanceFunction", "Euclidian").unwrap_err();
+        assert!(error.to_string().starts_with(
+            "enum value 'Euclidian' not found for 'DistanceFunction' (did you mean 'Euclidean'"
+        ));
+        node.set("DistanceFunction", DistanceFunction::Euclidean)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_kind() {
+        let mut node = Node::from_name("FractalFBm").unwrap();
+        let error = node.set("Octaves", 0.5).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+        assert_eq!(error.path(), None);
+        assert_eq!(error.root_cause().to_string(), error.to_string());
+    }
+}
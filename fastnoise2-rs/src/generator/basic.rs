@@ -1,5 +1,8 @@
-use super::{DistanceFunction, Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, DistanceFunction, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper,
+    Hybrid, NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 /// Constant value generator.
 #[derive(Clone, Debug)]
@@ -136,61 +139,161 @@ impl Default for SineWave {
     }
 }
 
+content_eq!(
+    Constant,
+    White,
+    Checkerboard,
+    SineWave,
+    Gradient,
+    DistanceToPoint<X: Hybrid, Y: Hybrid, Z: Hybrid, W: Hybrid, M: Hybrid>,
+);
+
 impl Generator for Constant {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Constant").unwrap();
-        node.set("Value", self.value).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Constant")?;
+        node.set("Value", self.value)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Constant", visitor)
+            .variable("Value", &self.value)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Constant", folder);
+        Ok(Self {
+            value: fold.variable("Value", &self.value)?,
+        })
     }
 }
 
 impl Generator for White {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("White").unwrap();
-        node.set("SeedOffset", self.seed_offset).unwrap();
-        node.set("OutputMin", self.output_min).unwrap();
-        node.set("OutputMax", self.output_max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("White")?;
+        node.set("SeedOffset", self.seed_offset)?;
+        node.set("OutputMin", self.output_min)?;
+        node.set("OutputMax", self.output_max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("White", visitor)
+            .variable("SeedOffset", &self.seed_offset)
+            .variable("OutputMin", &self.output_min)
+            .variable("OutputMax", &self.output_max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("White", folder);
+        Ok(Self {
+            seed_offset: fold.variable("SeedOffset", &self.seed_offset)?,
+            output_min: fold.variable("OutputMin", &self.output_min)?,
+            output_max: fold.variable("OutputMax", &self.output_max)?,
+        })
     }
 }
 
 impl Generator for Checkerboard {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Checkerboard").unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set("OutputMin", self.output_min).unwrap();
-        node.set("OutputMax", self.output_max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Checkerboard")?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("OutputMin", self.output_min)?;
+        node.set("OutputMax", self.output_max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Checkerboard", visitor)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("OutputMin", &self.output_min)
+            .variable("OutputMax", &self.output_max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Checkerboard", folder);
+        Ok(Self {
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            output_min: fold.variable("OutputMin", &self.output_min)?,
+            output_max: fold.variable("OutputMax", &self.output_max)?,
+        })
     }
 }
 
 impl Generator for SineWave {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("SineWave").unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set("OutputMin", self.output_min).unwrap();
-        node.set("OutputMax", self.output_max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("SineWave")?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("OutputMin", self.output_min)?;
+        node.set("OutputMax", self.output_max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("SineWave", visitor)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("OutputMin", &self.output_min)
+            .variable("OutputMax", &self.output_max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("SineWave", folder);
+        Ok(Self {
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            output_min: fold.variable("OutputMin", &self.output_min)?,
+            output_max: fold.variable("OutputMax", &self.output_max)?,
+        })
     }
 }
 
 impl Generator for Gradient {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Gradient").unwrap();
-        node.set("MultiplierX", self.multiplier_x).unwrap();
-        node.set("MultiplierY", self.multiplier_y).unwrap();
-        node.set("MultiplierZ", self.multiplier_z).unwrap();
-        node.set("MultiplierW", self.multiplier_w).unwrap();
-        node.set("OffsetX", self.offset_x).unwrap();
-        node.set("OffsetY", self.offset_y).unwrap();
-        node.set("OffsetZ", self.offset_z).unwrap();
-        node.set("OffsetW", self.offset_w).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Gradient")?;
+        node.set("MultiplierX", self.multiplier_x)?;
+        node.set("MultiplierY", self.multiplier_y)?;
+        node.set("MultiplierZ", self.multiplier_z)?;
+        node.set("MultiplierW", self.multiplier_w)?;
+        node.set("OffsetX", self.offset_x)?;
+        node.set("OffsetY", self.offset_y)?;
+        node.set("OffsetZ", self.offset_z)?;
+        node.set("OffsetW", self.offset_w)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Gradient", visitor)
+            .variable("MultiplierX", &self.multiplier_x)
+            .variable("MultiplierY", &self.multiplier_y)
+            .variable("MultiplierZ", &self.multiplier_z)
+            .variable("MultiplierW", &self.multiplier_w)
+            .variable("OffsetX", &self.offset_x)
+            .variable("OffsetY", &self.offset_y)
+            .variable("OffsetZ", &self.offset_z)
+            .variable("OffsetW", &self.offset_w)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Gradient", folder);
+        Ok(Self {
+            multiplier_x: fold.variable("MultiplierX", &self.multiplier_x)?,
+            multiplier_y: fold.variable("MultiplierY", &self.multiplier_y)?,
+            multiplier_z: fold.variable("MultiplierZ", &self.multiplier_z)?,
+            multiplier_w: fold.variable("MultiplierW", &self.multiplier_w)?,
+            offset_x: fold.variable("OffsetX", &self.offset_x)?,
+            offset_y: fold.variable("OffsetY", &self.offset_y)?,
+            offset_z: fold.variable("OffsetZ", &self.offset_z)?,
+            offset_w: fold.variable("OffsetW", &self.offset_w)?,
+        })
     }
 }
 
@@ -203,16 +306,38 @@ where
     M: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DistanceToPoint").unwrap();
-        node.set("DistanceFunction", &*self.distance_function.to_string())
-            .unwrap();
-        node.set("PointX", self.point_x.clone()).unwrap();
-        node.set("PointY", self.point_y.clone()).unwrap();
-        node.set("PointZ", self.point_z.clone()).unwrap();
-        node.set("PointW", self.point_w.clone()).unwrap();
-        node.set("MinkowskiP", self.minkowski_p.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DistanceToPoint")?;
+        node.set("DistanceFunction", &self.distance_function)?;
+        node.set("PointX", self.point_x.clone())?;
+        node.set("PointY", self.point_y.clone())?;
+        node.set("PointZ", self.point_z.clone())?;
+        node.set("PointW", self.point_w.clone())?;
+        node.set("MinkowskiP", self.minkowski_p.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DistanceToPoint", visitor)
+            .hybrid("PointX", &self.point_x)
+            .hybrid("PointY", &self.point_y)
+            .hybrid("PointZ", &self.point_z)
+            .hybrid("PointW", &self.point_w)
+            .hybrid("MinkowskiP", &self.minkowski_p)
+            .variable("DistanceFunction", &self.distance_function)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DistanceToPoint", folder);
+        Ok(Self {
+            point_x: fold.hybrid("PointX", &self.point_x)?,
+            point_y: fold.hybrid("PointY", &self.point_y)?,
+            point_z: fold.hybrid("PointZ", &self.point_z)?,
+            point_w: fold.hybrid("PointW", &self.point_w)?,
+            minkowski_p: fold.hybrid("MinkowskiP", &self.minkowski_p)?,
+            distance_function: fold.variable("DistanceFunction", &self.distance_function)?,
+        })
     }
 }
 
@@ -1,5 +1,8 @@
-use super::{FadeInterpolation, Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, FadeInterpolation, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper,
+    Hybrid, NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 #[derive(Clone, Debug)]
 pub struct Add<Lhs, Rhs>
@@ -131,17 +134,47 @@ where
     pub pow: i32,
 }
 
+content_eq!(
+    Add<Lhs: Generator, Rhs: Hybrid>,
+    Subtract<Lhs: Hybrid, Rhs: Hybrid>,
+    Multiply<Lhs: Generator, Rhs: Hybrid>,
+    Divide<Lhs: Hybrid, Rhs: Hybrid>,
+    Modulus<Lhs: Hybrid, Rhs: Hybrid>,
+    Min<Lhs: Generator, Rhs: Hybrid>,
+    Max<Lhs: Generator, Rhs: Hybrid>,
+    MinSmooth<Lhs: Generator, Rhs: Hybrid, S: Hybrid>,
+    MaxSmooth<Lhs: Generator, Rhs: Hybrid, S: Hybrid>,
+    Fade<A: Generator, B: Generator, F: Hybrid, FMin: Hybrid, FMax: Hybrid>,
+    PowFloat<V: Hybrid, P: Hybrid>,
+    PowInt<V: Generator>,
+);
+
 impl<Lhs, Rhs> Generator for Add<Lhs, Rhs>
 where
     Lhs: Generator,
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Add").unwrap();
-        node.set("LHS", &self.lhs).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Add")?;
+        node.set("LHS", &self.lhs)?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Add", visitor)
+            .node("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Add", folder);
+        Ok(Self {
+            lhs: fold.node("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -151,11 +184,26 @@ where
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Subtract").unwrap();
-        node.set("LHS", self.lhs.clone()).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Subtract")?;
+        node.set("LHS", self.lhs.clone())?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Subtract", visitor)
+            .hybrid("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Subtract", folder);
+        Ok(Self {
+            lhs: fold.hybrid("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -165,11 +213,26 @@ where
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Multiply").unwrap();
-        node.set("LHS", &self.lhs).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Multiply")?;
+        node.set("LHS", &self.lhs)?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Multiply", visitor)
+            .node("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Multiply", folder);
+        Ok(Self {
+            lhs: fold.node("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -179,11 +242,26 @@ where
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Divide").unwrap();
-        node.set("LHS", self.lhs.clone()).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Divide")?;
+        node.set("LHS", self.lhs.clone())?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Divide", visitor)
+            .hybrid("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Divide", folder);
+        Ok(Self {
+            lhs: fold.hybrid("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -193,11 +271,26 @@ where
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Modulus").unwrap();
-        node.set("LHS", self.lhs.clone()).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Modulus")?;
+        node.set("LHS", self.lhs.clone())?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Modulus", visitor)
+            .hybrid("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Modulus", folder);
+        Ok(Self {
+            lhs: fold.hybrid("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -207,11 +300,26 @@ where
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Min").unwrap();
-        node.set("LHS", &self.lhs).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Min")?;
+        node.set("LHS", &self.lhs)?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Min", visitor)
+            .node("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Min", folder);
+        Ok(Self {
+            lhs: fold.node("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -221,11 +329,26 @@ where
     Rhs: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Max").unwrap();
-        node.set("LHS", &self.lhs).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Max")?;
+        node.set("LHS", &self.lhs)?;
+        node.set("RHS", self.rhs.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Max", visitor)
+            .node("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Max", folder);
+        Ok(Self {
+            lhs: fold.node("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+        })
     }
 }
 
@@ -236,12 +359,29 @@ where
     S: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("MinSmooth").unwrap();
-        node.set("LHS", &self.lhs).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        node.set("Smoothness", self.smoothness.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("MinSmooth")?;
+        node.set("LHS", &self.lhs)?;
+        node.set("RHS", self.rhs.clone())?;
+        node.set("Smoothness", self.smoothness.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("MinSmooth", visitor)
+            .node("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .hybrid("Smoothness", &self.smoothness)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("MinSmooth", folder);
+        Ok(Self {
+            lhs: fold.node("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+            smoothness: fold.hybrid("Smoothness", &self.smoothness)?,
+        })
     }
 }
 
@@ -252,12 +392,29 @@ where
     S: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("MaxSmooth").unwrap();
-        node.set("LHS", &self.lhs).unwrap();
-        node.set("RHS", self.rhs.clone()).unwrap();
-        node.set("Smoothness", self.smoothness.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("MaxSmooth")?;
+        node.set("LHS", &self.lhs)?;
+        node.set("RHS", self.rhs.clone())?;
+        node.set("Smoothness", self.smoothness.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("MaxSmooth", visitor)
+            .node("LHS", &self.lhs)
+            .hybrid("RHS", &self.rhs)
+            .hybrid("Smoothness", &self.smoothness)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("MaxSmooth", folder);
+        Ok(Self {
+            lhs: fold.node("LHS", &self.lhs)?,
+            rhs: fold.hybrid("RHS", &self.rhs)?,
+            smoothness: fold.hybrid("Smoothness", &self.smoothness)?,
+        })
     }
 }
 
@@ -270,16 +427,41 @@ where
     FMax: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Fade").unwrap();
-        node.set("A", &self.a).unwrap();
-        node.set("B", &self.b).unwrap();
-        node.set("Fade", self.fade.clone()).unwrap();
-        node.set("FadeMin", self.fade_min.clone()).unwrap();
-        node.set("FadeMax", self.fade_max.clone()).unwrap();
-        node.set("Interpolation", &*self.interpolation.to_string())
-            .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Fade")?;
+        node.set("A", &self.a)?;
+        node.set("B", &self.b)?;
+        node.set("Fade", self.fade.clone())?;
+        node.set("FadeMin", self.fade_min.clone())?;
+        node.set("FadeMax", self.fade_max.clone())?;
+        node.set("Interpolation", &self.interpolation)?;
+        Ok(node.build())
+    }
+
+    fn visit<Vis: GeneratorVisitor + ?Sized>(&self, visitor: &mut Vis) {
+        TypedVisit::new("Fade", visitor)
+            .node("A", &self.a)
+            .node("B", &self.b)
+            .hybrid("Fade", &self.fade)
+            .hybrid("FadeMin", &self.fade_min)
+            .hybrid("FadeMax", &self.fade_max)
+            .variable("Interpolation", &self.interpolation)
+            .leave();
+    }
+
+    fn fold<Fol: GeneratorFolder + ?Sized>(
+        &self,
+        folder: &mut Fol,
+    ) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Fade", folder);
+        Ok(Self {
+            a: fold.node("A", &self.a)?,
+            b: fold.node("B", &self.b)?,
+            fade: fold.hybrid("Fade", &self.fade)?,
+            fade_min: fold.hybrid("FadeMin", &self.fade_min)?,
+            fade_max: fold.hybrid("FadeMax", &self.fade_max)?,
+            interpolation: fold.variable("Interpolation", &self.interpolation)?,
+        })
     }
 }
 
@@ -289,11 +471,29 @@ where
     P: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("PowFloat").unwrap();
-        node.set("Value", self.value.clone()).unwrap();
-        node.set("Pow", self.pow.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("PowFloat")?;
+        node.set("Value", self.value.clone())?;
+        node.set("Pow", self.pow.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<Vis: GeneratorVisitor + ?Sized>(&self, visitor: &mut Vis) {
+        TypedVisit::new("PowFloat", visitor)
+            .hybrid("Value", &self.value)
+            .hybrid("Pow", &self.pow)
+            .leave();
+    }
+
+    fn fold<Fol: GeneratorFolder + ?Sized>(
+        &self,
+        folder: &mut Fol,
+    ) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("PowFloat", folder);
+        Ok(Self {
+            value: fold.hybrid("Value", &self.value)?,
+            pow: fold.hybrid("Pow", &self.pow)?,
+        })
     }
 }
 
@@ -302,11 +502,29 @@ where
     V: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("PowInt").unwrap();
-        node.set("Value", &self.value).unwrap();
-        node.set("Pow", self.pow).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("PowInt")?;
+        node.set("Value", &self.value)?;
+        node.set("Pow", self.pow)?;
+        Ok(node.build())
+    }
+
+    fn visit<Vis: GeneratorVisitor + ?Sized>(&self, visitor: &mut Vis) {
+        TypedVisit::new("PowInt", visitor)
+            .node("Value", &self.value)
+            .variable("Pow", &self.pow)
+            .leave();
+    }
+
+    fn fold<Fol: GeneratorFolder + ?Sized>(
+        &self,
+        folder: &mut Fol,
+    ) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("PowInt", folder);
+        Ok(Self {
+            value: fold.node("Value", &self.value)?,
+            pow: fold.variable("Pow", &self.pow)?,
+        })
     }
 }
 
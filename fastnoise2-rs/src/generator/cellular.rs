@@ -1,5 +1,8 @@
-use super::{DistanceFunction, Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, enum_member_value, DistanceFunction, Generator, GeneratorFolder, GeneratorVisitor,
+    GeneratorWrapper, Hybrid, NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 #[derive(Clone, Debug)]
 pub struct CellularValue<J, M, S>
@@ -46,6 +49,12 @@ where
     pub size_jitter: S,
 }
 
+content_eq!(
+    CellularValue<J: Hybrid, M: Hybrid, S: Hybrid>,
+    CellularDistance<J: Hybrid, M: Hybrid, S: Hybrid>,
+    CellularLookup<L: Generator, J: Hybrid, M: Hybrid, S: Hybrid>,
+);
+
 impl<J, M, S> Generator for CellularValue<J, M, S>
 where
     J: Hybrid,
@@ -53,15 +62,35 @@ where
     S: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("CellularValue").unwrap();
-        node.set("GridJitter", self.grid_jitter.clone()).unwrap();
-        node.set("DistanceFunction", &*self.distance_function.to_string())
-            .unwrap();
-        node.set("ValueIndex", self.value_index).unwrap();
-        node.set("MinkowskiP", self.minkowski_p.clone()).unwrap();
-        node.set("SizeJitter", self.size_jitter.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("CellularValue")?;
+        node.set("GridJitter", self.grid_jitter.clone())?;
+        node.set("DistanceFunction", &self.distance_function)?;
+        node.set("ValueIndex", self.value_index)?;
+        node.set("MinkowskiP", self.minkowski_p.clone())?;
+        node.set("SizeJitter", self.size_jitter.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("CellularValue", visitor)
+            .hybrid("GridJitter", &self.grid_jitter)
+            .hybrid("MinkowskiP", &self.minkowski_p)
+            .hybrid("SizeJitter", &self.size_jitter)
+            .variable("DistanceFunction", &self.distance_function)
+            .variable("ValueIndex", &self.value_index)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("CellularValue", folder);
+        Ok(Self {
+            grid_jitter: fold.hybrid("GridJitter", &self.grid_jitter)?,
+            minkowski_p: fold.hybrid("MinkowskiP", &self.minkowski_p)?,
+            size_jitter: fold.hybrid("SizeJitter", &self.size_jitter)?,
+            distance_function: fold.variable("DistanceFunction", &self.distance_function)?,
+            value_index: fold.variable("ValueIndex", &self.value_index)?,
+        })
     }
 }
 
@@ -72,18 +101,41 @@ where
     S: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("CellularDistance").unwrap();
-        node.set("GridJitter", self.grid_jitter.clone()).unwrap();
-        node.set("DistanceFunction", &*self.distance_function.to_string())
-            .unwrap();
-        node.set("DistanceIndex0", self.distance_index_0).unwrap();
-        node.set("DistanceIndex1", self.distance_index_1).unwrap();
-        node.set("ReturnType", &*self.return_type.to_string())
-            .unwrap();
-        node.set("MinkowskiP", self.minkowski_p.clone()).unwrap();
-        node.set("SizeJitter", self.size_jitter.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("CellularDistance")?;
+        node.set("GridJitter", self.grid_jitter.clone())?;
+        node.set("DistanceFunction", &self.distance_function)?;
+        node.set("DistanceIndex0", self.distance_index_0)?;
+        node.set("DistanceIndex1", self.distance_index_1)?;
+        node.set("ReturnType", &self.return_type)?;
+        node.set("MinkowskiP", self.minkowski_p.clone())?;
+        node.set("SizeJitter", self.size_jitter.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("CellularDistance", visitor)
+            .hybrid("GridJitter", &self.grid_jitter)
+            .hybrid("MinkowskiP", &self.minkowski_p)
+            .hybrid("SizeJitter", &self.size_jitter)
+            .variable("DistanceFunction", &self.distance_function)
+            .variable("DistanceIndex0", &self.distance_index_0)
+            .variable("DistanceIndex1", &self.distance_index_1)
+            .variable("ReturnType", &self.return_type)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("CellularDistance", folder);
+        Ok(Self {
+            grid_jitter: fold.hybrid("GridJitter", &self.grid_jitter)?,
+            minkowski_p: fold.hybrid("MinkowskiP", &self.minkowski_p)?,
+            size_jitter: fold.hybrid("SizeJitter", &self.size_jitter)?,
+            distance_function: fold.variable("DistanceFunction", &self.distance_function)?,
+            distance_index_0: fold.variable("DistanceIndex0", &self.distance_index_0)?,
+            distance_index_1: fold.variable("DistanceIndex1", &self.distance_index_1)?,
+            return_type: fold.variable("ReturnType", &self.return_type)?,
+        })
     }
 }
 
@@ -95,15 +147,35 @@ where
     S: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("CellularLookup").unwrap();
-        node.set("Lookup", &self.lookup).unwrap();
-        node.set("GridJitter", self.grid_jitter.clone()).unwrap();
-        node.set("DistanceFunction", &*self.distance_function.to_string())
-            .unwrap();
-        node.set("MinkowskiP", self.minkowski_p.clone()).unwrap();
-        node.set("SizeJitter", self.size_jitter.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("CellularLookup")?;
+        node.set("Lookup", &self.lookup)?;
+        node.set("GridJitter", self.grid_jitter.clone())?;
+        node.set("DistanceFunction", &self.distance_function)?;
+        node.set("MinkowskiP", self.minkowski_p.clone())?;
+        node.set("SizeJitter", self.size_jitter.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("CellularLookup", visitor)
+            .node("Lookup", &self.lookup)
+            .hybrid("GridJitter", &self.grid_jitter)
+            .hybrid("MinkowskiP", &self.minkowski_p)
+            .hybrid("SizeJitter", &self.size_jitter)
+            .variable("DistanceFunction", &self.distance_function)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("CellularLookup", folder);
+        Ok(Self {
+            lookup: fold.node("Lookup", &self.lookup)?,
+            grid_jitter: fold.hybrid("GridJitter", &self.grid_jitter)?,
+            minkowski_p: fold.hybrid("MinkowskiP", &self.minkowski_p)?,
+            size_jitter: fold.hybrid("SizeJitter", &self.size_jitter)?,
+            distance_function: fold.variable("DistanceFunction", &self.distance_function)?,
+        })
     }
 }
 
@@ -264,6 +336,14 @@ impl std::fmt::Display for CellularDistanceReturnType {
     }
 }
 
+enum_member_value!(CellularDistanceReturnType {
+    Index0,
+    Index0Add1,
+    Index0Sub1,
+    Index0Mul1,
+    Index0Div1,
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
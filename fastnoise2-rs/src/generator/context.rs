@@ -0,0 +1,235 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use super::{Generator, GeneratorWrapper};
+use crate::{
+    metadata::{MemberSlot, RecordedValue},
+    safe::SafeNode,
+    FastNoiseError,
+};
+
+/// Shares the nodes built for structurally identical subtrees.
+///
+/// A [`Generator`] only describes a tree, so building the same generator twice, e.g. as both sides of a
+/// [`Fade`][super::blend::Fade], would create two independent FastNoise2 nodes. While a context is active, every
+/// built node is looked up by its type and member values, children included, and replaced by the node already built
+/// for an identical subtree if there is one. Shared nodes are generated once per call by FastNoise2's generator cache
+/// and written once in encoded node trees.
+///
+/// [`GeneratorWrapper::build`] uses a fresh context for each tree. Use a context directly to share nodes between
+/// several trees, or to build them at a specific SIMD level.
+///
+/// # Examples
+///
+/// ```rust
+/// use fastnoise2::generator::{prelude::*, BuildContext};
+///
+/// let mut context = BuildContext::new();
+/// let terrain = context.build(&perlin().fbm(0.5, 0.0, 4, 2.0).domain_scale(0.5));
+/// let caves = context.build(&perlin().fbm(0.5, 0.0, 4, 2.0).abs());
+///
+/// // The fractal subtree is built once and used by both trees.
+/// assert_eq!(context.len(), 4);
+/// ```
+#[derive(Debug)]
+pub struct BuildContext {
+    simd_level: u32,
+    nodes: HashMap<NodeKey, SafeNode>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct NodeKey {
+    metadata_id: i32,
+    members: Vec<(MemberSlot, ValueKey)>,
+}
+
+/// [`RecordedValue`] compared by bits, so that keys can be hashed.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum ValueKey {
+    Float(u32),
+    Int(i32),
+    Node(usize),
+}
+
+impl From<&RecordedValue> for ValueKey {
+    fn from(value: &RecordedValue) -> Self {
+        match *value {
+            RecordedValue::Float(value) => Self::Float(value.to_bits()),
+            RecordedValue::Int(value) => Self::Int(value),
+            RecordedValue::Node(ref child) => Self::Node(child.handle as usize),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<BuildContext>> = const { RefCell::new(None) };
+}
+
+impl Default for BuildContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildContext {
+    /// Creates a context building nodes at the highest SIMD level supported by the CPU.
+    pub fn new() -> Self {
+        // Pass u32::MAX (~0u in C++) for auto-detect SIMD level
+        Self::with_simd_level(u32::MAX)
+    }
+
+    /// Creates a context building nodes at the requested SIMD level. FastNoise2 falls back to a lower level if the
+    /// requested one is not available, use [`SafeNode::get_simd_level`] to know which one was picked.
+    pub fn with_simd_level(simd_level: u32) -> Self {
+        Self {
+            simd_level,
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn simd_level(&self) -> u32 {
+        self.simd_level
+    }
+
+    /// Builds `generator`, reusing the nodes of identical subtrees already built with this context.
+    ///
+    /// # Panics
+    /// Panics if [`BuildContext::try_build`] fails.
+    pub fn build<G: Generator>(&mut self, generator: &G) -> GeneratorWrapper<SafeNode> {
+        self.try_build(generator)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Builds `generator`, reusing the nodes of identical subtrees already built with this context.
+    ///
+    /// # Errors
+    /// Returns the error of [`Generator::try_build`]. Nodes built before the failure stay in the context.
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
+    pub fn try_build<G: Generator>(
+        &mut self,
+        generator: &G,
+    ) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        struct Restore<'a> {
+            context: &'a mut BuildContext,
+            previous: Option<BuildContext>,
+        }
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                if let Some(context) = CURRENT.replace(self.previous.take()) {
+                    *self.context = context;
+                }
+            }
+        }
+
+        let simd_level = self.simd_level;
+        let taken = std::mem::replace(self, Self::with_simd_level(simd_level));
+        let _restore = Restore {
+            previous: CURRENT.replace(Some(taken)),
+            context: self,
+        };
+        Ok(intern(generator.try_build()?.0).into())
+    }
+
+    /// Number of distinct nodes built with this context.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Forgets every node built so far, later builds create new nodes.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    fn intern(&mut self, node: SafeNode) -> SafeNode {
+        // Nodes decoded from an encoded node tree have unknown members, they can only be shared as they are.
+        if node.0.encoded.is_some() {
+            return node;
+        }
+        let key = NodeKey {
+            metadata_id: node.0.metadata_id,
+            members: node
+                .0
+                .members
+                .iter()
+                .map(|(&slot, value)| (slot, value.into()))
+                .collect(),
+        };
+        self.nodes.entry(key).or_insert(node).clone()
+    }
+}
+
+/// Returns the node already built for a subtree identical to `node` in the active context, or registers `node`.
+///
+/// Children must be interned before their parent is built, so that identical subtrees reference the same child
+/// nodes and compare equal.
+pub(crate) fn intern(node: SafeNode) -> SafeNode {
+    CURRENT.with_borrow_mut(|context| match context {
+        Some(context) => context.intern(node),
+        None => node,
+    })
+}
+
+/// Calls [`Generator::try_build`] within the active context, or within a fresh one if there is none.
+pub(crate) fn scoped<G: Generator>(
+    generator: &G,
+) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+    if CURRENT.with_borrow(Option::is_some) {
+        generator.try_build()
+    } else {
+        BuildContext::new().try_build(generator)
+    }
+}
+
+/// SIMD level requested by the active context, if any.
+pub(crate) fn build_simd_level() -> u32 {
+    CURRENT.with_borrow(|context| context.as_ref().map_or(u32::MAX, |c| c.simd_level))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::generator::{perlin::perlin, simplex::simplex};
+
+    #[test]
+    fn test_shared_subtree() {
+        let source = perlin().fbm(0.5, 0.0, 3, 2.0);
+        let mut context = BuildContext::new();
+        context.build(&source.clone().fade(source, simplex()));
+        // Perlin, FractalFBm, Simplex and Fade.
+        assert_eq!(context.len(), 4);
+    }
+
+    #[test]
+    fn test_distinct_subtrees() {
+        let mut context = BuildContext::new();
+        context.build(&perlin().fade(perlin().with_seed_offset(1), 0.5));
+        assert_eq!(context.len(), 3);
+    }
+
+    #[test]
+    fn test_shared_between_trees() {
+        let mut context = BuildContext::new();
+        let a = context.build(&perlin().domain_scale(2.0));
+        let b = context.build(&perlin().domain_scale(2.0));
+        assert!(Arc::ptr_eq(&a.0 .0, &b.0 .0));
+        assert_eq!(context.len(), 2);
+
+        context.clear();
+        let c = context.build(&perlin().domain_scale(2.0));
+        assert!(!Arc::ptr_eq(&a.0 .0, &c.0 .0));
+    }
+
+    #[test]
+    fn test_simd_level() {
+        let level = perlin().build().get_simd_level();
+        let node = BuildContext::with_simd_level(level).build(&perlin());
+        assert_eq!(node.get_simd_level(), level);
+        assert_eq!(build_simd_level(), u32::MAX);
+    }
+}
@@ -0,0 +1,523 @@
+//! Optional layer tracking how many dimensions a tree reads.
+//!
+//! Most generators can be sampled in 2D, 3D or 4D, but [`AddDimension`] and [`RemoveDimension`] change how many
+//! dimensions their source reads, and some trees are only meaningful with a real Z or W. Sampling such a tree with the
+//! wrong method gives a slice or a projection of it, which only shows up as odd-looking output.
+//!
+//! [`Dimensioned`] tags a generator with the number of dimensions it must be sampled in, [`D2`], [`D3`] or [`D4`].
+//! The tag follows [`Dimensioned::add_dimension`] and [`Dimensioned::remove_dimension`], combining trees requires the
+//! same tag, and the built [`DimensionedNode`] only has the generation methods of its dimension count.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use fastnoise2::generator::{
+//!     dimensioned::{D2, D3},
+//!     prelude::*,
+//!     Dimension,
+//! };
+//!
+//! // Caves are carved in 3D.
+//! let caves = perlin().fbm(0.5, 0.0, 3, 2.0).with_dimensions::<D3>();
+//! // A 2D height map, extruded along Z so it can be combined with the caves.
+//! let height = simplex()
+//!     .with_dimensions::<D2>()
+//!     .remove_dimension(Dimension::Z);
+//!
+//! let terrain = caves
+//!     .zip(height, |caves, height| caves.min(height))
+//!     .build_dimensioned();
+//! let value = terrain.gen_single_3d(1.0, 2.0, 3.0, 1337);
+//! // `terrain.gen_single_2d(..)` doesn't compile.
+//! # let _ = value;
+//! ```
+use std::marker::PhantomData;
+
+use super::{
+    content_eq,
+    modifier::{AddDimension, RemoveDimension},
+    Dimension, Generator, GeneratorWrapper, Hybrid,
+};
+use crate::{FastNoiseError, GeneratorFolder, GeneratorVisitor, OutputMinMax, SafeNode};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Number of dimensions a tree must be sampled in, see [`D2`], [`D3`] and [`D4`].
+pub trait Dimensions: private::Sealed + Clone + Copy + std::fmt::Debug + Default {
+    const COUNT: usize;
+}
+
+/// Dimension counts that an [`AddDimension`] can lower.
+pub trait Lower: Dimensions {
+    type Lower: Dimensions;
+}
+
+/// Dimension counts that a [`RemoveDimension`] can raise.
+pub trait Higher: Dimensions {
+    type Higher: Dimensions;
+}
+
+/// Trees sampled in 2D.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct D2;
+
+/// Trees sampled in 3D.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct D3;
+
+/// Trees sampled in 4D.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct D4;
+
+impl private::Sealed for D2 {}
+impl private::Sealed for D3 {}
+impl private::Sealed for D4 {}
+
+impl Dimensions for D2 {
+    const COUNT: usize = 2;
+}
+
+impl Dimensions for D3 {
+    const COUNT: usize = 3;
+}
+
+impl Dimensions for D4 {
+    const COUNT: usize = 4;
+}
+
+impl Lower for D3 {
+    type Lower = D2;
+}
+
+impl Lower for D4 {
+    type Lower = D3;
+}
+
+impl Higher for D2 {
+    type Higher = D3;
+}
+
+impl Higher for D3 {
+    type Higher = D4;
+}
+
+/// Generator that must be sampled in `D` dimensions.
+#[derive(Clone, Debug)]
+pub struct Dimensioned<G, D>
+where
+    G: Generator,
+    D: Dimensions,
+{
+    pub generator: G,
+    dimensions: PhantomData<D>,
+}
+
+impl<G> GeneratorWrapper<G>
+where
+    G: Generator,
+{
+    /// Tags this generator as sampled in `D` dimensions.
+    pub fn with_dimensions<D: Dimensions>(self) -> Dimensioned<G, D> {
+        Dimensioned::new(self.0)
+    }
+}
+
+impl<G, D> Dimensioned<G, D>
+where
+    G: Generator,
+    D: Dimensions,
+{
+    pub fn new(generator: G) -> Self {
+        Self {
+            generator,
+            dimensions: PhantomData,
+        }
+    }
+
+    /// Applies combinators that keep the dimension count, e.g. fractals or blends with untagged generators.
+    pub fn map<H, F>(self, f: F) -> Dimensioned<H, D>
+    where
+        H: Generator,
+        F: FnOnce(GeneratorWrapper<G>) -> GeneratorWrapper<H>,
+    {
+        Dimensioned::new(f(GeneratorWrapper(self.generator)).0)
+    }
+
+    /// Combines this tree with another one sampled in the same number of dimensions.
+    pub fn zip<O, H, F>(self, other: Dimensioned<O, D>, f: F) -> Dimensioned<H, D>
+    where
+        O: Generator,
+        H: Generator,
+        F: FnOnce(GeneratorWrapper<G>, GeneratorWrapper<O>) -> GeneratorWrapper<H>,
+    {
+        Dimensioned::new(
+            f(
+                GeneratorWrapper(self.generator),
+                GeneratorWrapper(other.generator),
+            )
+            .0,
+        )
+    }
+
+    /// Forgets the dimension count.
+    pub fn into_inner(self) -> GeneratorWrapper<G> {
+        GeneratorWrapper(self.generator)
+    }
+
+    /// Builds the tree into a [`DimensionedNode`], see [`Generator::build`], which builds an untagged
+    /// [`SafeNode`] instead.
+    ///
+    /// # Panics
+    /// Panics if [`Dimensioned::try_build_dimensioned`] fails.
+    pub fn build_dimensioned(&self) -> DimensionedNode<D> {
+        self.try_build_dimensioned()
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Builds the tree into a [`DimensionedNode`], see [`Generator::try_build`].
+    pub fn try_build_dimensioned(&self) -> Result<DimensionedNode<D>, FastNoiseError> {
+        Ok(DimensionedNode::new(
+            GeneratorWrapper(&self.generator).try_build()?.0,
+        ))
+    }
+}
+
+impl<G, D> Dimensioned<G, D>
+where
+    G: Generator,
+    D: Lower,
+{
+    /// Samples the source with one more dimension set to `new_dimension_position`, so the tree needs one less.
+    pub fn add_dimension<N>(
+        self,
+        new_dimension_position: N,
+    ) -> Dimensioned<AddDimension<G, N>, D::Lower>
+    where
+        N: Hybrid,
+    {
+        Dimensioned::new(
+            GeneratorWrapper(self.generator)
+                .add_dimension(new_dimension_position)
+                .0,
+        )
+    }
+}
+
+impl<G, D> Dimensioned<G, D>
+where
+    G: Generator,
+    D: Higher,
+{
+    /// Samples the source without `remove_dimension`, so the tree needs one more.
+    pub fn remove_dimension(
+        self,
+        remove_dimension: Dimension,
+    ) -> Dimensioned<RemoveDimension<G>, D::Higher> {
+        Dimensioned::new(
+            GeneratorWrapper(self.generator)
+                .remove_dimension(remove_dimension)
+                .0,
+        )
+    }
+}
+
+content_eq!(Dimensioned<G: Generator, D: Dimensions>);
+
+impl<G, D> Generator for Dimensioned<G, D>
+where
+    G: Generator,
+    D: Dimensions,
+{
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        self.generator.try_build()
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        self.generator.visit(visitor);
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        Ok(Self {
+            generator: self.generator.fold(folder)?,
+            dimensions: PhantomData,
+        })
+    }
+}
+
+/// Built tree that can only be sampled in `D` dimensions.
+#[derive(Clone, Debug)]
+pub struct DimensionedNode<D: Dimensions> {
+    node: SafeNode,
+    dimensions: PhantomData<D>,
+}
+
+impl<D: Dimensions> DimensionedNode<D> {
+    /// Tags `node` as sampled in `D` dimensions.
+    pub fn new(node: SafeNode) -> Self {
+        Self {
+            node,
+            dimensions: PhantomData,
+        }
+    }
+
+    pub fn as_safe_node(&self) -> &SafeNode {
+        &self.node
+    }
+
+    /// Forgets the dimension count.
+    pub fn into_inner(self) -> SafeNode {
+        self.node
+    }
+}
+
+impl DimensionedNode<D2> {
+    /// See [`SafeNode::gen_uniform_grid_2d`].
+    pub fn gen_uniform_grid_2d(
+        &self,
+        noise_out: &mut [f32],
+        x_offset: f32,
+        y_offset: f32,
+        x_count: i32,
+        y_count: i32,
+        x_step_size: f32,
+        y_step_size: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node.gen_uniform_grid_2d(
+            noise_out,
+            x_offset,
+            y_offset,
+            x_count,
+            y_count,
+            x_step_size,
+            y_step_size,
+            seed,
+        )
+    }
+
+    /// See [`SafeNode::gen_position_array_2d`].
+    pub fn gen_position_array_2d(
+        &self,
+        noise_out: &mut [f32],
+        x_pos_array: &[f32],
+        y_pos_array: &[f32],
+        x_offset: f32,
+        y_offset: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node.gen_position_array_2d(
+            noise_out,
+            x_pos_array,
+            y_pos_array,
+            x_offset,
+            y_offset,
+            seed,
+        )
+    }
+
+    /// See [`SafeNode::gen_tileable_2d`].
+    pub fn gen_tileable_2d(
+        &self,
+        noise_out: &mut [f32],
+        x_size: i32,
+        y_size: i32,
+        x_step_size: f32,
+        y_step_size: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node
+            .gen_tileable_2d(noise_out, x_size, y_size, x_step_size, y_step_size, seed)
+    }
+
+    pub fn gen_single_2d(&self, x: f32, y: f32, seed: i32) -> f32 {
+        self.node.gen_single_2d(x, y, seed)
+    }
+}
+
+impl DimensionedNode<D3> {
+    /// See [`SafeNode::gen_uniform_grid_3d`].
+    pub fn gen_uniform_grid_3d(
+        &self,
+        noise_out: &mut [f32],
+        x_offset: f32,
+        y_offset: f32,
+        z_offset: f32,
+        x_count: i32,
+        y_count: i32,
+        z_count: i32,
+        x_step_size: f32,
+        y_step_size: f32,
+        z_step_size: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node.gen_uniform_grid_3d(
+            noise_out,
+            x_offset,
+            y_offset,
+            z_offset,
+            x_count,
+            y_count,
+            z_count,
+            x_step_size,
+            y_step_size,
+            z_step_size,
+            seed,
+        )
+    }
+
+    /// See [`SafeNode::gen_position_array_3d`].
+    pub fn gen_position_array_3d(
+        &self,
+        noise_out: &mut [f32],
+        x_pos_array: &[f32],
+        y_pos_array: &[f32],
+        z_pos_array: &[f32],
+        x_offset: f32,
+        y_offset: f32,
+        z_offset: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node.gen_position_array_3d(
+            noise_out,
+            x_pos_array,
+            y_pos_array,
+            z_pos_array,
+            x_offset,
+            y_offset,
+            z_offset,
+            seed,
+        )
+    }
+
+    pub fn gen_single_3d(&self, x: f32, y: f32, z: f32, seed: i32) -> f32 {
+        self.node.gen_single_3d(x, y, z, seed)
+    }
+}
+
+impl DimensionedNode<D4> {
+    /// See [`SafeNode::gen_uniform_grid_4d`].
+    pub fn gen_uniform_grid_4d(
+        &self,
+        noise_out: &mut [f32],
+        x_offset: f32,
+        y_offset: f32,
+        z_offset: f32,
+        w_offset: f32,
+        x_count: i32,
+        y_count: i32,
+        z_count: i32,
+        w_count: i32,
+        x_step_size: f32,
+        y_step_size: f32,
+        z_step_size: f32,
+        w_step_size: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node.gen_uniform_grid_4d(
+            noise_out,
+            x_offset,
+            y_offset,
+            z_offset,
+            w_offset,
+            x_count,
+            y_count,
+            z_count,
+            w_count,
+            x_step_size,
+            y_step_size,
+            z_step_size,
+            w_step_size,
+            seed,
+        )
+    }
+
+    /// See [`SafeNode::gen_position_array_4d`].
+    pub fn gen_position_array_4d(
+        &self,
+        noise_out: &mut [f32],
+        x_pos_array: &[f32],
+        y_pos_array: &[f32],
+        z_pos_array: &[f32],
+        w_pos_array: &[f32],
+        x_offset: f32,
+        y_offset: f32,
+        z_offset: f32,
+        w_offset: f32,
+        seed: i32,
+    ) -> OutputMinMax {
+        self.node.gen_position_array_4d(
+            noise_out,
+            x_pos_array,
+            y_pos_array,
+            z_pos_array,
+            w_pos_array,
+            x_offset,
+            y_offset,
+            z_offset,
+            w_offset,
+            seed,
+        )
+    }
+
+    pub fn gen_single_4d(&self, x: f32, y: f32, z: f32, w: f32, seed: i32) -> f32 {
+        self.node.gen_single_4d(x, y, z, w, seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{perlin::perlin, simplex::simplex};
+
+    #[test]
+    fn test_dimension_changes() {
+        let node = perlin()
+            .with_dimensions::<D4>()
+            .add_dimension(0.5)
+            .add_dimension(0.25)
+            .build_dimensioned();
+        let expected = perlin().add_dimension(0.5).add_dimension(0.25).build();
+        assert_eq!(
+            node.gen_single_2d(1.0, 2.0, 7),
+            expected.gen_single_2d(1.0, 2.0, 7)
+        );
+
+        let node = perlin()
+            .with_dimensions::<D2>()
+            .remove_dimension(Dimension::W)
+            .remove_dimension(Dimension::Z)
+            .build_dimensioned();
+        assert!(node.gen_single_4d(1.0, 2.0, 3.0, 4.0, 7).is_finite());
+    }
+
+    #[test]
+    fn test_map_and_zip() {
+        let a = perlin().with_dimensions::<D3>();
+        let b = simplex().with_dimensions::<D3>();
+        let node = a
+            .map(|a| a.fbm(0.5, 0.0, 3, 2.0))
+            .zip(b, |a, b| a + b)
+            .build_dimensioned();
+
+        let expected = (perlin().fbm(0.5, 0.0, 3, 2.0) + simplex()).build();
+        assert_eq!(
+            node.gen_single_3d(1.0, 2.0, 3.0, 7),
+            expected.gen_single_3d(1.0, 2.0, 3.0, 7)
+        );
+    }
+
+    #[test]
+    fn test_as_generator() {
+        let tagged = perlin().with_dimensions::<D2>();
+        let node = GeneratorWrapper(tagged).domain_scale(2.0).build();
+        let expected = perlin().domain_scale(2.0).build();
+        assert_eq!(
+            node.gen_single_2d(1.0, 2.0, 7),
+            expected.gen_single_2d(1.0, 2.0, 7)
+        );
+        assert_eq!(<D3 as Dimensions>::COUNT, 3);
+    }
+}
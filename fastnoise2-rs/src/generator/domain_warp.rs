@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
-use super::{Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, enum_member_value, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper,
+    Hybrid, NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 pub trait DomainWarpNode: Generator {}
 
@@ -24,6 +27,11 @@ impl Display for VectorizationScheme {
     }
 }
 
+enum_member_value!(VectorizationScheme {
+    OrthogonalGradientMatrix,
+    GradientOuterProduct,
+});
+
 #[derive(Clone, Debug)]
 pub struct DomainWarpGradient<S, A>
 where
@@ -61,19 +69,41 @@ where
     pub vectorization_scheme: VectorizationScheme,
 }
 
+content_eq!(
+    DomainWarpGradient<S: Generator, A: Hybrid>,
+    DomainWarpSimplex<S: Generator, A: Hybrid>,
+    DomainWarpSuperSimplex<S: Generator, A: Hybrid>,
+);
+
 impl<S, A> Generator for DomainWarpGradient<S, A>
 where
     S: Generator,
     A: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainWarpGradient").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("WarpAmplitude", self.warp_amplitude.clone())
-            .unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainWarpGradient")?;
+        node.set("Source", &self.source)?;
+        node.set("WarpAmplitude", self.warp_amplitude.clone())?;
+        node.set("FeatureScale", self.feature_scale)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainWarpGradient", visitor)
+            .node("Source", &self.source)
+            .hybrid("WarpAmplitude", &self.warp_amplitude)
+            .variable("FeatureScale", &self.feature_scale)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainWarpGradient", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            warp_amplitude: fold.hybrid("WarpAmplitude", &self.warp_amplitude)?,
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+        })
     }
 }
 
@@ -90,18 +120,33 @@ where
     A: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainWarpSimplex").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("WarpAmplitude", self.warp_amplitude.clone())
-            .unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set(
-            "VectorizationScheme",
-            &*self.vectorization_scheme.to_string(),
-        )
-        .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainWarpSimplex")?;
+        node.set("Source", &self.source)?;
+        node.set("WarpAmplitude", self.warp_amplitude.clone())?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("VectorizationScheme", &self.vectorization_scheme)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainWarpSimplex", visitor)
+            .node("Source", &self.source)
+            .hybrid("WarpAmplitude", &self.warp_amplitude)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("VectorizationScheme", &self.vectorization_scheme)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainWarpSimplex", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            warp_amplitude: fold.hybrid("WarpAmplitude", &self.warp_amplitude)?,
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            vectorization_scheme: fold
+                .variable("VectorizationScheme", &self.vectorization_scheme)?,
+        })
     }
 }
 
@@ -118,18 +163,33 @@ where
     A: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainWarpSuperSimplex").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("WarpAmplitude", self.warp_amplitude.clone())
-            .unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set(
-            "VectorizationScheme",
-            &*self.vectorization_scheme.to_string(),
-        )
-        .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainWarpSuperSimplex")?;
+        node.set("Source", &self.source)?;
+        node.set("WarpAmplitude", self.warp_amplitude.clone())?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("VectorizationScheme", &self.vectorization_scheme)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainWarpSuperSimplex", visitor)
+            .node("Source", &self.source)
+            .hybrid("WarpAmplitude", &self.warp_amplitude)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("VectorizationScheme", &self.vectorization_scheme)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainWarpSuperSimplex", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            warp_amplitude: fold.hybrid("WarpAmplitude", &self.warp_amplitude)?,
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            vectorization_scheme: fold
+                .variable("VectorizationScheme", &self.vectorization_scheme)?,
+        })
     }
 }
 
@@ -1,5 +1,8 @@
-use super::{domain_warp::DomainWarpNode, Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, domain_warp::DomainWarpNode, Generator, GeneratorFolder, GeneratorVisitor,
+    GeneratorWrapper, Hybrid, NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 #[derive(Clone, Debug)]
 pub struct DomainWarpFractalProgressive<S, G, W>
@@ -29,6 +32,11 @@ where
     pub lacunarity: f32,
 }
 
+content_eq!(
+    DomainWarpFractalProgressive<S: DomainWarpNode, G: Hybrid, W: Hybrid>,
+    DomainWarpFractalIndependent<S: DomainWarpNode, G: Hybrid, W: Hybrid>,
+);
+
 impl<S, G, W> Generator for DomainWarpFractalProgressive<S, G, W>
 where
     S: DomainWarpNode,
@@ -36,16 +44,35 @@ where
     W: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainWarpFractalProgressive").unwrap();
-        node.set("DomainWarpSource", &self.domain_warp_source)
-            .unwrap();
-        node.set("Gain", self.gain.clone()).unwrap();
-        node.set("WeightedStrength", self.weighted_strength.clone())
-            .unwrap();
-        node.set("Octaves", self.octaves).unwrap();
-        node.set("Lacunarity", self.lacunarity).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainWarpFractalProgressive")?;
+        node.set("DomainWarpSource", &self.domain_warp_source)?;
+        node.set("Gain", self.gain.clone())?;
+        node.set("WeightedStrength", self.weighted_strength.clone())?;
+        node.set("Octaves", self.octaves)?;
+        node.set("Lacunarity", self.lacunarity)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainWarpFractalProgressive", visitor)
+            .node("DomainWarpSource", &self.domain_warp_source)
+            .hybrid("Gain", &self.gain)
+            .hybrid("WeightedStrength", &self.weighted_strength)
+            .variable("Octaves", &self.octaves)
+            .variable("Lacunarity", &self.lacunarity)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainWarpFractalProgressive", folder);
+        Ok(Self {
+            domain_warp_source: fold.node("DomainWarpSource", &self.domain_warp_source)?,
+            gain: fold.hybrid("Gain", &self.gain)?,
+            weighted_strength: fold.hybrid("WeightedStrength", &self.weighted_strength)?,
+            octaves: fold.variable("Octaves", &self.octaves)?,
+            lacunarity: fold.variable("Lacunarity", &self.lacunarity)?,
+        })
     }
 }
 
@@ -56,16 +83,35 @@ where
     W: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainWarpFractalIndependent").unwrap();
-        node.set("DomainWarpSource", &self.domain_warp_source)
-            .unwrap();
-        node.set("Gain", self.gain.clone()).unwrap();
-        node.set("WeightedStrength", self.weighted_strength.clone())
-            .unwrap();
-        node.set("Octaves", self.octaves).unwrap();
-        node.set("Lacunarity", self.lacunarity).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainWarpFractalIndependent")?;
+        node.set("DomainWarpSource", &self.domain_warp_source)?;
+        node.set("Gain", self.gain.clone())?;
+        node.set("WeightedStrength", self.weighted_strength.clone())?;
+        node.set("Octaves", self.octaves)?;
+        node.set("Lacunarity", self.lacunarity)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainWarpFractalIndependent", visitor)
+            .node("DomainWarpSource", &self.domain_warp_source)
+            .hybrid("Gain", &self.gain)
+            .hybrid("WeightedStrength", &self.weighted_strength)
+            .variable("Octaves", &self.octaves)
+            .variable("Lacunarity", &self.lacunarity)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainWarpFractalIndependent", folder);
+        Ok(Self {
+            domain_warp_source: fold.node("DomainWarpSource", &self.domain_warp_source)?,
+            gain: fold.hybrid("Gain", &self.gain)?,
+            weighted_strength: fold.hybrid("WeightedStrength", &self.weighted_strength)?,
+            octaves: fold.variable("Octaves", &self.octaves)?,
+            lacunarity: fold.variable("Lacunarity", &self.lacunarity)?,
+        })
     }
 }
 
@@ -1,5 +1,8 @@
-use super::{Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper, Hybrid,
+    NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 #[derive(Clone, Debug)]
 pub struct FractalFBm<S, G, W>
@@ -29,6 +32,11 @@ where
     pub lacunarity: f32,
 }
 
+content_eq!(
+    FractalFBm<S: Generator, G: Hybrid, W: Hybrid>,
+    FractalRidged<S: Generator, G: Hybrid, W: Hybrid>,
+);
+
 impl<S, G, W> Generator for FractalFBm<S, G, W>
 where
     S: Generator,
@@ -36,15 +44,35 @@ where
     W: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("FractalFBm").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("Gain", self.gain.clone()).unwrap();
-        node.set("WeightedStrength", self.weighted_strength.clone())
-            .unwrap();
-        node.set("Octaves", self.octaves).unwrap();
-        node.set("Lacunarity", self.lacunarity).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("FractalFBm")?;
+        node.set("Source", &self.source)?;
+        node.set("Gain", self.gain.clone())?;
+        node.set("WeightedStrength", self.weighted_strength.clone())?;
+        node.set("Octaves", self.octaves)?;
+        node.set("Lacunarity", self.lacunarity)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("FractalFBm", visitor)
+            .node("Source", &self.source)
+            .hybrid("Gain", &self.gain)
+            .hybrid("WeightedStrength", &self.weighted_strength)
+            .variable("Octaves", &self.octaves)
+            .variable("Lacunarity", &self.lacunarity)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("FractalFBm", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            gain: fold.hybrid("Gain", &self.gain)?,
+            weighted_strength: fold.hybrid("WeightedStrength", &self.weighted_strength)?,
+            octaves: fold.variable("Octaves", &self.octaves)?,
+            lacunarity: fold.variable("Lacunarity", &self.lacunarity)?,
+        })
     }
 }
 
@@ -55,15 +83,35 @@ where
     W: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("FractalRidged").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("Gain", self.gain.clone()).unwrap();
-        node.set("WeightedStrength", self.weighted_strength.clone())
-            .unwrap();
-        node.set("Octaves", self.octaves).unwrap();
-        node.set("Lacunarity", self.lacunarity).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("FractalRidged")?;
+        node.set("Source", &self.source)?;
+        node.set("Gain", self.gain.clone())?;
+        node.set("WeightedStrength", self.weighted_strength.clone())?;
+        node.set("Octaves", self.octaves)?;
+        node.set("Lacunarity", self.lacunarity)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("FractalRidged", visitor)
+            .node("Source", &self.source)
+            .hybrid("Gain", &self.gain)
+            .hybrid("WeightedStrength", &self.weighted_strength)
+            .variable("Octaves", &self.octaves)
+            .variable("Lacunarity", &self.lacunarity)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("FractalRidged", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            gain: fold.hybrid("Gain", &self.gain)?,
+            weighted_strength: fold.hybrid("WeightedStrength", &self.weighted_strength)?,
+            octaves: fold.variable("Octaves", &self.octaves)?,
+            lacunarity: fold.variable("Lacunarity", &self.lacunarity)?,
+        })
     }
 }
 
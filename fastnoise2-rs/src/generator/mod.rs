@@ -14,11 +14,19 @@
 //! - [safe_simple_terrain example](https://github.com/Lemonzyy/fastnoise2-rs/blob/main/fastnoise2-rs/examples/safe_simple_terrain.rs)
 use std::fmt::{Debug, Display};
 
-use crate::{metadata::MemberValue, safe::SafeNode, MemberType, Node};
+use crate::{
+    hash::{content_eq, Content},
+    metadata::MemberValue,
+    safe::SafeNode,
+    visit::{TypedFold, TypedMember, TypedVisit},
+    ContentHash, FastNoiseError, GeneratorFolder, GeneratorVisitor, MemberType, Node, PrettyTree,
+};
 
 pub mod basic;
 pub mod blend;
 pub mod cellular;
+pub(crate) mod context;
+pub mod dimensioned;
 pub mod domain_warp;
 pub mod domain_warp_fractal;
 pub mod fractal;
@@ -27,6 +35,14 @@ pub mod perlin;
 pub mod simplex;
 pub mod value;
 
+pub use context::BuildContext;
+
+/// Derives [`Generator`] for a struct with a `tree(&self) -> impl Generator` method, with a `new` constructor returning
+/// a [`GeneratorWrapper`] and a `with_*` builder method on the wrapper for each field. Documented in the
+/// fastnoise2-derive crate.
+#[cfg(feature = "derive")]
+pub use fastnoise2_derive::Generator;
+
 pub mod prelude {
     //! Functions and [`Generator`] re-exports
     pub use super::{
@@ -40,24 +56,150 @@ pub mod prelude {
 }
 
 pub trait Generator: Clone + Debug {
-    fn build(&self) -> GeneratorWrapper<SafeNode>;
+    /// Builds the FastNoise2 node tree described by this generator.
+    ///
+    /// # Errors
+    /// Returns [`FastNoiseError::BuildFailed`] naming the node and member that could not be created or set, e.g. if
+    /// the linked FastNoise2 library does not know them.
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError>;
+
+    /// Builds the FastNoise2 node tree described by this generator.
+    ///
+    /// # Panics
+    /// Panics if [`Generator::try_build`] fails.
+    fn build(&self) -> GeneratorWrapper<SafeNode> {
+        self.try_build().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Builds the tree and optimizes it, see [`SafeNode::optimize`]: constants are folded and chained nodes fused, so
+    /// that there are fewer nodes to generate.
+    ///
+    /// # Errors
+    /// Returns the errors of [`Generator::try_build`] and [`SafeNode::optimize`].
+    fn try_build_optimized(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        Ok(self.try_build()?.0.optimize()?.into())
+    }
+
+    /// Builds the tree and optimizes it, see [`Generator::try_build_optimized`].
+    ///
+    /// # Panics
+    /// Panics if [`Generator::try_build_optimized`] fails.
+    fn build_optimized(&self) -> GeneratorWrapper<SafeNode> {
+        self.try_build_optimized()
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Builds the tree with every node created at the requested SIMD level instead of the highest one
+    /// supported by the CPU. FastNoise2 falls back to a lower level if the requested one is not available,
+    /// use [`SafeNode::get_simd_level`] to know which one was picked.
+    ///
+    /// Mostly useful to compare outputs across SIMD levels, see [`crate::determinism`].
+    fn build_with_simd_level(&self, simd_level: u32) -> GeneratorWrapper<SafeNode> {
+        BuildContext::with_simd_level(simd_level).build(self)
+    }
+
+    /// Builds the tree and returns it as a Graphviz DOT graph, see [`SafeNode::to_dot`]. Unlike the [`Debug`] output
+    /// of nested generators, nodes are labelled with their name and non-default values only.
+    ///
+    /// # Panics
+    /// Panics if [`Generator::try_build`] fails.
+    fn to_dot(&self) -> String {
+        self.build().0.to_dot()
+    }
+
+    /// Builds the tree and returns it as a Mermaid flowchart, see [`SafeNode::to_mermaid`].
+    ///
+    /// # Panics
+    /// Panics if [`Generator::try_build`] fails.
+    fn to_mermaid(&self) -> String {
+        self.build().0.to_mermaid()
+    }
+
+    /// Returns a printer writing the tree indented, see [`SafeNode::pretty`]. Typed generators are printed through
+    /// their fields without building them. [`GeneratorWrapper`] implements [`Display`] with it.
+    ///
+    /// # Panics
+    /// Panics if the generator is built by [`Generator::visit`] and [`Generator::try_build`] fails.
+    fn pretty(&self) -> PrettyTree {
+        PrettyTree::of(self)
+    }
+
+    /// Returns the hash of the content of the tree, see [`SafeNode::content_hash`]. Typed generators are hashed
+    /// through their fields without building them, and hash like the tree they build.
+    ///
+    /// # Panics
+    /// Panics if the generator is built by [`Generator::visit`] and [`Generator::try_build`] fails.
+    fn content_hash(&self) -> ContentHash {
+        Content::of(self).hash()
+    }
+
+    /// Compares the content of both trees, floats by bits, like [`SafeNode`]'s [`PartialEq`], without building typed
+    /// generators. Use it to skip rebuilding when a reloaded configuration gives an identical tree. Typed generators
+    /// implement [`PartialEq`] with it.
+    ///
+    /// # Panics
+    /// Panics if either generator is built by [`Generator::visit`] and [`Generator::try_build`] fails.
+    fn same_tree<G: Generator>(&self, other: &G) -> bool {
+        Content::of(self) == Content::of(other)
+    }
+
+    /// Walks the tree depth-first with `visitor`, see [`GeneratorVisitor`]. Typed generators are walked through
+    /// their fields without building them, a hybrid member being visited as either a float or a subtree, and
+    /// [`SafeNode`]s through their members.
+    ///
+    /// Derived generators are visited through the tree returned by their `tree` method, and generators whose fields
+    /// are not known are built and visited as their tree by the default implementation.
+    ///
+    /// # Panics
+    /// The default implementation panics if [`Generator::try_build`] fails.
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        self.build().0.visit(visitor);
+    }
+
+    /// Rewrites the tree with `folder` without building it, see [`GeneratorFolder`]. Typed generators keep their type,
+    /// so their fields are rewritten in place: values returned by [`GeneratorFolder::fold_member`] must have the type
+    /// of the field, and subtrees are folded with their own [`Generator::fold`]. [`SafeNode`]s are folded with
+    /// [`SafeNode::fold`].
+    ///
+    /// Generators whose fields are not members of a node, such as derived ones and references, are returned unchanged
+    /// by the default implementation. Build them to fold their tree.
+    ///
+    /// # Errors
+    /// Returns the errors of `folder`, and [`FastNoiseError::InvalidMemberType`] or
+    /// [`FastNoiseError::EnumValueNotFound`] if it returns a value that doesn't fit the field.
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let _ = folder;
+        Ok(self.clone())
+    }
 }
 
 impl<T: Generator> Generator for &T {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        (*self).build()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        (*self).try_build()
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        (*self).visit(visitor);
     }
 }
 
 impl Generator for SafeNode {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        self.clone().into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        Ok(self.clone().into())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        SafeNode::visit(self, visitor);
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        SafeNode::fold(self, folder)
     }
 }
 
-pub trait Hybrid: MemberValue + Clone + Debug {}
+pub trait Hybrid: MemberValue + TypedMember + Clone + Debug {}
 
 impl Hybrid for f32 {}
 
@@ -67,11 +209,133 @@ impl<T: Generator> MemberValue for T {
     const TYPE: MemberType = MemberType::NodeLookup;
 
     fn apply(
-        &self,
+        self,
         node: &mut Node,
         member: &crate::metadata::Member,
     ) -> Result<(), crate::FastNoiseError> {
-        node.set(&member.name, self.build().0 .0.as_ref())
+        context::intern(self.try_build()?.0).0.apply(node, member)
+    }
+}
+
+/// Implements [`MemberValue`] for typed enums, and references to them, setting the variant named by their
+/// [`Display`] implementation. The variants are listed to read the enum back when folding, see [`Generator::fold`].
+macro_rules! enum_member_value {
+    ($($name:ident { $($variant:ident),* $(,)? }),* $(,)?) => {
+        $(
+            impl $crate::metadata::MemberValue for $name {
+                const TYPE: $crate::MemberType = $crate::MemberType::Enum;
+
+                fn apply(
+                    self,
+                    node: &mut $crate::Node,
+                    member: &$crate::metadata::Member,
+                ) -> Result<(), $crate::FastNoiseError> {
+                    $crate::metadata::MemberValue::apply(&self, node, member)
+                }
+            }
+
+            impl $crate::metadata::MemberValue for &$name {
+                const TYPE: $crate::MemberType = $crate::MemberType::Enum;
+
+                fn apply(
+                    self,
+                    node: &mut $crate::Node,
+                    member: &$crate::metadata::Member,
+                ) -> Result<(), $crate::FastNoiseError> {
+                    $crate::metadata::MemberValue::apply(self.to_string().as_str(), node, member)
+                }
+            }
+
+            impl $crate::visit::TypedMember for $name {
+                fn visit_typed<V: $crate::GeneratorVisitor + ?Sized>(
+                    &self,
+                    member: &str,
+                    member_type: $crate::MemberType,
+                    visitor: &mut V,
+                ) {
+                    let name = self.to_string();
+                    visitor.visit_member(member, member_type, $crate::MemberRef::Enum(&name));
+                }
+
+                fn fold_typed<F: $crate::GeneratorFolder + ?Sized>(
+                    &self,
+                    node: &str,
+                    member: &str,
+                    member_type: $crate::MemberType,
+                    folder: &mut F,
+                ) -> Result<Self, $crate::FastNoiseError> {
+                    let value = folder.fold_member(
+                        node,
+                        member,
+                        member_type,
+                        $crate::MemberData::Enum(self.to_string()),
+                    )?;
+                    $crate::visit::fold_enum(
+                        member,
+                        member_type,
+                        value,
+                        [$($name::$variant),*],
+                        ToString::to_string,
+                    )
+                }
+            }
+        )*
+    };
+}
+pub(crate) use enum_member_value;
+
+enum_member_value!(
+    DistanceFunction {
+        Euclidean,
+        EuclideanSquared,
+        Manhattan,
+        Hybrid,
+        MaxAxis,
+        Minkowski,
+    },
+    FadeInterpolation {
+        Linear,
+        Hermite,
+        Quintic,
+    },
+    Dimension { X, Y, Z, W },
+);
+
+/// Node being created by a [`Generator::try_build`] implementation.
+///
+/// Wraps errors in [`FastNoiseError::BuildFailed`] so they name the node and the member that failed.
+pub(crate) struct NodeBuilder {
+    name: &'static str,
+    node: Node,
+}
+
+impl NodeBuilder {
+    pub(crate) fn new(name: &'static str) -> Result<Self, FastNoiseError> {
+        match Node::from_name(name) {
+            Ok(node) => Ok(Self { name, node }),
+            Err(error) => Err(FastNoiseError::BuildFailed {
+                node: name.to_string(),
+                member: None,
+                error: Box::new(error),
+            }),
+        }
+    }
+
+    pub(crate) fn set<V>(&mut self, member_name: &str, value: V) -> Result<(), FastNoiseError>
+    where
+        V: MemberValue + Debug,
+    {
+        self.node
+            .set(member_name, value)
+            .map_err(|error| FastNoiseError::BuildFailed {
+                node: self.name.to_string(),
+                member: Some(member_name.to_string()),
+                error: Box::new(error),
+            })
+    }
+
+    pub(crate) fn build(self) -> GeneratorWrapper<SafeNode> {
+        SafeNode(self.node.into()).into()
     }
 }
 
@@ -92,20 +356,38 @@ impl<T> std::ops::Deref for GeneratorWrapper<T> {
     }
 }
 
+/// Writes the tree built from the generator, see [`SafeNode::pretty`], or the error if it fails to build.
+impl<T: Generator> Display for GeneratorWrapper<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.pretty(), f)
+    }
+}
+
 impl<T: Generator> Generator for GeneratorWrapper<T> {
+    /// Builds the tree, sharing nodes between identical subtrees, see [`BuildContext`].
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        self.0.build()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        context::scoped(&self.0)
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        self.0.visit(visitor);
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        Ok(Self(self.0.fold(folder)?))
     }
 }
 
+content_eq!(GeneratorWrapper<T: Generator>);
+
 impl Hybrid for GeneratorWrapper<f32> {}
 
 impl MemberValue for GeneratorWrapper<f32> {
     const TYPE: MemberType = MemberType::Float;
 
     fn apply(
-        &self,
+        self,
         node: &mut Node,
         member: &crate::metadata::Member,
     ) -> Result<(), crate::FastNoiseError> {
@@ -174,3 +456,102 @@ impl Display for Dimension {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::perlin::perlin;
+
+    /// Generator using names the linked FastNoise2 library doesn't know.
+    #[derive(Clone, Debug)]
+    struct Renamed {
+        node_name: &'static str,
+    }
+
+    impl Generator for Renamed {
+        fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+            let mut node = NodeBuilder::new(self.node_name)?;
+            node.set("RenamedScale", 1.0)?;
+            Ok(node.build())
+        }
+    }
+
+    #[test]
+    fn test_try_build_unknown_node() {
+        let error = Renamed {
+            node_name: "Perlim",
+        }
+        .try_build()
+        .unwrap_err();
+        let FastNoiseError::BuildFailed { node, member, .. } = &error else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(node, "Perlim");
+        assert_eq!(member, &None);
+        assert_eq!(error.path().as_deref(), Some("root"));
+        assert!(error.to_string().contains("did you mean 'Perlin'?"));
+    }
+
+    #[test]
+    fn test_try_build_unknown_member() {
+        let error = Renamed {
+            node_name: "Perlin",
+        }
+        .try_build()
+        .unwrap_err();
+        let FastNoiseError::BuildFailed {
+            node,
+            member,
+            error,
+        } = &error
+        else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(node, "Perlin");
+        assert_eq!(member.as_deref(), Some("RenamedScale"));
+        assert!(matches!(**error, FastNoiseError::MemberNameNotFound { .. }));
+    }
+
+    #[test]
+    fn test_try_build_nested() {
+        let generator = GeneratorWrapper(Renamed {
+            node_name: "Perlin",
+        })
+        .fbm(0.5, 0.0, 3, 2.0);
+        let error = generator.try_build().unwrap_err();
+        let FastNoiseError::BuildFailed {
+            node,
+            member,
+            error,
+        } = &error
+        else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(node, "FractalFBm");
+        assert_eq!(member.as_deref(), Some("Source"));
+        assert!(matches!(**error, FastNoiseError::BuildFailed { .. }));
+
+        let error = generator.domain_scale(2.0).try_build().unwrap_err();
+        assert_eq!(error.path().as_deref(), Some("root.Source.Source"));
+        assert_eq!(error.node_type(), Some("Perlin"));
+        assert_eq!(error.kind(), crate::ErrorKind::NotFound);
+        assert!(matches!(
+            error.root_cause(),
+            FastNoiseError::MemberNameNotFound { .. }
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to build 'Perlin' member 'RenamedScale'")]
+    fn test_build_panics_with_error() {
+        Renamed {
+            node_name: "Perlin",
+        }
+        .build();
+    }
+
+    #[test]
+    fn test_try_build() {
+        assert!(perlin().try_build().is_ok());
+    }
+}
@@ -1,7 +1,14 @@
 use std::fmt::Display;
 
-use super::{Dimension, Generator, GeneratorWrapper, Hybrid};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, enum_member_value, Dimension, Generator, GeneratorFolder, GeneratorVisitor,
+    GeneratorWrapper, Hybrid, NodeBuilder, TypedFold, TypedVisit,
+};
+use crate::{
+    safe::SafeNode,
+    stats::{estimate_range, SampleRegion, NORMALIZATION_CLIP_PERCENT},
+    FastNoiseError, OutputMinMax,
+};
 
 /// Rotation type for DomainRotatePlane.
 #[derive(Clone, Debug, Default)]
@@ -20,6 +27,11 @@ impl Display for PlaneRotationType {
     }
 }
 
+enum_member_value!(PlaneRotationType {
+    ImproveXYPlanes,
+    ImproveXZPlanes,
+});
+
 #[derive(Clone, Debug)]
 pub struct DomainScale<S>
 where
@@ -183,16 +195,49 @@ where
     pub rotation_type: PlaneRotationType,
 }
 
+content_eq!(
+    DomainScale<S: Generator>,
+    DomainOffset<S: Generator, X: Hybrid, Y: Hybrid, Z: Hybrid, W: Hybrid>,
+    DomainRotate<S: Generator>,
+    SeedOffset<S: Generator>,
+    Remap<S: Generator>,
+    ConvertRgba8<S: Generator>,
+    Terrace<S: Generator, Sm: Hybrid>,
+    DomainAxisScale<S: Generator>,
+    AddDimension<S: Generator, N: Hybrid>,
+    RemoveDimension<S: Generator>,
+    GeneratorCache<S: Generator>,
+    PingPong<S: Generator, P: Hybrid>,
+    Abs<S: Generator>,
+    SignedSquareRoot<S: Generator>,
+    DomainRotatePlane<S: Generator>,
+);
+
 impl<S> Generator for DomainScale<S>
 where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainScale").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("Scaling", self.scaling).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainScale")?;
+        node.set("Source", &self.source)?;
+        node.set("Scaling", self.scaling)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainScale", visitor)
+            .node("Source", &self.source)
+            .variable("Scaling", &self.scaling)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainScale", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            scaling: fold.variable("Scaling", &self.scaling)?,
+        })
     }
 }
 
@@ -205,14 +250,35 @@ where
     W: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainOffset").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("OffsetX", self.offset_x.clone()).unwrap();
-        node.set("OffsetY", self.offset_y.clone()).unwrap();
-        node.set("OffsetZ", self.offset_z.clone()).unwrap();
-        node.set("OffsetW", self.offset_w.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainOffset")?;
+        node.set("Source", &self.source)?;
+        node.set("OffsetX", self.offset_x.clone())?;
+        node.set("OffsetY", self.offset_y.clone())?;
+        node.set("OffsetZ", self.offset_z.clone())?;
+        node.set("OffsetW", self.offset_w.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainOffset", visitor)
+            .node("Source", &self.source)
+            .hybrid("OffsetX", &self.offset_x)
+            .hybrid("OffsetY", &self.offset_y)
+            .hybrid("OffsetZ", &self.offset_z)
+            .hybrid("OffsetW", &self.offset_w)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainOffset", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            offset_x: fold.hybrid("OffsetX", &self.offset_x)?,
+            offset_y: fold.hybrid("OffsetY", &self.offset_y)?,
+            offset_z: fold.hybrid("OffsetZ", &self.offset_z)?,
+            offset_w: fold.hybrid("OffsetW", &self.offset_w)?,
+        })
     }
 }
 
@@ -221,13 +287,32 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainRotate").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("Yaw", self.yaw).unwrap();
-        node.set("Pitch", self.pitch).unwrap();
-        node.set("Roll", self.roll).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainRotate")?;
+        node.set("Source", &self.source)?;
+        node.set("Yaw", self.yaw)?;
+        node.set("Pitch", self.pitch)?;
+        node.set("Roll", self.roll)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainRotate", visitor)
+            .node("Source", &self.source)
+            .variable("Yaw", &self.yaw)
+            .variable("Pitch", &self.pitch)
+            .variable("Roll", &self.roll)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainRotate", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            yaw: fold.variable("Yaw", &self.yaw)?,
+            pitch: fold.variable("Pitch", &self.pitch)?,
+            roll: fold.variable("Roll", &self.roll)?,
+        })
     }
 }
 
@@ -236,11 +321,26 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("SeedOffset").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("SeedOffset", self.seed_offset).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("SeedOffset")?;
+        node.set("Source", &self.source)?;
+        node.set("SeedOffset", self.seed_offset)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("SeedOffset", visitor)
+            .node("Source", &self.source)
+            .variable("SeedOffset", &self.seed_offset)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("SeedOffset", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            seed_offset: fold.variable("SeedOffset", &self.seed_offset)?,
+        })
     }
 }
 
@@ -249,19 +349,38 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Remap").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("FromMin", self.from_min).unwrap();
-        node.set("FromMax", self.from_max).unwrap();
-        node.set("ToMin", self.to_min).unwrap();
-        node.set("ToMax", self.to_max).unwrap();
-        node.set(
-            "ClampOutput",
-            if self.clamp_output { "True" } else { "False" },
-        )
-        .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Remap")?;
+        node.set("Source", &self.source)?;
+        node.set("FromMin", self.from_min)?;
+        node.set("FromMax", self.from_max)?;
+        node.set("ToMin", self.to_min)?;
+        node.set("ToMax", self.to_max)?;
+        node.set("ClampOutput", self.clamp_output)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Remap", visitor)
+            .node("Source", &self.source)
+            .variable("FromMin", &self.from_min)
+            .variable("FromMax", &self.from_max)
+            .variable("ToMin", &self.to_min)
+            .variable("ToMax", &self.to_max)
+            .variable("ClampOutput", &self.clamp_output)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Remap", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            from_min: fold.variable("FromMin", &self.from_min)?,
+            from_max: fold.variable("FromMax", &self.from_max)?,
+            to_min: fold.variable("ToMin", &self.to_min)?,
+            to_max: fold.variable("ToMax", &self.to_max)?,
+            clamp_output: fold.variable("ClampOutput", &self.clamp_output)?,
+        })
     }
 }
 
@@ -270,12 +389,29 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("ConvertRgba8").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("Min", self.min).unwrap();
-        node.set("Max", self.max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("ConvertRgba8")?;
+        node.set("Source", &self.source)?;
+        node.set("Min", self.min)?;
+        node.set("Max", self.max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("ConvertRgba8", visitor)
+            .node("Source", &self.source)
+            .variable("Min", &self.min)
+            .variable("Max", &self.max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("ConvertRgba8", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            min: fold.variable("Min", &self.min)?,
+            max: fold.variable("Max", &self.max)?,
+        })
     }
 }
 
@@ -285,12 +421,29 @@ where
     Sm: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Terrace").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("StepCount", self.step_count).unwrap();
-        node.set("Smoothness", self.smoothness.clone()).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Terrace")?;
+        node.set("Source", &self.source)?;
+        node.set("StepCount", self.step_count)?;
+        node.set("Smoothness", self.smoothness.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Terrace", visitor)
+            .node("Source", &self.source)
+            .hybrid("Smoothness", &self.smoothness)
+            .variable("StepCount", &self.step_count)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Terrace", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            smoothness: fold.hybrid("Smoothness", &self.smoothness)?,
+            step_count: fold.variable("StepCount", &self.step_count)?,
+        })
     }
 }
 
@@ -299,14 +452,35 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainAxisScale").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("ScalingX", self.scaling_x).unwrap();
-        node.set("ScalingY", self.scaling_y).unwrap();
-        node.set("ScalingZ", self.scaling_z).unwrap();
-        node.set("ScalingW", self.scaling_w).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainAxisScale")?;
+        node.set("Source", &self.source)?;
+        node.set("ScalingX", self.scaling_x)?;
+        node.set("ScalingY", self.scaling_y)?;
+        node.set("ScalingZ", self.scaling_z)?;
+        node.set("ScalingW", self.scaling_w)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainAxisScale", visitor)
+            .node("Source", &self.source)
+            .variable("ScalingX", &self.scaling_x)
+            .variable("ScalingY", &self.scaling_y)
+            .variable("ScalingZ", &self.scaling_z)
+            .variable("ScalingW", &self.scaling_w)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainAxisScale", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            scaling_x: fold.variable("ScalingX", &self.scaling_x)?,
+            scaling_y: fold.variable("ScalingY", &self.scaling_y)?,
+            scaling_z: fold.variable("ScalingZ", &self.scaling_z)?,
+            scaling_w: fold.variable("ScalingW", &self.scaling_w)?,
+        })
     }
 }
 
@@ -316,12 +490,27 @@ where
     N: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("AddDimension").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("NewDimensionPosition", self.new_dimension_position.clone())
-            .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("AddDimension")?;
+        node.set("Source", &self.source)?;
+        node.set("NewDimensionPosition", self.new_dimension_position.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("AddDimension", visitor)
+            .node("Source", &self.source)
+            .hybrid("NewDimensionPosition", &self.new_dimension_position)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("AddDimension", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            new_dimension_position: fold
+                .hybrid("NewDimensionPosition", &self.new_dimension_position)?,
+        })
     }
 }
 
@@ -330,12 +519,26 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("RemoveDimension").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("RemoveDimension", &*self.remove_dimension.to_string())
-            .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("RemoveDimension")?;
+        node.set("Source", &self.source)?;
+        node.set("RemoveDimension", &self.remove_dimension)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("RemoveDimension", visitor)
+            .node("Source", &self.source)
+            .variable("RemoveDimension", &self.remove_dimension)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("RemoveDimension", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            remove_dimension: fold.variable("RemoveDimension", &self.remove_dimension)?,
+        })
     }
 }
 
@@ -344,10 +547,23 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("GeneratorCache").unwrap();
-        node.set("Source", &self.source).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("GeneratorCache")?;
+        node.set("Source", &self.source)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("GeneratorCache", visitor)
+            .node("Source", &self.source)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("GeneratorCache", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+        })
     }
 }
 
@@ -357,12 +573,26 @@ where
     P: Hybrid,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("PingPong").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("PingPongStrength", self.ping_pong_strength.clone())
-            .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("PingPong")?;
+        node.set("Source", &self.source)?;
+        node.set("PingPongStrength", self.ping_pong_strength.clone())?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("PingPong", visitor)
+            .node("Source", &self.source)
+            .hybrid("PingPongStrength", &self.ping_pong_strength)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("PingPong", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            ping_pong_strength: fold.hybrid("PingPongStrength", &self.ping_pong_strength)?,
+        })
     }
 }
 
@@ -371,10 +601,23 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Abs").unwrap();
-        node.set("Source", &self.source).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Abs")?;
+        node.set("Source", &self.source)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Abs", visitor)
+            .node("Source", &self.source)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Abs", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+        })
     }
 }
 
@@ -383,10 +626,23 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("SignedSquareRoot").unwrap();
-        node.set("Source", &self.source).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("SignedSquareRoot")?;
+        node.set("Source", &self.source)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("SignedSquareRoot", visitor)
+            .node("Source", &self.source)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("SignedSquareRoot", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+        })
     }
 }
 
@@ -395,12 +651,26 @@ where
     S: Generator,
 {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("DomainRotatePlane").unwrap();
-        node.set("Source", &self.source).unwrap();
-        node.set("RotationType", &*self.rotation_type.to_string())
-            .unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("DomainRotatePlane")?;
+        node.set("Source", &self.source)?;
+        node.set("RotationType", &self.rotation_type)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("DomainRotatePlane", visitor)
+            .node("Source", &self.source)
+            .variable("RotationType", &self.rotation_type)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("DomainRotatePlane", folder);
+        Ok(Self {
+            source: fold.node("Source", &self.source)?,
+            rotation_type: fold.variable("RotationType", &self.rotation_type)?,
+        })
     }
 }
 
@@ -501,6 +771,46 @@ where
         .into()
     }
 
+    /// Remaps output to `-1.0..=1.0` using its range estimated over `sample_region`, see [`normalized_to`](Self::normalized_to).
+    pub fn normalized(
+        self,
+        sample_region: &SampleRegion,
+        seed_count: i32,
+    ) -> (GeneratorWrapper<Remap<S>>, OutputMinMax) {
+        self.normalized_to(sample_region, seed_count, -1.0, 1.0)
+    }
+
+    /// Remaps output to `to_min..=to_max` using its range estimated over `sample_region`.
+    ///
+    /// The tree is built and sampled with seeds `0..seed_count`, and [`NORMALIZATION_CLIP_PERCENT`] % of the samples
+    /// are clipped at each end so rare extremes don't flatten the output. Those extremes are clamped to the target
+    /// range. Returns the wrapped generator along with the estimated source range.
+    ///
+    /// # Panics
+    /// Panics if `seed_count` is not positive or if the tree produces no finite sample over `sample_region`.
+    pub fn normalized_to(
+        self,
+        sample_region: &SampleRegion,
+        seed_count: i32,
+        to_min: f32,
+        to_max: f32,
+    ) -> (GeneratorWrapper<Remap<S>>, OutputMinMax) {
+        let range = estimate_range(
+            &self.build(),
+            sample_region,
+            seed_count,
+            NORMALIZATION_CLIP_PERCENT,
+        );
+        // A constant output has no range to stretch, widen it so it lands in the middle of the target range.
+        let (from_min, from_max) = if range.max > range.min {
+            (range.min, range.max)
+        } else {
+            (range.min - 1.0, range.max + 1.0)
+        };
+        let generator = self.remap_clamped(from_min, from_max, to_min, to_max, true);
+        (generator, range)
+    }
+
     pub fn convert_rgba8(self, min: f32, max: f32) -> GeneratorWrapper<ConvertRgba8<S>> {
         ConvertRgba8 {
             source: self.0,
@@ -644,6 +954,20 @@ mod tests {
         test_generator_produces_output(node.0);
     }
 
+    #[test]
+    fn test_normalized() {
+        let region = SampleRegion::grid_2d([0.0, 0.0], [64, 64], 4.0);
+        let (generator, range) = perlin()
+            .fbm(0.5, 0.0, 4, 2.0)
+            .normalized_to(&region, 2, 0.0, 1.0);
+        assert!(range.min < range.max);
+        assert_eq!(generator.from_min, range.min);
+        assert_eq!(generator.from_max, range.max);
+
+        let output = generate_output(&generator.build());
+        assert!(output.iter().all(|v| (0.0..=1.0).contains(v)));
+    }
+
     #[test]
     fn test_remap_clamped() {
         let node = perlin().remap_clamped(-1.0, 1.0, 0.0, 1.0, true).build();
@@ -1,6 +1,9 @@
-use crate::{safe::SafeNode, Node};
+use crate::{safe::SafeNode, FastNoiseError};
 
-use super::{Generator, GeneratorWrapper};
+use super::{
+    content_eq, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper, NodeBuilder,
+    TypedFold, TypedVisit,
+};
 
 /// Perlin gradient noise.
 /// Smooth gradient noise from N dimensional grid, developed by Ken Perlin in 1983.
@@ -27,15 +30,36 @@ impl Default for Perlin {
     }
 }
 
+content_eq!(Perlin);
+
 impl Generator for Perlin {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Perlin").unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set("SeedOffset", self.seed_offset).unwrap();
-        node.set("OutputMin", self.output_min).unwrap();
-        node.set("OutputMax", self.output_max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Perlin")?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("SeedOffset", self.seed_offset)?;
+        node.set("OutputMin", self.output_min)?;
+        node.set("OutputMax", self.output_max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Perlin", visitor)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("SeedOffset", &self.seed_offset)
+            .variable("OutputMin", &self.output_min)
+            .variable("OutputMax", &self.output_max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Perlin", folder);
+        Ok(Self {
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            seed_offset: fold.variable("SeedOffset", &self.seed_offset)?,
+            output_min: fold.variable("OutputMin", &self.output_min)?,
+            output_max: fold.variable("OutputMax", &self.output_max)?,
+        })
     }
 }
 
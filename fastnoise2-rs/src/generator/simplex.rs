@@ -1,5 +1,8 @@
-use super::{Generator, GeneratorWrapper};
-use crate::{safe::SafeNode, Node};
+use super::{
+    content_eq, Generator, GeneratorFolder, GeneratorVisitor, GeneratorWrapper, NodeBuilder,
+    TypedFold, TypedVisit,
+};
+use crate::{safe::SafeNode, FastNoiseError};
 
 /// Simplex noise generator.
 #[derive(Clone, Debug)]
@@ -49,27 +52,67 @@ impl Default for SuperSimplex {
     }
 }
 
+content_eq!(Simplex, SuperSimplex);
+
 impl Generator for Simplex {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("Simplex").unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set("SeedOffset", self.seed_offset).unwrap();
-        node.set("OutputMin", self.output_min).unwrap();
-        node.set("OutputMax", self.output_max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("Simplex")?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("SeedOffset", self.seed_offset)?;
+        node.set("OutputMin", self.output_min)?;
+        node.set("OutputMax", self.output_max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("Simplex", visitor)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("SeedOffset", &self.seed_offset)
+            .variable("OutputMin", &self.output_min)
+            .variable("OutputMax", &self.output_max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("Simplex", folder);
+        Ok(Self {
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            seed_offset: fold.variable("SeedOffset", &self.seed_offset)?,
+            output_min: fold.variable("OutputMin", &self.output_min)?,
+            output_max: fold.variable("OutputMax", &self.output_max)?,
+        })
     }
 }
 
 impl Generator for SuperSimplex {
     #[cfg_attr(feature = "trace", tracing::instrument(level = "trace"))]
-    fn build(&self) -> GeneratorWrapper<SafeNode> {
-        let mut node = Node::from_name("SuperSimplex").unwrap();
-        node.set("FeatureScale", self.feature_scale).unwrap();
-        node.set("SeedOffset", self.seed_offset).unwrap();
-        node.set("OutputMin", self.output_min).unwrap();
-        node.set("OutputMax", self.output_max).unwrap();
-        SafeNode(node.into()).into()
+    fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+        let mut node = NodeBuilder::new("SuperSimplex")?;
+        node.set("FeatureScale", self.feature_scale)?;
+        node.set("SeedOffset", self.seed_offset)?;
+        node.set("OutputMin", self.output_min)?;
+        node.set("OutputMax", self.output_max)?;
+        Ok(node.build())
+    }
+
+    fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        TypedVisit::new("SuperSimplex", visitor)
+            .variable("FeatureScale", &self.feature_scale)
+            .variable("SeedOffset", &self.seed_offset)
+            .variable("OutputMin", &self.output_min)
+            .variable("OutputMax", &self.output_max)
+            .leave();
+    }
+
+    fn fold<F: GeneratorFolder + ?Sized>(&self, folder: &mut F) -> Result<Self, FastNoiseError> {
+        let mut fold = TypedFold::new("SuperSimplex", folder);
+        Ok(Self {
+            feature_scale: fold.variable("FeatureScale", &self.feature_scale)?,
+            seed_offset: fold.variable("SeedOffset", &self.seed_offset)?,
+            output_min: fold.variable("OutputMin", &self.output_min)?,
+            output_max: fold.variable("OutputMax", &self.output_max)?,
+        })
     }
 }
 
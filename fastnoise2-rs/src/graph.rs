@@ -0,0 +1,500 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Write},
+};
+
+use crate::{
+    codegen::{field_name, ordered_members, variant_name},
+    generator::Generator,
+    metadata::{format_lookup, Member, RecordedValue, METADATA_NAME_LOOKUP, NODE_METADATA},
+    GeneratorVisitor, MemberRef, MemberType, Node, SafeNode,
+};
+
+impl SafeNode {
+    /// Returns the tree as a [Graphviz](https://graphviz.org) DOT graph, e.g. to review changes to a tree.
+    ///
+    /// Nodes are labelled with their metadata name and the values of their members that differ from the defaults of
+    /// the typed generators, and edges with the member they are set on, e.g. `Source`. Nodes shared by several
+    /// branches appear once. Members of trees decoded from an encoded node tree can't be inspected, so only their root
+    /// is shown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fastnoise2::generator::prelude::*;
+    ///
+    /// let node = perlin().fbm(0.5, 0.0, 3, 2.0).build();
+    /// let dot = node.to_dot();
+    /// assert!(dot.contains("n0 -> n1 [label=\"Source\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let graph = Graph::new(&self.0);
+        let mut out = String::from("digraph {\n    node [shape=box];\n");
+        for (id, node) in graph.nodes.iter().enumerate() {
+            let label = node.lines().join("\\n");
+            let _ = writeln!(out, "    n{id} [label=\"{}\"];", escape_dot(&label));
+        }
+        for edge in &graph.edges {
+            let _ = writeln!(
+                out,
+                "    n{} -> n{} [label=\"{}\"];",
+                edge.from,
+                edge.to,
+                escape_dot(&edge.member)
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Returns the tree as a [Mermaid](https://mermaid.js.org) flowchart, which renders in GitHub comments and
+    /// Markdown files. See [`SafeNode::to_dot`] for the labels.
+    pub fn to_mermaid(&self) -> String {
+        let graph = Graph::new(&self.0);
+        let mut out = String::from("flowchart TD\n");
+        for (id, node) in graph.nodes.iter().enumerate() {
+            let label = node
+                .lines()
+                .iter()
+                .map(|line| escape_mermaid(line))
+                .collect::<Vec<_>>()
+                .join("<br/>");
+            let _ = writeln!(out, "    n{id}[\"{label}\"]");
+        }
+        for edge in &graph.edges {
+            let _ = writeln!(
+                out,
+                "    n{} -->|\"{}\"| n{}",
+                edge.from,
+                escape_mermaid(&edge.member),
+                edge.to
+            );
+        }
+        out
+    }
+
+    /// Returns a printer writing the tree indented, one node per line, with the values of its members that differ
+    /// from the defaults of the typed generators. [`SafeNode`] implements [`Display`] with it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fastnoise2::generator::prelude::*;
+    ///
+    /// let node = perlin().with_feature_scale(50.0).fbm(0.5, 0.0, 3, 2.0).build();
+    /// assert_eq!(
+    ///     node.pretty().to_string(),
+    ///     "\
+    /// FractalFBm { gain: 0.5, weighted_strength: 0.0, octaves: 3, lacunarity: 2.0 }
+    ///   source: Perlin { feature_scale: 50.0 }
+    /// "
+    /// );
+    /// ```
+    pub fn pretty(&self) -> PrettyTree {
+        PrettyTree::of(self)
+    }
+}
+
+impl Display for SafeNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pretty().fmt(f)
+    }
+}
+
+/// Indented view of a tree, returned by [`SafeNode::pretty`] and
+/// [`Generator::pretty`][crate::generator::Generator::pretty].
+///
+/// The tree is read once with [`Generator::visit`], so typed generators are printed from their fields without
+/// building them. Members are named like the fields of the typed generators, e.g. `feature_scale`, or like FastNoise2
+/// with [`PrettyTree::member_names`], e.g. `FeatureScale`. Nodes used several times in the tree are written each
+/// time.
+#[derive(Clone, Debug)]
+pub struct PrettyTree {
+    root: Option<PrettyNode>,
+    member_names: bool,
+}
+
+/// Node of a [`PrettyTree`].
+#[derive(Clone, Debug)]
+struct PrettyNode {
+    name: String,
+    encoded: bool,
+    /// Members holding a value that differs from the defaults of the typed generators.
+    params: Vec<(&'static Member, RecordedValue)>,
+    children: Vec<(&'static Member, PrettyNode)>,
+}
+
+impl PrettyTree {
+    pub(crate) fn of<G: Generator>(generator: &G) -> Self {
+        let mut reader = PrettyReader::default();
+        generator.visit(&mut reader);
+        Self {
+            root: reader.root,
+            member_names: false,
+        }
+    }
+
+    /// Names members and enum values like FastNoise2, as used by [`Node::set`] and the Node Editor.
+    pub fn member_names(mut self, member_names: bool) -> Self {
+        self.member_names = member_names;
+        self
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, node: &PrettyNode, depth: usize) -> fmt::Result {
+        f.write_str(&node.name)?;
+        if node.encoded {
+            f.write_str(" (encoded node tree)")?;
+        }
+        let params: Vec<String> = node
+            .params
+            .iter()
+            .map(|(member, value)| {
+                let value = match value {
+                    // `False`/`True` enums are `bool` fields.
+                    RecordedValue::Int(_) if !self.member_names => {
+                        format_value(member, value, |name| match name {
+                            "False" | "True" => name.to_lowercase(),
+                            _ => variant_name(name),
+                        })
+                    }
+                    _ => format_value(member, value, str::to_string),
+                };
+                format!("{}: {value}", self.name(member))
+            })
+            .collect();
+        if !params.is_empty() {
+            write!(f, " {{ {} }}", params.join(", "))?;
+        }
+        writeln!(f)?;
+        for (member, child) in &node.children {
+            write!(f, "{}{}: ", "  ".repeat(depth + 1), self.name(member))?;
+            self.write(f, child, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    fn name(&self, member: &Member) -> String {
+        if self.member_names {
+            member.set_name()
+        } else {
+            field_name(member)
+        }
+    }
+}
+
+impl Display for PrettyTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.root {
+            Some(root) => self.write(f, root, 0),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Reads a [`PrettyTree`] from the visited nodes and members.
+#[derive(Default)]
+struct PrettyReader {
+    /// Nodes being visited, each with the member holding its next subtree.
+    stack: Vec<(PrettyNode, Option<&'static Member>)>,
+    root: Option<PrettyNode>,
+}
+
+impl GeneratorVisitor for PrettyReader {
+    fn enter_node(&mut self, name: &str) -> bool {
+        let name = METADATA_NAME_LOOKUP
+            .get(&format_lookup(name))
+            .map_or(name, |&id| &NODE_METADATA[id as usize].display_name);
+        let node = PrettyNode {
+            name: name.to_string(),
+            encoded: false,
+            params: Vec::new(),
+            children: Vec::new(),
+        };
+        self.stack.push((node, None));
+        true
+    }
+
+    fn visit_encoded(&mut self, _encoded: &str) {
+        if let Some((node, _)) = self.stack.last_mut() {
+            node.encoded = true;
+        }
+    }
+
+    fn visit_member(&mut self, member: &str, _member_type: MemberType, value: MemberRef<'_>) {
+        let Some((node, subtree_member)) = self.stack.last_mut() else {
+            return;
+        };
+        let Some(member) = METADATA_NAME_LOOKUP
+            .get(&format_lookup(&node.name))
+            .and_then(|&id| {
+                NODE_METADATA[id as usize]
+                    .members
+                    .get(&format_lookup(member))
+            })
+        else {
+            return;
+        };
+        let value = match value {
+            MemberRef::Node => {
+                *subtree_member = Some(member);
+                return;
+            }
+            MemberRef::Float(value) => RecordedValue::Float(value),
+            MemberRef::Int(value) => RecordedValue::Int(value),
+            MemberRef::Enum(name) => match member.enum_names.get(&format_lookup(name)) {
+                Some(&index) => RecordedValue::Int(index),
+                None => return,
+            },
+        };
+        if !member.is_default(Some(&value)) {
+            node.params.push((member, value));
+        }
+    }
+
+    fn leave_node(&mut self, _name: &str) {
+        let Some((node, _)) = self.stack.pop() else {
+            return;
+        };
+        match self.stack.last_mut() {
+            Some((parent, subtree_member)) => {
+                if let Some(member) = subtree_member.take() {
+                    parent.children.push((member, node));
+                }
+            }
+            None => self.root = Some(node),
+        }
+    }
+}
+
+/// Members of `node` holding a value that differs from the defaults of the typed generators.
+fn params(node: &Node) -> impl Iterator<Item = (&'static Member, &RecordedValue)> {
+    members(node).filter(|(member, value)| {
+        !matches!(value, RecordedValue::Node(_)) && !member.is_default(Some(value))
+    })
+}
+
+/// Children of `node`, with the member they are set on.
+fn children(node: &Node) -> impl Iterator<Item = (&'static Member, &Node)> {
+    members(node).filter_map(|(member, value)| match value {
+        RecordedValue::Node(child) => Some((member, &**child)),
+        _ => None,
+    })
+}
+
+/// Members set on `node`, node lookups first, then hybrids and variables.
+fn members(node: &Node) -> impl Iterator<Item = (&'static Member, &RecordedValue)> {
+    ordered_members(&NODE_METADATA[node.metadata_id as usize])
+        .into_iter()
+        .filter_map(|member| Some((member, node.members.get(&member.slot())?)))
+}
+
+/// Nodes of a tree, each listed once in depth-first order, and the members linking them.
+struct Graph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<Edge>,
+}
+
+struct GraphNode {
+    name: &'static str,
+    /// Non-default member values, e.g. `Gain = 0.5`.
+    params: Vec<String>,
+    encoded: bool,
+}
+
+struct Edge {
+    from: usize,
+    to: usize,
+    member: String,
+}
+
+impl Graph {
+    fn new(root: &Node) -> Self {
+        let mut graph = Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        graph.add(root, &mut HashMap::new());
+        graph
+    }
+
+    /// Adds `node` and its children unless it was already added, and returns its index.
+    fn add(&mut self, node: &Node, ids: &mut HashMap<*const Node, usize>) -> usize {
+        if let Some(&id) = ids.get(&(node as *const Node)) {
+            return id;
+        }
+        let id = self.nodes.len();
+        ids.insert(node, id);
+        let metadata = &NODE_METADATA[node.metadata_id as usize];
+        self.nodes.push(GraphNode {
+            name: &metadata.display_name,
+            params: Vec::new(),
+            encoded: node.encoded.is_some(),
+        });
+
+        for (member, child) in children(node) {
+            // List the edge before those of the child.
+            let edge = self.edges.len();
+            self.edges.push(Edge {
+                from: id,
+                to: 0,
+                member: member.set_name(),
+            });
+            self.edges[edge].to = self.add(child, ids);
+        }
+        self.nodes[id].params = params(node)
+            .map(|(member, value)| {
+                let value = format_value(member, value, str::to_string);
+                format!("{} = {value}", member.set_name())
+            })
+            .collect();
+        id
+    }
+}
+
+impl GraphNode {
+    fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.name.to_string()];
+        if self.encoded {
+            lines.push("(encoded node tree)".to_string());
+        }
+        lines.extend(self.params.iter().cloned());
+        lines
+    }
+}
+
+/// Formats a float or integer, or an enum value with `variant` applied to its FastNoise2 name.
+fn format_value(member: &Member, value: &RecordedValue, variant: fn(&str) -> String) -> String {
+    match value {
+        RecordedValue::Float(value) => format!("{value:?}"),
+        RecordedValue::Int(value) if member.enum_display_names.is_empty() => value.to_string(),
+        RecordedValue::Int(index) => usize::try_from(*index)
+            .ok()
+            .and_then(|index| member.enum_display_names.get(index))
+            .map_or_else(|| index.to_string(), |name| variant(name)),
+        RecordedValue::Node(_) => unreachable!("nodes are children"),
+    }
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
+
+fn escape_mermaid(label: &str) -> String {
+    label
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generator::{prelude::*, DistanceFunction},
+        FastNoiseError, GeneratorVisitor, Node, SafeNode,
+    };
+
+    #[test]
+    fn test_to_dot() {
+        let node = (perlin().with_feature_scale(50.0).fbm(0.5, 0.0, 3, 2.0)
+            + gradient().with_multiplier_x(2.0))
+        .build();
+        assert_eq!(
+            node.to_dot(),
+            "\
+digraph {
+    node [shape=box];
+    n0 [label=\"Add\"];
+    n1 [label=\"FractalFBm\\nGain = 0.5\\nWeightedStrength = 0.0\\nOctaves = 3\\nLacunarity = 2.0\"];
+    n2 [label=\"Perlin\\nFeatureScale = 50.0\"];
+    n3 [label=\"Gradient\\nMultiplierX = 2.0\"];
+    n0 -> n1 [label=\"LHS\"];
+    n1 -> n2 [label=\"Source\"];
+    n0 -> n3 [label=\"RHS\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let node = cellular_value(1.0, DistanceFunction::Manhattan, 0)
+            .max(1.0)
+            .build();
+        assert_eq!(
+            node.to_mermaid(),
+            "\
+flowchart TD
+    n0[\"Max<br/>RHS = 1.0\"]
+    n1[\"CellularValue<br/>GridJitter = 1.0<br/>DistanceFunction = Manhattan<br/>ValueIndex = 0\"]
+    n0 -->|\"LHS\"| n1
+"
+        );
+    }
+
+    #[test]
+    fn test_shared_node() {
+        let source = perlin().domain_scale(2.0);
+        let dot = (source.clone() * source).to_dot();
+        assert_eq!(dot.matches("[label=\"DomainScale").count(), 1);
+        assert!(dot.contains("n0 -> n1 [label=\"LHS\"];"));
+        assert!(dot.contains("n0 -> n1 [label=\"RHS\"];"));
+    }
+
+    #[test]
+    fn test_pretty() {
+        let tree = cellular_lookup(
+            perlin().with_seed_offset(2),
+            1.0,
+            DistanceFunction::Euclidean,
+        )
+        .remap_clamped(-1.0, 1.0, 0.0, 1.0, true);
+        let expected = "\
+Remap { from_min: -1.0, from_max: 1.0, to_min: 0.0, to_max: 1.0, clamp_output: true }
+  source: CellularLookup { grid_jitter: 1.0, distance_function: Euclidean }
+    lookup: Perlin { seed_offset: 2 }
+";
+        assert_eq!(tree.to_string(), expected);
+        assert_eq!(tree.build().to_string(), expected);
+        assert_eq!(
+            tree.pretty().member_names(true).to_string(),
+            "\
+Remap { FromMin: -1.0, FromMax: 1.0, ToMin: 0.0, ToMax: 1.0, ClampOutput: True }
+  Source: CellularLookup { GridJitter: 1.0, DistanceFunction: Euclidean }
+    Lookup: Perlin { SeedOffset: 2 }
+"
+        );
+    }
+
+    #[test]
+    fn test_pretty_unbuilt() {
+        /// Generator that fails to build, printed from its visit.
+        #[derive(Clone, Debug)]
+        struct Unbuildable;
+
+        impl Generator for Unbuildable {
+            fn try_build(&self) -> Result<GeneratorWrapper<SafeNode>, FastNoiseError> {
+                Err(FastNoiseError::NodeCreationFailed)
+            }
+
+            fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+                perlin().with_feature_scale(50.0).visit(visitor);
+            }
+        }
+
+        assert_eq!(
+            GeneratorWrapper(Unbuildable).abs().to_string(),
+            "Abs\n  source: Perlin { feature_scale: 50.0 }\n"
+        );
+    }
+
+    #[test]
+    fn test_pretty_encoded() {
+        let mut encoded = Node::from_name("Constant").unwrap();
+        encoded.encoded = Some("EQACAAAAAAAg".into());
+        let tree = GeneratorWrapper(SafeNode(encoded.into())).abs();
+        assert_eq!(
+            tree.to_string(),
+            "Abs\n  source: Constant (encoded node tree)\n"
+        );
+    }
+}
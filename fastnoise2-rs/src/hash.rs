@@ -0,0 +1,454 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{
+    generator::Generator,
+    metadata::{format_lookup, Member, RecordedValue, NODE_METADATA},
+    GeneratorVisitor, MemberRef, MemberType, Node, SafeNode,
+};
+
+/// Content hash of a tree, returned by [`SafeNode::content_hash`].
+///
+/// It is computed with 128-bit FNV-1a from the names of the nodes and members, and the values of the members, so it
+/// doesn't depend on the compiler, the platform, the SIMD level or the process, and can be stored, e.g. as the key of
+/// baked tiles. Names are hashed lowercase without spaces, like [`crate::Node::set`] matches them. It changes if
+/// FastNoise2 renames a node, a member or an enum value of the tree.
+///
+/// A typed generator hashes like the tree it builds, see [`Generator::content_hash`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ContentHash(pub u128);
+
+impl fmt::Display for ContentHash {
+    /// Writes the hash as 32 lowercase hexadecimal digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+impl SafeNode {
+    /// Returns a hash of the content of the tree: its nodes and the values set on their members, floats compared by
+    /// bits. Trees equal by [`PartialEq`] have the same hash.
+    ///
+    /// Trees decoded from an encoded node tree are hashed by their encoded string, along with the members set
+    /// afterwards. A member never set doesn't hash like a member set to its default value, but typed generators set
+    /// all their members.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fastnoise2::generator::prelude::*;
+    ///
+    /// let a = perlin().fbm(0.5, 0.0, 3, 2.0).build();
+    /// let b = perlin().fbm(0.5, 0.0, 3, 2.0).build();
+    /// assert_eq!(a.content_hash(), b.content_hash());
+    /// assert_ne!(a.content_hash(), perlin().fbm(0.5, 0.0, 4, 2.0).build().content_hash());
+    /// ```
+    pub fn content_hash(&self) -> ContentHash {
+        ContentHash(node_hash(&self.0, &mut HashMap::new()))
+    }
+}
+
+/// Compares trees by content, see [`SafeNode::content_hash`]. Clones of a tree are equal, and so are trees built
+/// separately from the same generator or decoded from the same encoded node tree. Typed generators compare the same
+/// way without building, see [`Generator::same_tree`].
+impl PartialEq for SafeNode {
+    fn eq(&self, other: &Self) -> bool {
+        node_eq(&self.0, &other.0, &mut HashSet::new())
+    }
+}
+
+impl Eq for SafeNode {}
+
+impl Hash for SafeNode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.content_hash().hash(state);
+    }
+}
+
+/// 128-bit FNV-1a.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u128::from(byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes a length-prefixed string, so that consecutive strings can't be confused.
+    fn write_str(&mut self, value: &str) {
+        self.write(&(value.len() as u64).to_le_bytes());
+        self.write(value.as_bytes());
+    }
+}
+
+/// Hashes `node`, with the hashes of its children. Shared subtrees are hashed once.
+fn node_hash(node: &Node, hashes: &mut HashMap<*const Node, u128>) -> u128 {
+    if let Some(&hash) = hashes.get(&(node as *const Node)) {
+        return hash;
+    }
+    let mut hasher = Fnv128::new();
+    hasher.write_str(&format_lookup(
+        &NODE_METADATA[node.metadata_id as usize].display_name,
+    ));
+    match &node.encoded {
+        Some(encoded) => {
+            hasher.write(b"E");
+            hasher.write_str(encoded);
+        }
+        None => hasher.write(b"N"),
+    }
+    for (member, value) in members(node) {
+        hasher.write_str(&format_lookup(&member.set_name()));
+        match value {
+            RecordedValue::Float(value) => {
+                hasher.write(b"F");
+                hasher.write(&value.to_bits().to_le_bytes());
+            }
+            RecordedValue::Int(value) if member.enum_display_names.is_empty() => {
+                hasher.write(b"I");
+                hasher.write(&value.to_le_bytes());
+            }
+            // Enum values are hashed by name, their index may change between FastNoise2 versions.
+            RecordedValue::Int(index) => {
+                hasher.write(b"V");
+                let name = usize::try_from(*index)
+                    .ok()
+                    .and_then(|index| member.enum_display_names.get(index));
+                hasher.write_str(&name.map_or(String::new(), |name| format_lookup(name)));
+            }
+            RecordedValue::Node(child) => {
+                hasher.write(b"C");
+                hasher.write(&node_hash(child, hashes).to_le_bytes());
+            }
+        }
+    }
+    hashes.insert(node, hasher.0);
+    hasher.0
+}
+
+/// Compares trees by content. Pairs of subtrees already found equal are skipped.
+fn node_eq(a: &Node, b: &Node, equal: &mut HashSet<(*const Node, *const Node)>) -> bool {
+    if std::ptr::eq(a, b) || equal.contains(&(a as *const Node, b as *const Node)) {
+        return true;
+    }
+    let same = a.metadata_id == b.metadata_id
+        && a.encoded == b.encoded
+        && a.members.len() == b.members.len()
+        && a.members
+            .iter()
+            .all(|(slot, a_value)| match (a_value, b.members.get(slot)) {
+                (RecordedValue::Float(a), Some(RecordedValue::Float(b))) => {
+                    a.to_bits() == b.to_bits()
+                }
+                (RecordedValue::Int(a), Some(RecordedValue::Int(b))) => a == b,
+                (RecordedValue::Node(a), Some(RecordedValue::Node(b))) => {
+                    Arc::ptr_eq(a, b) || node_eq(a, b, equal)
+                }
+                _ => false,
+            });
+    if same {
+        equal.insert((a, b));
+    }
+    same
+}
+
+/// Members set on `node`, sorted by name so that the hash doesn't depend on their order in the metadata.
+fn members(node: &Node) -> Vec<(&'static Member, &RecordedValue)> {
+    let metadata = &NODE_METADATA[node.metadata_id as usize];
+    let mut members: Vec<_> = metadata
+        .members
+        .values()
+        .filter_map(|member| Some((member, node.members.get(&member.slot())?)))
+        .collect();
+    members.sort_by_key(|(member, _)| format_lookup(&member.set_name()));
+    members
+}
+
+/// Content of a tree collected by [`ContentVisitor`], compared and hashed like [`SafeNode`] without building it.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Content {
+    /// Name of the node, normalized with [`format_lookup`].
+    name: String,
+    encoded: Option<String>,
+    /// Members by normalized name, sorted.
+    members: Vec<(String, ContentValue)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ContentValue {
+    /// Bits of the float.
+    Float(u32),
+    Int(i32),
+    /// Normalized name of the enum value.
+    Enum(String),
+    Node(Content),
+}
+
+impl Content {
+    /// Collects the content of `generator` with [`Generator::visit`].
+    pub(crate) fn of<G: Generator>(generator: &G) -> Self {
+        let mut visitor = ContentVisitor::default();
+        generator.visit(&mut visitor);
+        visitor.root.unwrap_or_default()
+    }
+
+    /// Same hash as [`node_hash`] for the tree built from the generator.
+    pub(crate) fn hash(&self) -> ContentHash {
+        ContentHash(self.hash_node())
+    }
+
+    fn hash_node(&self) -> u128 {
+        let mut hasher = Fnv128::new();
+        hasher.write_str(&self.name);
+        match &self.encoded {
+            Some(encoded) => {
+                hasher.write(b"E");
+                hasher.write_str(encoded);
+            }
+            None => hasher.write(b"N"),
+        }
+        for (member, value) in &self.members {
+            hasher.write_str(member);
+            match value {
+                ContentValue::Float(bits) => {
+                    hasher.write(b"F");
+                    hasher.write(&bits.to_le_bytes());
+                }
+                ContentValue::Int(value) => {
+                    hasher.write(b"I");
+                    hasher.write(&value.to_le_bytes());
+                }
+                ContentValue::Enum(name) => {
+                    hasher.write(b"V");
+                    hasher.write_str(name);
+                }
+                ContentValue::Node(child) => {
+                    hasher.write(b"C");
+                    hasher.write(&child.hash_node().to_le_bytes());
+                }
+            }
+        }
+        hasher.0
+    }
+}
+
+/// Collects the [`Content`] of a tree, keeping the nodes being visited on a stack.
+#[derive(Default)]
+struct ContentVisitor {
+    /// Nodes being visited, with the member holding the next subtree.
+    stack: Vec<(Content, Option<String>)>,
+    root: Option<Content>,
+}
+
+impl GeneratorVisitor for ContentVisitor {
+    fn enter_node(&mut self, name: &str) -> bool {
+        let content = Content {
+            name: format_lookup(name),
+            ..Content::default()
+        };
+        self.stack.push((content, None));
+        true
+    }
+
+    fn visit_encoded(&mut self, encoded: &str) {
+        if let Some((content, _)) = self.stack.last_mut() {
+            content.encoded = Some(encoded.to_string());
+        }
+    }
+
+    fn visit_member(&mut self, member: &str, _member_type: MemberType, value: MemberRef<'_>) {
+        let Some((content, subtree)) = self.stack.last_mut() else {
+            return;
+        };
+        let member = format_lookup(member);
+        let value = match value {
+            MemberRef::Float(value) => ContentValue::Float(value.to_bits()),
+            MemberRef::Int(value) => ContentValue::Int(value),
+            MemberRef::Enum(name) => ContentValue::Enum(format_lookup(name)),
+            MemberRef::Node => {
+                *subtree = Some(member);
+                return;
+            }
+        };
+        content.members.push((member, value));
+    }
+
+    fn leave_node(&mut self, _name: &str) {
+        let Some((mut content, _)) = self.stack.pop() else {
+            return;
+        };
+        content.members.sort_by(|(a, _), (b, _)| a.cmp(b));
+        match self.stack.last_mut() {
+            Some((parent, subtree)) => {
+                if let Some(member) = subtree.take() {
+                    parent.members.push((member, ContentValue::Node(content)));
+                }
+            }
+            None => self.root = Some(content),
+        }
+    }
+}
+
+/// Implements [`PartialEq`], [`Eq`] and [`Hash`] for typed generators by their content, floats by bits, like the
+/// trees they build, see [`Generator::same_tree`] and [`Generator::content_hash`].
+macro_rules! content_eq {
+    ($($name:ident $(<$($param:ident: $bound:ident),* $(,)?>)?),* $(,)?) => {
+        $(
+            impl$(<$($param: $bound),*>)? PartialEq for $name$(<$($param),*>)? {
+                fn eq(&self, other: &Self) -> bool {
+                    $crate::generator::Generator::same_tree(self, other)
+                }
+            }
+
+            impl$(<$($param: $bound),*>)? Eq for $name$(<$($param),*>)? {}
+
+            impl$(<$($param: $bound),*>)? ::std::hash::Hash for $name$(<$($param),*>)? {
+                fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                    $crate::generator::Generator::content_hash(self).hash(state);
+                }
+            }
+        )*
+    };
+}
+pub(crate) use content_eq;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{
+        cellular::CellularDistanceReturnType, prelude::*, BuildContext, DistanceFunction,
+    };
+
+    #[test]
+    fn test_fnv128() {
+        // Reference values of 128-bit FNV-1a.
+        assert_eq!(Fnv128::new().0, 0x6c62272e07bb014262b821756295c58d);
+        let mut hasher = Fnv128::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.0, 0xd228cb696f1a8caf78912b704e4a8964);
+    }
+
+    #[test]
+    fn test_equal_trees() {
+        let generator = perlin().fbm(0.5, 0.0, 3, 2.0) + constant(0.25);
+        // Built in separate contexts, so that no node is shared.
+        let a = BuildContext::new().build(&generator).0;
+        let b = BuildContext::new().build(&generator).0;
+        assert!(!Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(a, b);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert!(generator.same_tree(&a));
+
+        let other = (perlin().fbm(0.5, 0.0, 3, 2.0) + constant(0.5)).build().0;
+        assert_ne!(a, other);
+        assert_ne!(a.content_hash(), other.content_hash());
+        assert!(!generator.same_tree(&other));
+    }
+
+    #[test]
+    fn test_floats_by_bits() {
+        assert_ne!(constant(0.0).build().0, constant(-0.0).build().0);
+        assert_eq!(constant(f32::NAN).build().0, constant(f32::NAN).build().0);
+        assert_ne!(constant(0.0).content_hash(), constant(-0.0).content_hash());
+    }
+
+    #[test]
+    fn test_members_distinguished() {
+        // Same values on different members.
+        let a = perlin().domain_offset(1.0, 0.0, 0.0, 0.0);
+        let b = perlin().domain_offset(0.0, 1.0, 0.0, 0.0);
+        assert_ne!(a.content_hash(), b.content_hash());
+        assert_ne!(perlin().content_hash(), simplex().content_hash());
+    }
+
+    #[test]
+    fn test_encoded_trees() {
+        let mut a = Node::from_name("Constant").unwrap();
+        a.encoded = Some("EQACAAAAAAAg".into());
+        let mut b = Node::from_name("Constant").unwrap();
+        b.encoded = Some("EQACAAAAAAAg".into());
+        let (a, b) = (SafeNode(a.into()), SafeNode(b.into()));
+        assert_eq!(a, b);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_ne!(a.content_hash(), constant(0.0).content_hash());
+    }
+
+    /// The hash is stored as a cache key, it must not change unless the format of the hashed content changes.
+    #[test]
+    fn test_stable_hash() {
+        assert_eq!(
+            constant(1.0).content_hash().to_string(),
+            "8d2b7a0c1081da40b4befec4038c7404"
+        );
+        assert_eq!(
+            constant(1.0).build().content_hash(),
+            constant(1.0).content_hash()
+        );
+    }
+
+    #[test]
+    fn test_typed_hash_matches_built() {
+        let generators: Vec<(SafeNode, ContentHash)> = vec![
+            {
+                let generator = perlin().fbm(0.5, constant(0.2), 3, 2.0).domain_scale(2.0);
+                (generator.build().0, generator.content_hash())
+            },
+            {
+                let generator = cellular_distance(
+                    1.0,
+                    DistanceFunction::Manhattan,
+                    0,
+                    1,
+                    CellularDistanceReturnType::Index0Add1,
+                );
+                (generator.build().0, generator.content_hash())
+            },
+            {
+                let generator = perlin().remap(-1.0, 1.0, 0.0, 1.0) + perlin().min(simplex());
+                (generator.build().0, generator.content_hash())
+            },
+        ];
+        for (built, typed) in generators {
+            assert_eq!(built.content_hash(), typed);
+        }
+    }
+
+    #[test]
+    fn test_typed_eq() {
+        let a = perlin().fbm(0.5, 0.0, 3, 2.0) + constant(0.25);
+        let b = perlin().fbm(0.5, 0.0, 3, 2.0) + constant(0.25);
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+        assert_ne!(a, perlin().fbm(0.5, 0.0, 3, 2.0) + constant(0.5));
+        assert_ne!(constant(0.0), constant(-0.0));
+        assert_eq!(constant(f32::NAN), constant(f32::NAN));
+        assert!(a.same_tree(&a.build()));
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_content_hash_display() {
+        assert_eq!(
+            ContentHash(0xab).to_string(),
+            "000000000000000000000000000000ab"
+        );
+    }
+}
@@ -67,18 +67,51 @@
 //! - The `FASTNOISE2_SOURCE_DIR` environment variable is generally not needed as fastnoise2-sys includes the FastNoise2 source code as a Git submodule. If you need to use a different source directory, set `FASTNOISE2_SOURCE_DIR` to point to the root of the FastNoise2 source code.
 //!
 #![allow(clippy::too_many_arguments)]
+pub mod codegen;
+pub mod determinism;
+mod encoded;
 mod error;
 pub mod generator;
+mod graph;
+mod hash;
+mod library;
 mod metadata;
+pub mod migration;
+mod optimize;
+mod param;
 mod safe;
-
-pub use error::FastNoiseError;
+pub mod schema;
+pub mod stats;
+mod visit;
+
+use error::suggestions;
+pub use error::{ErrorKind, FastNoiseError};
+pub use fastnoise2_sys::encoded::DecodeError;
+pub use graph::PrettyTree;
+pub use hash::ContentHash;
+pub use library::{library_info, BuildKind, LibraryInfo};
 pub use metadata::MemberType;
-use metadata::{format_lookup, MemberValue, METADATA_NAME_LOOKUP, NODE_METADATA};
+use metadata::{
+    format_lookup, metadata_id, Member, MemberSlot, MemberValue, RecordedValue, NODE_METADATA,
+};
+use migration::{member_rename, warn_deprecated};
+pub use param::Param;
 pub use safe::SafeNode;
+pub use visit::{GeneratorFolder, GeneratorVisitor, MemberData, MemberRef};
+
+/// Checks an encoded node tree at compile time, and expands to a `&'static SafeNode` decoding it on first use.
+/// Documented in the fastnoise2-derive crate.
+#[cfg(feature = "macros")]
+pub use fastnoise2_derive::encoded_tree;
 
 use fastnoise2_sys::*;
-use std::{ffi::CString, fmt::Debug};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::CString,
+    fmt::Debug,
+    sync::Arc,
+    thread::{self, ThreadId},
+};
 
 /// Represents a node in the FastNoise2 C++ library.
 ///
@@ -98,46 +131,113 @@ use std::{ffi::CString, fmt::Debug};
 pub struct Node {
     handle: *mut core::ffi::c_void,
     metadata_id: i32,
+    /// Values set through [`Node::set`], recorded to recreate the node. Children are owned here.
+    members: BTreeMap<MemberSlot, RecordedValue>,
+    /// Encoded node tree the node was decoded from, its members are unknown unless they were set afterwards.
+    encoded: Option<Box<str>>,
+    /// Whether the tree keeps mutable state between generation calls (i.e. contains a `GeneratorCache`), in which
+    /// case it must not be used by several threads at once.
+    stateful: bool,
+    /// Thread that created the node.
+    owner: ThreadId,
 }
 
 impl Node {
     /// Creates a [`Node`] instance using a metadata name.
     ///
+    /// The node uses the highest SIMD level supported by the CPU, unless it is created while building a generator
+    /// with a [`BuildContext`][crate::generator::BuildContext] requesting another one.
+    ///
     /// # Errors
     /// Returns an error if the metadata name is not found in the FastNoise2 metadata system.
     #[cfg_attr(feature = "trace", tracing::instrument(level = "debug"))]
     pub fn from_name(metadata_name: &str) -> Result<Self, FastNoiseError> {
-        let metadata_name = format_lookup(metadata_name);
-        let metadata_id = *METADATA_NAME_LOOKUP.get(&metadata_name).ok_or_else(|| {
-            FastNoiseError::MetadataNameNotFound {
-                expected: METADATA_NAME_LOOKUP.keys().cloned().collect(),
-                found: metadata_name,
-            }
-        })?;
-        // Pass u32::MAX (~0u in C++) for auto-detect SIMD level
-        let handle = unsafe { fnNewFromMetadata(metadata_id, u32::MAX) };
+        Self::from_name_with_simd_level(metadata_name, generator::context::build_simd_level())
+    }
+
+    /// Creates a [`Node`] instance using a metadata name, requesting a specific SIMD level.
+    ///
+    /// Pass `u32::MAX` to let FastNoise2 pick the highest level supported by the CPU.
+    ///
+    /// # Errors
+    /// Returns an error if the metadata name is not found in the FastNoise2 metadata system,
+    /// or if FastNoise2 could not create the node at the requested SIMD level.
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "debug"))]
+    pub fn from_name_with_simd_level(
+        metadata_name: &str,
+        simd_level: u32,
+    ) -> Result<Self, FastNoiseError> {
+        let metadata_id = metadata_id(metadata_name)?;
+        let handle = unsafe { fnNewFromMetadata(metadata_id, simd_level) };
+        if handle.is_null() {
+            return Err(FastNoiseError::NodeCreationFailed);
+        }
         Ok(Self {
             handle,
             metadata_id,
+            members: BTreeMap::new(),
+            encoded: None,
+            stateful: NODE_METADATA[metadata_id as usize].name == "generatorcache",
+            owner: thread::current().id(),
         })
     }
 
     /// Creates a `Node` instance from an encoded node tree.
     ///
     /// # Errors
-    /// Returns an error if the encoded node tree is invalid or if creation fails.
+    /// Returns [`FastNoiseError::InvalidEncodedNodeTree`] if the encoded node tree doesn't match the node table of the
+    /// linked library, or [`FastNoiseError::EncodedNodeTreeDecodeFailed`] if FastNoise2 fails to decode it anyway, e.g.
+    /// if it was exported by an incompatible Node Editor version.
     #[cfg_attr(feature = "trace", tracing::instrument(level = "debug"))]
     pub fn from_encoded_node_tree(encoded_node_tree: &str) -> Result<Self, FastNoiseError> {
+        // Pass u32::MAX (~0u in C++) for auto-detect SIMD level
+        Self::from_encoded_node_tree_with_simd_level(encoded_node_tree, u32::MAX)
+    }
+
+    /// Creates a `Node` instance from an encoded node tree, requesting a specific SIMD level.
+    ///
+    /// Pass `u32::MAX` to let FastNoise2 pick the highest level supported by the CPU.
+    ///
+    /// # Errors
+    /// Returns [`FastNoiseError::InvalidEncodedNodeTree`] if the encoded node tree doesn't match the node table of the
+    /// linked library, [`FastNoiseError::EncodedNodeTreeDecodeFailed`] if FastNoise2 fails to decode it anyway, or
+    /// [`FastNoiseError::NodeCreationFailed`] if the SIMD level isn't available.
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "debug"))]
+    pub fn from_encoded_node_tree_with_simd_level(
+        encoded_node_tree: &str,
+        simd_level: u32,
+    ) -> Result<Self, FastNoiseError> {
+        encoded::decode(encoded_node_tree).map_err(|error| {
+            FastNoiseError::InvalidEncodedNodeTree {
+                library: library_info().to_string(),
+                error,
+            }
+        })?;
         let cstring =
             CString::new(encoded_node_tree).map_err(FastNoiseError::CStringCreationFailed)?;
-        // Pass u32::MAX (~0u in C++) for auto-detect SIMD level
-        let node_ptr = unsafe { fnNewFromEncodedNodeTree(cstring.as_ptr(), u32::MAX) };
+        let node_ptr = unsafe { fnNewFromEncodedNodeTree(cstring.as_ptr(), simd_level) };
         if node_ptr.is_null() {
-            Err(FastNoiseError::NodeCreationFailed)
+            // Tell an unsupported SIMD level apart from a tree that can't be decoded: FastNoise2 uses the highest
+            // available level up to the requested one.
+            if !library_info()
+                .simd_levels
+                .iter()
+                .any(|&level| level <= simd_level)
+            {
+                return Err(FastNoiseError::NodeCreationFailed);
+            }
+            Err(FastNoiseError::EncodedNodeTreeDecodeFailed {
+                library: library_info().to_string(),
+            })
         } else {
             Ok(Self {
                 handle: node_ptr,
                 metadata_id: unsafe { fnGetMetadataID(node_ptr) },
+                members: BTreeMap::new(),
+                encoded: Some(encoded_node_tree.into()),
+                // The tree can't be inspected, assume it may contain a cache.
+                stateful: true,
+                owner: thread::current().id(),
             })
         }
     }
@@ -151,6 +251,20 @@ impl Node {
     /// The `member_name` is looked up in the metadata, and the `value` is applied based on its type.
     /// The type of `value` must match the member's expected type as defined in the metadata.
     ///
+    /// A node member takes a [`Node`], an [`Arc<Node>`] or a `&Node`. The parent keeps its children alive, see
+    /// [`Node::children`]; since a borrowed node may be dropped first, a `&Node` is copied instead.
+    ///
+    /// **Breaking:** unlike in 0.4.0, the copy of a `&Node` is made when it is set, so later changes to the borrowed
+    /// node are no longer seen by the parent, and setting it costs a new FastNoise2 node. Pass an [`Arc<Node>`] to
+    /// share a child that is changed afterwards, or a [`Node`] to move it into the parent.
+    ///
+    /// Enum members take the typed enums of [`generator`], such as
+    /// [`DistanceFunction`][generator::DistanceFunction], or their name as a `&str`. `False`/`True` members take a
+    /// [`bool`]. [`u32`] and [`f64`] values are converted, failing if they overflow.
+    ///
+    /// Per-dimension members such as `OffsetX` to `OffsetW` can be set together by their common name with an array,
+    /// e.g. `node.set("Offset", [1.0, 2.0, 0.0, 0.0])`.
+    ///
     /// # Errors
     /// Returns an error if the member name is not found which includes a list of valid member names.
     /// Also returns an error if `value`'s type does not match the expected type for the member. The error provides the expected and actual types to assist in debugging.
@@ -160,16 +274,191 @@ impl Node {
     where
         V: MemberValue + Debug,
     {
+        match self.member(member_name) {
+            Ok(member) => value.apply(self, member),
+            Err(error) => match self.dimension_members(member_name) {
+                Some(members) => value.apply_dimensions(self, &members),
+                None => Err(error),
+            },
+        }
+    }
+
+    /// Looks up a group of per-dimension members by their common name, e.g. `Offset`, ordered by dimension.
+    pub(crate) fn dimension_members(&self, group_name: &str) -> Option<Vec<&'static Member>> {
         let metadata = &NODE_METADATA[self.metadata_id as usize];
-        let member_name = format_lookup(member_name);
-        let member = metadata.members.get(&member_name).ok_or_else(|| {
-            FastNoiseError::MemberNameNotFound {
-                expected: metadata.members.values().map(|m| m.name.clone()).collect(),
-                found: member_name,
+        let lookup = format_lookup(group_name);
+        let mut members: Vec<_> = metadata
+            .members
+            .values()
+            .filter(|m| m.dimension.is_some() && format_lookup(&m.display_name) == lookup)
+            .collect();
+        members.sort_by_key(|m| m.dimension);
+        if members.is_empty() {
+            let (rename, new) = member_rename(&metadata.display_name, group_name)?;
+            let members = self.dimension_members(&new)?;
+            warn_deprecated(group_name, &new, rename);
+            return Some(members);
+        }
+        Some(members)
+    }
+
+    /// Looks up the metadata of a member by name.
+    pub(crate) fn member(&self, member_name: &str) -> Result<&'static Member, FastNoiseError> {
+        let metadata = &NODE_METADATA[self.metadata_id as usize];
+        if let Some(member) = metadata.members.get(&format_lookup(member_name)) {
+            return Ok(member);
+        }
+        member_rename(&metadata.display_name, member_name)
+            .and_then(|(rename, new)| {
+                let member = metadata.members.get(&format_lookup(&new))?;
+                warn_deprecated(member_name, &new, rename);
+                Some(member)
+            })
+            .ok_or_else(|| {
+                let mut expected: Vec<String> =
+                    metadata.members.values().map(Member::set_name).collect();
+                expected.sort();
+                FastNoiseError::MemberNameNotFound {
+                    node: metadata.display_name.clone(),
+                    suggestions: suggestions(member_name, expected.iter().map(String::as_str)),
+                    expected,
+                    found: member_name.to_string(),
+                }
+            })
+    }
+
+    /// Returns the child set on `member`, if any.
+    pub(crate) fn child(&self, member: &Member) -> Option<&Arc<Node>> {
+        match self.members.get(&member.slot()) {
+            Some(RecordedValue::Node(child)) => Some(child),
+            _ => None,
+        }
+    }
+
+    /// Returns the nodes set on the members of this node.
+    ///
+    /// A node keeps its children alive for as long as it references them, so they can't be dropped before it.
+    pub fn children(&self) -> impl Iterator<Item = &Arc<Node>> {
+        self.members.values().filter_map(|value| match value {
+            RecordedValue::Node(child) => Some(child),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn record(&mut self, slot: MemberSlot, value: RecordedValue) {
+        self.members.insert(slot, value);
+    }
+
+    /// Creates an independent copy of this tree: every node is created again with the same members, at the same SIMD
+    /// level, so the copy gives the same output but shares no state with the original.
+    ///
+    /// Nodes used several times in the tree are copied once and stay shared within the copy.
+    ///
+    /// # Errors
+    /// Returns an error if FastNoise2 fails to create one of the nodes.
+    pub fn deep_clone(&self) -> Result<Node, FastNoiseError> {
+        self.recreate(&|_| true, &mut HashMap::new())
+    }
+
+    /// Creates a copy of this node with the same members, sharing its children.
+    pub(crate) fn shallow_copy(&self) -> Result<Node, FastNoiseError> {
+        self.recreate(&|_| false, &mut HashMap::new())
+    }
+
+    /// Creates a copy of this tree with new nodes for the stateful parts, so that it can be used by another thread.
+    /// Stateless subtrees are shared with the original.
+    pub(crate) fn replicate_stateful(&self) -> Result<Node, FastNoiseError> {
+        self.recreate(&|node| node.stateful, &mut HashMap::new())
+    }
+
+    /// Creates a new node with the same members, recreating the children for which `deep` returns `true`.
+    ///
+    /// `copies` maps the handles of recreated children to their copies, so that shared children stay shared.
+    fn recreate(
+        &self,
+        deep: &dyn Fn(&Node) -> bool,
+        copies: &mut HashMap<usize, Arc<Node>>,
+    ) -> Result<Node, FastNoiseError> {
+        let simd_level = self.get_simd_level();
+        let mut copy = match &self.encoded {
+            Some(encoded) => Self::from_encoded_node_tree_with_simd_level(encoded, simd_level)?,
+            None => {
+                let handle = unsafe { fnNewFromMetadata(self.metadata_id, simd_level) };
+                if handle.is_null() {
+                    return Err(FastNoiseError::NodeCreationFailed);
+                }
+                Self {
+                    handle,
+                    metadata_id: self.metadata_id,
+                    members: BTreeMap::new(),
+                    encoded: None,
+                    stateful: self.stateful,
+                    owner: thread::current().id(),
+                }
             }
-        })?;
+        };
+
+        for (&slot, value) in &self.members {
+            let value = match value {
+                RecordedValue::Node(child) if deep(child) => {
+                    let child = match copies.get(&(child.handle as usize)) {
+                        Some(child_copy) => child_copy.clone(),
+                        None => {
+                            let child_copy: Arc<Node> = child.recreate(deep, copies)?.into();
+                            copies.insert(child.handle as usize, child_copy.clone());
+                            child_copy
+                        }
+                    };
+                    RecordedValue::Node(child)
+                }
+                value => value.clone(),
+            };
+            copy.apply_recorded(slot, value)?;
+        }
+        Ok(copy)
+    }
 
-        value.apply(self, member)
+    /// Sets a recorded value on the member at `slot`, and records it.
+    pub(crate) fn apply_recorded(
+        &mut self,
+        slot: MemberSlot,
+        value: RecordedValue,
+    ) -> Result<(), FastNoiseError> {
+        let result = match (slot, &value) {
+            (MemberSlot::Variable(index), RecordedValue::Float(value)) => {
+                unsafe { fnSetVariableFloat(self.handle, index, *value) }
+                    .then_some(())
+                    .ok_or(FastNoiseError::SetFloatFailed)
+            }
+            (MemberSlot::Variable(index), RecordedValue::Int(value)) => {
+                unsafe { fnSetVariableIntEnum(self.handle, index, *value) }
+                    .then_some(())
+                    .ok_or(FastNoiseError::SetIntFailed)
+            }
+            (MemberSlot::Hybrid(index), RecordedValue::Float(value)) => {
+                unsafe { fnSetHybridFloat(self.handle, index, *value) }
+                    .then_some(())
+                    .ok_or(FastNoiseError::SetHybridFloatFailed)
+            }
+            (MemberSlot::NodeLookup(index), RecordedValue::Node(child)) => unsafe {
+                fnSetNodeLookup(self.handle, index, &child.handle as *const _ as *const _)
+            }
+            .then_some(())
+            .ok_or(FastNoiseError::SetNodeLookupFailed),
+            (MemberSlot::Hybrid(index), RecordedValue::Node(child)) => unsafe {
+                fnSetHybridNodeLookup(self.handle, index, &child.handle as *const _ as *const _)
+            }
+            .then_some(())
+            .ok_or(FastNoiseError::SetHybridNodeLookupFailed),
+            _ => unreachable!("recorded values match the type of their member"),
+        };
+        if result.is_ok() {
+            if let RecordedValue::Node(child) = &value {
+                self.stateful |= child.stateful;
+            }
+            self.record(slot, value);
+        }
+        result
     }
 
     /// # Safety
@@ -473,7 +762,7 @@ impl Drop for Node {
 /// Holds the minimum and maximum values from noise generation.
 ///
 /// Used to represent the range of values produced by noise functions.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct OutputMinMax {
     pub min: f32,
     pub max: f32,
@@ -487,3 +776,208 @@ impl OutputMinMax {
 
 #[cfg(test)]
 pub mod test_utils;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(node: &Node) -> f32 {
+        unsafe { node.gen_single_2d_unchecked(0.5, 0.25, 1337) }
+    }
+
+    fn fractal(source: impl MemberValue + Debug) -> Node {
+        let mut fractal = Node::from_name("FractalFBm").unwrap();
+        fractal.set("Source", source).unwrap();
+        fractal.set("Gain", 0.6).unwrap();
+        fractal
+    }
+
+    #[test]
+    fn test_children_outlive_their_handles() {
+        let mut simplex = Node::from_name("Simplex").unwrap();
+        simplex.set("FeatureScale", 20.0).unwrap();
+        let simplex_handle = simplex.handle;
+        let node = fractal(simplex);
+
+        let children: Vec<_> = node.children().collect();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].handle, simplex_handle);
+        assert!(output(&node).is_finite());
+    }
+
+    #[test]
+    fn test_borrowed_child_is_copied() {
+        let simplex = Node::from_name("Simplex").unwrap();
+        let by_ref = fractal(&simplex);
+        let by_value = fractal(Arc::<Node>::from(simplex));
+
+        let copy = by_ref.children().next().unwrap();
+        assert_ne!(copy.handle, by_value.children().next().unwrap().handle);
+        assert_eq!(output(&by_ref), output(&by_value));
+    }
+
+    #[test]
+    fn test_shared_child() {
+        let simplex: Arc<Node> = Node::from_name("Simplex").unwrap().into();
+        let mut blend = Node::from_name("Add").unwrap();
+        blend.set("LHS", simplex.clone()).unwrap();
+        blend.set("RHS", simplex.clone()).unwrap();
+        drop(simplex);
+
+        let children: Vec<_> = blend.children().collect();
+        assert_eq!(children.len(), 2);
+        assert!(Arc::ptr_eq(children[0], children[1]));
+        assert_eq!(Arc::strong_count(children[0]), 2);
+    }
+
+    #[test]
+    fn test_deep_clone() {
+        let simplex: Arc<Node> = Node::from_name("Simplex").unwrap().into();
+        let mut blend = Node::from_name("Add").unwrap();
+        blend.set("LHS", fractal(simplex.clone())).unwrap();
+        blend.set("RHS", simplex).unwrap();
+
+        let mut copy = blend.deep_clone().unwrap();
+        assert_ne!(copy.handle, blend.handle);
+        assert_eq!(copy.get_simd_level(), blend.get_simd_level());
+        assert_eq!(output(&copy).to_bits(), output(&blend).to_bits());
+
+        let [lhs, rhs] = [MemberSlot::NodeLookup(0), MemberSlot::Hybrid(0)].map(|slot| {
+            let Some(RecordedValue::Node(child)) = copy.members.get(&slot) else {
+                panic!("missing child");
+            };
+            child.clone()
+        });
+        // The shared simplex is copied once.
+        assert!(Arc::ptr_eq(lhs.children().next().unwrap(), &rhs));
+        assert!(blend.children().all(|child| child.handle != rhs.handle));
+
+        let before = output(&blend);
+        copy.set("RHS", 2.0).unwrap();
+        assert_eq!(output(&blend), before);
+    }
+
+    #[test]
+    fn test_replaced_child_is_released() {
+        let first: Arc<Node> = Node::from_name("Simplex").unwrap().into();
+        let mut node = fractal(first.clone());
+        node.set("Source", Node::from_name("Perlin").unwrap())
+            .unwrap();
+        assert_eq!(Arc::strong_count(&first), 1);
+        assert_eq!(node.children().count(), 1);
+    }
+
+    #[test]
+    fn test_set_dimension_group() {
+        let mut grouped = Node::from_name("DomainOffset").unwrap();
+        grouped.set("Offset", [1.0, 2.0, 0.0, 0.5]).unwrap();
+        let mut single = Node::from_name("DomainOffset").unwrap();
+        for (name, value) in [
+            ("OffsetX", 1.0),
+            ("OffsetY", 2.0),
+            ("OffsetZ", 0.0),
+            ("OffsetW", 0.5),
+        ] {
+            single.set(name, value).unwrap();
+        }
+        assert_eq!(output(&grouped), output(&single));
+
+        let simplex = Node::from_name("Simplex").unwrap();
+        grouped
+            .set("Offset", [&simplex, &simplex, &simplex, &simplex])
+            .unwrap();
+        assert_eq!(grouped.children().count(), 4);
+
+        assert!(matches!(
+            grouped.set("Offset", [1.0, 2.0]),
+            Err(FastNoiseError::InvalidDimensionCount {
+                expected: 4,
+                found: 2,
+                ..
+            })
+        ));
+        assert!(matches!(
+            grouped.set("Offset", 1.0),
+            Err(FastNoiseError::InvalidDimensionCount {
+                expected: 4,
+                found: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            grouped.set("OffsetX", [1.0, 2.0]),
+            Err(FastNoiseError::InvalidDimensionCount {
+                expected: 1,
+                found: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_set_typed_values() {
+        let mut remap = Node::from_name("Remap").unwrap();
+        remap.set("ClampOutput", true).unwrap();
+        let mut by_name = Node::from_name("Remap").unwrap();
+        by_name.set("ClampOutput", "True").unwrap();
+        assert_eq!(output(&remap), output(&by_name));
+
+        let mut cellular = Node::from_name("CellularValue").unwrap();
+        cellular
+            .set("DistanceFunction", generator::DistanceFunction::MaxAxis)
+            .unwrap();
+        let mut by_name = Node::from_name("CellularValue").unwrap();
+        by_name.set("DistanceFunction", "Max Axis").unwrap();
+        assert_eq!(output(&cellular), output(&by_name));
+
+        let mut fractal = Node::from_name("FractalFBm").unwrap();
+        fractal.set("Octaves", 4u32).unwrap();
+        fractal.set("Gain", 0.5f64).unwrap();
+        assert!(matches!(
+            fractal.set("Octaves", u32::MAX),
+            Err(FastNoiseError::ValueOutOfRange { .. })
+        ));
+        assert!(matches!(
+            fractal.set("Gain", f64::MAX),
+            Err(FastNoiseError::ValueOutOfRange { .. })
+        ));
+        assert!(matches!(
+            fractal.set("Gain", true),
+            Err(FastNoiseError::InvalidMemberType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_encoded_node_tree() {
+        let error = Node::from_encoded_node_tree("not a node tree").unwrap_err();
+        assert!(matches!(
+            error,
+            FastNoiseError::InvalidEncodedNodeTree {
+                error: DecodeError::InvalidCharacter { index: 3, .. },
+                ..
+            }
+        ));
+        assert!(error.to_string().contains(&library_info().to_string()));
+        assert_eq!(error.kind(), ErrorKind::InvalidValue);
+
+        // A node id past the node table.
+        let error = Node::from_encoded_node_tree("/wQ=").unwrap_err();
+        assert!(error.to_string().contains("unknown node id 255"), "{error}");
+
+        // A fractal without its source.
+        let mut fbm = Node::from_name("FractalFBm").unwrap();
+        fbm.set("Octaves", 4).unwrap();
+        let error = Node::from_encoded_node_tree(&encoded::encode_tree(&fbm)).unwrap_err();
+        assert!(matches!(
+            error,
+            FastNoiseError::InvalidEncodedNodeTree {
+                error: DecodeError::MissingNodeLookup { .. },
+                ..
+            }
+        ));
+
+        let perlin = encoded::encode_tree(&Node::from_name("Perlin").unwrap());
+        let error = Node::from_encoded_node_tree_with_simd_level(&perlin, 0).unwrap_err();
+        assert!(matches!(error, FastNoiseError::NodeCreationFailed));
+    }
+}
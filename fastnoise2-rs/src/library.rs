@@ -0,0 +1,181 @@
+use std::{fmt, sync::LazyLock};
+
+use fastnoise2_sys::{
+    FASTNOISE2_BUILD_FLAGS, FASTNOISE2_BUILD_KIND, FASTNOISE2_BUNDLED_COMMIT, FASTNOISE2_COMMIT,
+};
+
+use crate::determinism::available_simd_levels;
+
+/// How the linked FastNoise2 library was obtained by `fastnoise2-sys`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildKind {
+    /// Built from source with CMake.
+    Source,
+    /// Precompiled library found in `FASTNOISE2_LIB_DIR`.
+    Prebuilt,
+    /// Built from source with Emscripten.
+    Wasm,
+    /// Documentation build, nothing is linked.
+    Docs,
+}
+
+impl fmt::Display for BuildKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildKind::Source => f.write_str("built from source"),
+            BuildKind::Prebuilt => f.write_str("prebuilt"),
+            BuildKind::Wasm => f.write_str("built from source for WASM"),
+            BuildKind::Docs => f.write_str("not linked"),
+        }
+    }
+}
+
+/// Linked FastNoise2 library, as recorded by the build script of `fastnoise2-sys`, see [`library_info`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LibraryInfo {
+    /// Commit of the FastNoise2 sources the library was built from, if known.
+    ///
+    /// It is unknown for a precompiled library unless `FASTNOISE2_LIB_COMMIT` was set when building, or for sources
+    /// set by `FASTNOISE2_SOURCE_DIR` outside of a git checkout.
+    pub commit: Option<&'static str>,
+    /// Commit of FastNoise2 this crate is written against.
+    pub bundled_commit: &'static str,
+    pub build_kind: BuildKind,
+    /// Release compiler flags the library was built with, if known.
+    pub build_flags: Option<&'static str>,
+    /// SIMD levels the library can run on this CPU, lowest first, see
+    /// [`available_simd_levels`][crate::determinism::available_simd_levels].
+    pub simd_levels: Vec<u32>,
+}
+
+impl LibraryInfo {
+    /// Returns whether the linked library is the FastNoise2 version this crate is written against, or `None` if its
+    /// commit is unknown.
+    ///
+    /// Node and member names, and the format of encoded node trees, may differ in other versions. Commits shorter
+    /// than 7 hex digits, or not hex, never match.
+    pub fn is_bundled_version(&self) -> Option<bool> {
+        self.commit
+            .map(|commit| same_commit(commit, self.bundled_commit))
+    }
+
+    /// Returns the first 7 characters of the commit, or `unknown`.
+    pub fn short_commit(&self) -> &'static str {
+        self.commit.map_or("unknown", short)
+    }
+}
+
+impl fmt::Display for LibraryInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FastNoise2 {} ({}", self.short_commit(), self.build_kind)?;
+        if let Some(build_flags) = self.build_flags {
+            write!(f, ", flags '{build_flags}'")?;
+        }
+        write!(f, ", SIMD levels {:?}", self.simd_levels)?;
+        if self.is_bundled_version() == Some(false) {
+            write!(f, ", expected {}", short(self.bundled_commit))?;
+        }
+        f.write_str(")")
+    }
+}
+
+static LIBRARY_INFO: LazyLock<LibraryInfo> = LazyLock::new(|| LibraryInfo {
+    commit: non_empty(FASTNOISE2_COMMIT),
+    bundled_commit: FASTNOISE2_BUNDLED_COMMIT,
+    build_kind: match FASTNOISE2_BUILD_KIND {
+        "source" => BuildKind::Source,
+        "prebuilt" => BuildKind::Prebuilt,
+        "wasm" => BuildKind::Wasm,
+        _ => BuildKind::Docs,
+    },
+    build_flags: non_empty(FASTNOISE2_BUILD_FLAGS),
+    simd_levels: available_simd_levels(),
+});
+
+/// Returns the linked FastNoise2 library: its commit, how it was built and the SIMD levels it can run on.
+///
+/// This is most useful with a precompiled library set by `FASTNOISE2_LIB_DIR`, which may not be the FastNoise2 version
+/// this crate is written against, see [`LibraryInfo::is_bundled_version`].
+///
+/// Encoded node trees are checked against the node table of the linked library, an encoded node tree from another
+/// version fails with [`FastNoiseError::InvalidEncodedNodeTree`][crate::FastNoiseError::InvalidEncodedNodeTree] naming
+/// the node or member that doesn't match. A tree whose nodes exist in both versions with the same members still decodes
+/// to them, with nothing but the commits to tell.
+///
+/// # Examples
+///
+/// ```rust
+/// let info = fastnoise2::library_info();
+/// println!("{info}");
+/// if info.is_bundled_version() == Some(false) {
+///     eprintln!("encoded node trees from the Node Editor may not load");
+/// }
+/// ```
+pub fn library_info() -> &'static LibraryInfo {
+    &LIBRARY_INFO
+}
+
+fn non_empty(value: &'static str) -> Option<&'static str> {
+    (!value.is_empty()).then_some(value)
+}
+
+fn short(commit: &str) -> &str {
+    commit.get(..7).unwrap_or(commit)
+}
+
+/// Compares commits, which may be abbreviated to no less than 7 hex digits like [`LibraryInfo::short_commit`].
+fn same_commit(a: &str, b: &str) -> bool {
+    let is_commit =
+        |commit: &str| commit.len() >= 7 && commit.bytes().all(|c| c.is_ascii_hexdigit());
+    let len = a.len().min(b.len());
+    is_commit(a) && is_commit(b) && a.as_bytes()[..len].eq_ignore_ascii_case(&b.as_bytes()[..len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_commit() {
+        assert!(same_commit(
+            "3728fde",
+            "3728fde069704509fcf2973825b2d385348bf336"
+        ));
+        assert!(same_commit("3728FDE", "3728fde"));
+        assert!(!same_commit(
+            "f8facba",
+            "3728fde069704509fcf2973825b2d385348bf336"
+        ));
+        assert!(!same_commit("", "3728fde"));
+        assert!(!same_commit("3", "3728fde"));
+        assert!(!same_commit("3728fd", "3728fde"));
+        assert!(!same_commit("unknown", "unknown"));
+    }
+
+    #[test]
+    fn test_library_info() {
+        let info = library_info();
+        assert_eq!(info.bundled_commit.len(), 40);
+        assert_ne!(info.build_kind, BuildKind::Docs);
+        assert!(!info.simd_levels.is_empty());
+        assert!(info.to_string().starts_with("FastNoise2 "));
+
+        let unknown = LibraryInfo {
+            commit: None,
+            ..info.clone()
+        };
+        assert_eq!(unknown.is_bundled_version(), None);
+        assert_eq!(unknown.short_commit(), "unknown");
+
+        let other = LibraryInfo {
+            commit: Some("f8facba"),
+            ..info.clone()
+        };
+        assert_eq!(other.is_bundled_version(), Some(false));
+        assert!(other
+            .to_string()
+            .contains(&format!("expected {}", &info.bundled_commit[..7])));
+    }
+}
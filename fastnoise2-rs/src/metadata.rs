@@ -1,26 +1,206 @@
-use std::{any::type_name, collections::HashMap, ffi::CStr, sync::LazyLock};
+use std::{
+    any::type_name,
+    collections::HashMap,
+    ffi::CStr,
+    sync::{Arc, LazyLock},
+};
 
 use fastnoise2_sys::*;
 
-use crate::{FastNoiseError, Node};
+use crate::{
+    error::suggestions,
+    migration::{node_rename, removal, warn_deprecated},
+    FastNoiseError, Node,
+};
 
 #[derive(Debug)]
 pub(crate) struct Metadata {
     #[allow(dead_code)]
     pub id: i32,
-    #[allow(dead_code)]
+    /// Name formatted for lookups, see [`format_lookup`].
     pub name: String,
+    /// Name as given by FastNoise2, e.g. `FractalFBm`.
+    pub display_name: String,
     pub members: HashMap<String, Member>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Member {
+    /// Name of the node owning the member, formatted for lookups, e.g. `domainoffset`.
+    pub node: String,
+    /// Name formatted for lookups, with the dimension suffix, e.g. `offsetx`.
     pub name: String,
+    /// Name as given by FastNoise2, without the dimension suffix, e.g. `Offset`.
+    pub display_name: String,
+    /// Dimension of the member within a group of per-dimension members, e.g. `1` for `OffsetY`.
+    pub dimension: Option<usize>,
     pub member_type: MemberType,
     pub index: i32,
     pub enum_names: HashMap<String, i32>,
+    /// Enum values as given by FastNoise2, by index.
+    pub enum_display_names: Vec<String>,
+}
+
+impl Member {
+    fn new(
+        node: &str,
+        display_name: String,
+        dimension_idx: i32,
+        member_type: MemberType,
+        index: i32,
+        enum_names: HashMap<String, i32>,
+        enum_display_names: Vec<String>,
+    ) -> Self {
+        Self {
+            node: node.to_string(),
+            name: format_dimension_member(&format_lookup(&display_name), dimension_idx),
+            display_name,
+            dimension: usize::try_from(dimension_idx).ok(),
+            member_type,
+            index,
+            enum_names,
+            enum_display_names,
+        }
+    }
+
+    /// Name of the member as used by [`Node::set`], e.g. `OffsetY` or `FeatureScale`.
+    pub(crate) fn set_name(&self) -> String {
+        let name = self.display_name.replace(' ', "");
+        match self.dimension {
+            Some(dimension) => format!("{name}{}", ['X', 'Y', 'Z', 'W'][dimension]),
+            None => name,
+        }
+    }
+
+    pub(crate) fn slot(&self) -> MemberSlot {
+        match self.member_type {
+            MemberType::Float | MemberType::Int | MemberType::Enum => {
+                MemberSlot::Variable(self.index)
+            }
+            MemberType::NodeLookup => MemberSlot::NodeLookup(self.index),
+            MemberType::Hybrid => MemberSlot::Hybrid(self.index),
+        }
+    }
+
+    /// Returns whether `value` is the default of this member in the typed generators, e.g. `100.0` for
+    /// `FeatureScale`. A member that was never set is assumed to hold its default. Members without default, such as
+    /// `Gain`, and nodes are never default.
+    pub(crate) fn is_default(&self, value: Option<&RecordedValue>) -> bool {
+        let Some(value) = value else {
+            return true;
+        };
+        let Some(default) = self.default_value() else {
+            return false;
+        };
+        match (value, default) {
+            (RecordedValue::Float(value), DefaultValue::Float(default)) => value == default,
+            (RecordedValue::Int(value), DefaultValue::Int(default)) => value == default,
+            (RecordedValue::Int(index), DefaultValue::Variant(default)) => usize::try_from(*index)
+                .ok()
+                .and_then(|index| self.enum_display_names.get(index))
+                .is_some_and(|variant| format_lookup(variant) == *default),
+            _ => false,
+        }
+    }
+
+    /// Returns the default of this member in the typed generators, if it is optional in its node.
+    pub(crate) fn default_value(&self) -> Option<&'static DefaultValue> {
+        let name = format_lookup(&self.display_name);
+        MEMBER_DEFAULTS
+            .iter()
+            .find(|(node, _)| *node == self.node)
+            .and_then(|(_, defaults)| defaults.iter().find(|(member, _)| *member == name))
+            .map(|(_, default)| default)
+    }
+}
+
+/// Default of an optional member in the typed generators.
+pub(crate) enum DefaultValue {
+    Float(f32),
+    Int(i32),
+    /// Enum variant, formatted for lookups.
+    Variant(&'static str),
 }
 
+/// Defaults of the optional members of the typed generators, by node and member name formatted for lookups, without
+/// the dimension suffix. A member with the same name in another node has its own default, or none, e.g. `SeedOffset`
+/// is optional in `Perlin` but required in the `SeedOffset` node.
+const MEMBER_DEFAULTS: &[(&str, &[(&str, DefaultValue)])] = &[
+    (
+        "gradient",
+        &[
+            ("multiplier", DefaultValue::Float(0.0)),
+            ("offset", DefaultValue::Float(0.0)),
+        ],
+    ),
+    (
+        "white",
+        &[
+            ("seedoffset", DefaultValue::Int(0)),
+            ("outputmin", DefaultValue::Float(-1.0)),
+            ("outputmax", DefaultValue::Float(1.0)),
+        ],
+    ),
+    (
+        "distancetopoint",
+        &[
+            (
+                "distancefunction",
+                DefaultValue::Variant("euclideansquared"),
+            ),
+            ("point", DefaultValue::Float(0.0)),
+            ("minkowskip", DefaultValue::Float(1.5)),
+        ],
+    ),
+    ("checkerboard", PATTERN_DEFAULTS),
+    ("sinewave", PATTERN_DEFAULTS),
+    ("perlin", COHERENT_NOISE_DEFAULTS),
+    ("simplex", COHERENT_NOISE_DEFAULTS),
+    ("supersimplex", COHERENT_NOISE_DEFAULTS),
+    ("value", COHERENT_NOISE_DEFAULTS),
+    ("cellularvalue", CELLULAR_DEFAULTS),
+    ("cellulardistance", CELLULAR_DEFAULTS),
+    ("cellularlookup", CELLULAR_DEFAULTS),
+    (
+        "fade",
+        &[
+            ("fademin", DefaultValue::Float(-1.0)),
+            ("fademax", DefaultValue::Float(1.0)),
+            ("interpolation", DefaultValue::Variant("linear")),
+        ],
+    ),
+    ("domainwarpsimplex", DOMAIN_WARP_DEFAULTS),
+    ("domainwarpsupersimplex", DOMAIN_WARP_DEFAULTS),
+    (
+        "domainrotateplane",
+        &[("rotationtype", DefaultValue::Variant("improvexyplanes"))],
+    ),
+    ("remap", &[("clampoutput", DefaultValue::Variant("false"))]),
+];
+
+const PATTERN_DEFAULTS: &[(&str, DefaultValue)] = &[
+    ("featurescale", DefaultValue::Float(100.0)),
+    ("outputmin", DefaultValue::Float(-1.0)),
+    ("outputmax", DefaultValue::Float(1.0)),
+];
+
+const COHERENT_NOISE_DEFAULTS: &[(&str, DefaultValue)] = &[
+    ("featurescale", DefaultValue::Float(100.0)),
+    ("seedoffset", DefaultValue::Int(0)),
+    ("outputmin", DefaultValue::Float(-1.0)),
+    ("outputmax", DefaultValue::Float(1.0)),
+];
+
+const CELLULAR_DEFAULTS: &[(&str, DefaultValue)] = &[
+    ("minkowskip", DefaultValue::Float(1.5)),
+    ("sizejitter", DefaultValue::Float(0.0)),
+];
+
+const DOMAIN_WARP_DEFAULTS: &[(&str, DefaultValue)] = &[(
+    "vectorizationscheme",
+    DefaultValue::Variant("orthogonalgradientmatrix"),
+)];
+
 /// Defines the type of value or reference a node can handle.
 #[derive(Clone, Copy, Debug)]
 pub enum MemberType {
@@ -48,6 +228,26 @@ impl std::fmt::Display for MemberType {
     }
 }
 
+/// Position of a member in FastNoise2's metadata, as indexed by the C API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum MemberSlot {
+    Variable(i32),
+    NodeLookup(i32),
+    Hybrid(i32),
+}
+
+/// Value set on a member.
+///
+/// The C API has no getters, so values are recorded on the Rust side when they are set.
+#[derive(Clone, Debug)]
+pub(crate) enum RecordedValue {
+    Float(f32),
+    /// Integer or enum index.
+    Int(i32),
+    /// Child node, kept alive by its parent.
+    Node(Arc<Node>),
+}
+
 pub(crate) static METADATA_NAME_LOOKUP: LazyLock<HashMap<String, i32>> = LazyLock::new(|| {
     let metadata_count = unsafe { fnGetMetadataCount() };
     let mut lookup = HashMap::new();
@@ -60,12 +260,43 @@ pub(crate) static METADATA_NAME_LOOKUP: LazyLock<HashMap<String, i32>> = LazyLoc
     lookup
 });
 
+/// Looks up the metadata ID of a node by name, accepting the old names of renamed nodes, see [`migration`].
+pub(crate) fn metadata_id(name: &str) -> Result<i32, FastNoiseError> {
+    if let Some(&id) = METADATA_NAME_LOOKUP.get(&format_lookup(name)) {
+        return Ok(id);
+    }
+    let renamed = node_rename(name).and_then(|rename| {
+        let id = *METADATA_NAME_LOOKUP.get(&format_lookup(rename.new))?;
+        warn_deprecated(name, rename.new, rename);
+        Some(id)
+    });
+    renamed.ok_or_else(|| metadata_name_not_found(name))
+}
+
+/// Error for a node name that the linked library doesn't know, suggesting the closest ones, or the replacements of a
+/// removed node.
+pub(crate) fn metadata_name_not_found(found: &str) -> FastNoiseError {
+    let expected: Vec<String> = NODE_METADATA
+        .iter()
+        .map(|metadata| metadata.display_name.clone())
+        .collect();
+    let suggestions = match removal(found) {
+        Some(removal) => removal.replacements.iter().map(|r| r.to_string()).collect(),
+        None => suggestions(found, expected.iter().map(String::as_str)),
+    };
+    FastNoiseError::MetadataNameNotFound {
+        suggestions,
+        expected,
+        found: found.to_string(),
+    }
+}
+
 pub(crate) static NODE_METADATA: LazyLock<Vec<Metadata>> = LazyLock::new(|| {
     let metadata_count = unsafe { fnGetMetadataCount() };
     let mut metadata_vec = Vec::with_capacity(metadata_count as usize);
     for id in 0..metadata_count {
-        let name =
-            format_lookup(&unsafe { CStr::from_ptr(fnGetMetadataName(id)) }.to_string_lossy());
+        let display_name = c_str(unsafe { fnGetMetadataName(id) });
+        let name = format_lookup(&display_name);
         let mut members = HashMap::new();
 
         let variable_count = unsafe { fnGetMetadataVariableCount(id) };
@@ -79,83 +310,71 @@ pub(crate) static NODE_METADATA: LazyLock<Vec<Metadata>> = LazyLock::new(|| {
                 2 => MemberType::Enum,
                 _ => MemberType::Hybrid,
             };
-            let dimension_idx = unsafe { fnGetMetadataVariableDimensionIdx(id, variable_idx) };
-            let name = format_dimension_member(
-                &format_lookup(
-                    &unsafe { CStr::from_ptr(fnGetMetadataVariableName(id, variable_idx)) }
-                        .to_string_lossy(),
-                ),
-                dimension_idx,
-            );
             let mut enum_names = HashMap::new();
+            let mut enum_display_names = Vec::new();
             if let MemberType::Enum = member_type {
                 let enum_count = unsafe { fnGetMetadataEnumCount(id, variable_idx) };
                 for enum_idx in 0..enum_count {
-                    let enum_name = format_lookup(
-                        &unsafe {
-                            CStr::from_ptr(fnGetMetadataEnumName(id, variable_idx, enum_idx))
-                        }
-                        .to_string_lossy(),
-                    );
-                    enum_names.insert(enum_name, enum_idx);
+                    let enum_name =
+                        c_str(unsafe { fnGetMetadataEnumName(id, variable_idx, enum_idx) });
+                    enum_names.insert(format_lookup(&enum_name), enum_idx);
+                    enum_display_names.push(enum_name);
                 }
             }
-            members.insert(
-                name.clone(),
-                Member {
-                    name,
-                    member_type,
-                    index: variable_idx,
-                    enum_names,
-                },
+            let member = Member::new(
+                &name,
+                c_str(unsafe { fnGetMetadataVariableName(id, variable_idx) }),
+                unsafe { fnGetMetadataVariableDimensionIdx(id, variable_idx) },
+                member_type,
+                variable_idx,
+                enum_names,
+                enum_display_names,
             );
+            members.insert(member.name.clone(), member);
         }
 
         for node_lookup_idx in 0..node_lookup_count {
-            let dimension_idx = unsafe { fnGetMetadataNodeLookupDimensionIdx(id, node_lookup_idx) };
-            let name = format_dimension_member(
-                &format_lookup(
-                    &unsafe { CStr::from_ptr(fnGetMetadataNodeLookupName(id, node_lookup_idx)) }
-                        .to_string_lossy(),
-                ),
-                dimension_idx,
-            );
-            members.insert(
-                name.clone(),
-                Member {
-                    name,
-                    member_type: MemberType::NodeLookup,
-                    index: node_lookup_idx,
-                    enum_names: HashMap::new(),
-                },
+            let member = Member::new(
+                &name,
+                c_str(unsafe { fnGetMetadataNodeLookupName(id, node_lookup_idx) }),
+                unsafe { fnGetMetadataNodeLookupDimensionIdx(id, node_lookup_idx) },
+                MemberType::NodeLookup,
+                node_lookup_idx,
+                HashMap::new(),
+                Vec::new(),
             );
+            members.insert(member.name.clone(), member);
         }
 
         for hybrid_idx in 0..hybrid_count {
-            let dimension_idx = unsafe { fnGetMetadataHybridDimensionIdx(id, hybrid_idx) };
-            let name = format_dimension_member(
-                &format_lookup(
-                    &unsafe { CStr::from_ptr(fnGetMetadataHybridName(id, hybrid_idx)) }
-                        .to_string_lossy(),
-                ),
-                dimension_idx,
-            );
-            members.insert(
-                name.clone(),
-                Member {
-                    name,
-                    member_type: MemberType::Hybrid,
-                    index: hybrid_idx,
-                    enum_names: HashMap::new(),
-                },
+            let member = Member::new(
+                &name,
+                c_str(unsafe { fnGetMetadataHybridName(id, hybrid_idx) }),
+                unsafe { fnGetMetadataHybridDimensionIdx(id, hybrid_idx) },
+                MemberType::Hybrid,
+                hybrid_idx,
+                HashMap::new(),
+                Vec::new(),
             );
+            members.insert(member.name.clone(), member);
         }
 
-        metadata_vec.push(Metadata { id, name, members });
+        metadata_vec.push(Metadata {
+            id,
+            name,
+            display_name,
+            members,
+        });
     }
     metadata_vec
 });
 
+fn c_str(ptr: *const core::ffi::c_char) -> String {
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
 pub(crate) fn format_lookup(name: &str) -> String {
     name.replace(" ", "").to_lowercase()
 }
@@ -173,31 +392,62 @@ fn format_dimension_member(name: &str, dim_idx: i32) -> String {
 pub trait MemberValue {
     const TYPE: MemberType;
 
-    fn apply(&self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError>;
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError>;
+
+    /// Applies the value to a group of per-dimension members such as `OffsetX` to `OffsetW`, ordered by dimension.
+    ///
+    /// Only arrays can be applied to a group, one value per dimension.
+    fn apply_dimensions(self, _node: &mut Node, members: &[&Member]) -> Result<(), FastNoiseError>
+    where
+        Self: Sized,
+    {
+        Err(dimension_count_error(members, 1))
+    }
 
     fn invalid_member_type_error(member: &Member) -> FastNoiseError {
         FastNoiseError::InvalidMemberType {
-            member_name: member.name.clone(),
+            member_name: member.set_name(),
             expected: member.member_type,
             found: Self::TYPE,
         }
     }
 }
 
+fn dimension_count_error(members: &[&Member], found: usize) -> FastNoiseError {
+    FastNoiseError::InvalidDimensionCount {
+        member_name: members[0].display_name.clone(),
+        expected: members.len(),
+        found,
+    }
+}
+
+fn out_of_range_error(member: &Member, value: impl std::fmt::Display) -> FastNoiseError {
+    FastNoiseError::ValueOutOfRange {
+        member_name: member.set_name(),
+        expected: member.member_type,
+        found: value.to_string(),
+    }
+}
+
 impl MemberValue for f32 {
     const TYPE: MemberType = MemberType::Float;
 
-    fn apply(&self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
         match member.member_type {
             MemberType::Float => {
-                if !unsafe { fnSetVariableFloat(node.handle, member.index, *self) } {
+                if !unsafe { fnSetVariableFloat(node.handle, member.index, self) } {
                     return Err(FastNoiseError::SetFloatFailed);
                 }
+                node.record(
+                    MemberSlot::Variable(member.index),
+                    RecordedValue::Float(self),
+                );
             }
             MemberType::Hybrid => {
-                if !unsafe { fnSetHybridFloat(node.handle, member.index, *self) } {
+                if !unsafe { fnSetHybridFloat(node.handle, member.index, self) } {
                     return Err(FastNoiseError::SetHybridFloatFailed);
                 }
+                node.record(MemberSlot::Hybrid(member.index), RecordedValue::Float(self));
             }
             _ => return Err(Self::invalid_member_type_error(member)),
         }
@@ -208,12 +458,13 @@ impl MemberValue for f32 {
 impl MemberValue for i32 {
     const TYPE: MemberType = MemberType::Int;
 
-    fn apply(&self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
         match member.member_type {
             MemberType::Int => {
-                if !unsafe { fnSetVariableIntEnum(node.handle, member.index, *self) } {
+                if !unsafe { fnSetVariableIntEnum(node.handle, member.index, self) } {
                     return Err(FastNoiseError::SetIntFailed);
                 }
+                node.record(MemberSlot::Variable(member.index), RecordedValue::Int(self));
             }
             _ => return Err(Self::invalid_member_type_error(member)),
         }
@@ -221,21 +472,85 @@ impl MemberValue for i32 {
     }
 }
 
+/// Narrowed to an [`f32`], failing if it is too large to be represented. Precision is lost as usual.
+impl MemberValue for f64 {
+    const TYPE: MemberType = MemberType::Float;
+
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        let narrowed = self as f32;
+        if self.is_finite() && narrowed.is_infinite() {
+            return Err(out_of_range_error(member, self));
+        }
+        narrowed.apply(node, member)
+    }
+}
+
+/// Converted to an [`i32`], failing if it is larger than [`i32::MAX`].
+impl MemberValue for u32 {
+    const TYPE: MemberType = MemberType::Int;
+
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        match member.member_type {
+            MemberType::Int => i32::try_from(self)
+                .map_err(|_| out_of_range_error(member, self))?
+                .apply(node, member),
+            _ => Err(Self::invalid_member_type_error(member)),
+        }
+    }
+}
+
+/// Sets a `False`/`True` enum member, e.g. `ClampOutput`.
+impl MemberValue for bool {
+    const TYPE: MemberType = MemberType::Enum;
+
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        if self { "True" } else { "False" }.apply(node, member)
+    }
+}
+
+/// Sets a group of per-dimension members, one value per dimension, e.g. `node.set("Offset", [1.0, 2.0, 0.0, 0.0])`.
+impl<T: MemberValue, const N: usize> MemberValue for [T; N] {
+    const TYPE: MemberType = T::TYPE;
+
+    fn apply(self, _node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        Err(dimension_count_error(&[member], N))
+    }
+
+    fn apply_dimensions(self, node: &mut Node, members: &[&Member]) -> Result<(), FastNoiseError> {
+        if members.len() != N {
+            return Err(dimension_count_error(members, N));
+        }
+        for (value, member) in self.into_iter().zip(members) {
+            value.apply(node, member)?;
+        }
+        Ok(())
+    }
+}
+
 impl MemberValue for &str {
     const TYPE: MemberType = MemberType::Enum;
 
-    fn apply(&self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
         match member.member_type {
             MemberType::Enum => {
                 let enum_idx = member.enum_names.get(&format_lookup(self)).ok_or_else(|| {
                     FastNoiseError::EnumValueNotFound {
-                        expected: member.enum_names.keys().cloned().collect(),
+                        member_name: member.set_name(),
+                        suggestions: suggestions(
+                            self,
+                            member.enum_display_names.iter().map(String::as_str),
+                        ),
+                        expected: member.enum_display_names.clone(),
                         found: self.to_string(),
                     }
                 })?;
                 if !unsafe { fnSetVariableIntEnum(node.handle, member.index, *enum_idx) } {
                     return Err(FastNoiseError::SetEnumFailed);
                 }
+                node.record(
+                    MemberSlot::Variable(member.index),
+                    RecordedValue::Int(*enum_idx),
+                );
             }
             _ => return Err(Self::invalid_member_type_error(member)),
         }
@@ -243,38 +558,39 @@ impl MemberValue for &str {
     }
 }
 
-impl MemberValue for &Node {
+impl MemberValue for Arc<Node> {
     const TYPE: MemberType = MemberType::NodeLookup;
 
-    fn apply(&self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
         match member.member_type {
-            MemberType::NodeLookup => {
-                // C API expects pointer to SmartNode-like structure (pointer at offset 0)
-                // Pass address of handle so C can dereference to get the Generator*
-                if !unsafe {
-                    fnSetNodeLookup(
-                        node.handle,
-                        member.index,
-                        &self.handle as *const _ as *const _,
-                    )
-                } {
-                    return Err(FastNoiseError::SetNodeLookupFailed);
-                }
+            // The parent keeps the child alive for as long as it references it.
+            MemberType::NodeLookup | MemberType::Hybrid => {
+                node.apply_recorded(member.slot(), RecordedValue::Node(self))
             }
-            MemberType::Hybrid => {
-                // Same fix for hybrid node lookups
-                if !unsafe {
-                    fnSetHybridNodeLookup(
-                        node.handle,
-                        member.index,
-                        &self.handle as *const _ as *const _,
-                    )
-                } {
-                    return Err(FastNoiseError::SetHybridNodeLookupFailed);
-                }
+            _ => Err(Self::invalid_member_type_error(member)),
+        }
+    }
+}
+
+impl MemberValue for Node {
+    const TYPE: MemberType = MemberType::NodeLookup;
+
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        Arc::<Node>::from(self).apply(node, member)
+    }
+}
+
+/// Sets a copy of the node, see [`Node::set`]: unlike in 0.4.0, the parent doesn't see later changes to it.
+impl MemberValue for &Node {
+    const TYPE: MemberType = MemberType::NodeLookup;
+
+    fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        match member.member_type {
+            // A borrowed node may be dropped before its parent, so the parent keeps a copy instead.
+            MemberType::NodeLookup | MemberType::Hybrid => {
+                Arc::<Node>::from(self.shallow_copy()?).apply(node, member)
             }
-            _ => return Err(Self::invalid_member_type_error(member)),
+            _ => Err(Self::invalid_member_type_error(member)),
         }
-        Ok(())
     }
 }
@@ -0,0 +1,593 @@
+//! Node and member names from previous FastNoise2 versions.
+//!
+//! Upstream renames nodes and members between versions, e.g. `OpenSimplex2` became `SuperSimplex`. [`Node::from_name`]
+//! and [`Node::set`] accept the names listed in [`RENAMES`] and use the current ones instead, so that names stored in
+//! configuration files keep working. With the `trace` feature, each use of an old name is logged as a warning. Use
+//! [`node_rename`] and [`member_rename`] to report them yourself, e.g. to update the files.
+//!
+//! Nodes listed in [`REMOVALS`] have no direct replacement, their error suggests the nodes to use instead.
+//!
+//! # Encoded node trees
+//!
+//! Encoded node trees exported before fastnoise2 0.4.0, by the Node Editor of FastNoise2 0.10, have another format and
+//! use the old names. [`migrate_encoded_node_tree`] reads them against the node table of FastNoise2 0.10, applies
+//! [`RENAMES`] and [`REMOVALS`], and encodes them again for the linked library. A tree using a node or member that
+//! was removed without a listed replacement can't be migrated, and must be rebuilt with typed generators.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use fastnoise2::{migration::node_rename, Node};
+//!
+//! let name = "OpenSimplex2";
+//! if let Some(rename) = node_rename(name) {
+//!     println!("'{name}' is deprecated, use '{}'", rename.new);
+//! }
+//! let node = Node::from_name(name).unwrap();
+//! ```
+//!
+//! [`Node::from_name`]: crate::Node::from_name
+//! [`Node::set`]: crate::Node::set
+use std::sync::LazyLock;
+
+use fastnoise2_sys::encoded::{
+    self, EncodedNode, HybridValue, NodeLayout, VariableKind, VariableLayout, VariableValue,
+};
+
+use crate::{metadata::format_lookup, FastNoiseError, Node};
+
+/// Node or member renamed by FastNoise2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rename {
+    /// Node of a renamed member, or `None` for a renamed node.
+    pub node: Option<&'static str>,
+    /// Previous name.
+    pub old: &'static str,
+    /// Current name.
+    pub new: &'static str,
+    /// Version of this crate that updated FastNoise2 past the rename.
+    pub since: &'static str,
+}
+
+/// Node removed by FastNoise2.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Removal {
+    /// Name of the removed node.
+    pub old: &'static str,
+    /// Nodes replacing it.
+    pub replacements: &'static [&'static str],
+    /// How to use the replacements.
+    pub note: &'static str,
+    /// Version of this crate that updated FastNoise2 past the removal.
+    pub since: &'static str,
+}
+
+/// Nodes and members renamed by FastNoise2. Per-dimension members are renamed as a group, e.g. `ScaleX` becomes
+/// `ScalingX`.
+pub const RENAMES: &[Rename] = &[
+    node("OpenSimplex2", "SuperSimplex", "0.4.0"),
+    node("OpenSimplex2S", "SuperSimplex", "0.4.0"),
+    node("PositionOutput", "Gradient", "0.4.0"),
+    node("SquareRoot", "SignedSquareRoot", "0.4.0"),
+    node(
+        "DomainWarpFractalIndependant",
+        "DomainWarpFractalIndependent",
+        "0.4.0",
+    ),
+    member("DomainScale", "Scale", "Scaling", "0.4.0"),
+    member("DomainAxisScale", "Scale", "Scaling", "0.4.0"),
+    member("Terrace", "Multiplier", "StepCount", "0.4.0"),
+    member("CellularValue", "JitterModifier", "GridJitter", "0.4.0"),
+    member("CellularDistance", "JitterModifier", "GridJitter", "0.4.0"),
+    member("CellularLookup", "JitterModifier", "GridJitter", "0.4.0"),
+    member(
+        "DomainWarpGradient",
+        "WarpFrequency",
+        "FeatureScale",
+        "0.4.0",
+    ),
+];
+
+/// Nodes removed by FastNoise2.
+pub const REMOVALS: &[Removal] = &[Removal {
+    old: "FractalPingPong",
+    replacements: &["FractalFBm", "PingPong"],
+    note: "set a PingPong node as the source of a fractal",
+    since: "0.4.0",
+}];
+
+const fn node(old: &'static str, new: &'static str, since: &'static str) -> Rename {
+    Rename {
+        node: None,
+        old,
+        new,
+        since,
+    }
+}
+
+const fn member(
+    node: &'static str,
+    old: &'static str,
+    new: &'static str,
+    since: &'static str,
+) -> Rename {
+    Rename {
+        node: Some(node),
+        old,
+        new,
+        since,
+    }
+}
+
+/// Returns the rename of the node `name`, if it is an old name. Names are compared ignoring case and spaces.
+pub fn node_rename(name: &str) -> Option<&'static Rename> {
+    let name = format_lookup(name);
+    RENAMES
+        .iter()
+        .find(|rename| rename.node.is_none() && format_lookup(rename.old) == name)
+}
+
+/// Returns the current name of the member `name` of `node` if it is an old name, e.g. `ScalingY` for `ScaleY`.
+pub fn member_rename(node: &str, name: &str) -> Option<(&'static Rename, String)> {
+    let node = format_lookup(node);
+    let name = format_lookup(name);
+    let renames = RENAMES
+        .iter()
+        .filter(|rename| rename.node.is_some_and(|n| format_lookup(n) == node));
+    for rename in renames {
+        let old = format_lookup(rename.old);
+        if old == name {
+            return Some((rename, rename.new.to_string()));
+        }
+        // Per-dimension member, e.g. `scalex`.
+        if let Some(dimension @ ("x" | "y" | "z" | "w")) = name.strip_prefix(old.as_str()) {
+            return Some((
+                rename,
+                format!("{}{}", rename.new, dimension.to_uppercase()),
+            ));
+        }
+    }
+    None
+}
+
+/// Returns the removal of the node `name`, if it was removed.
+pub fn removal(name: &str) -> Option<&'static Removal> {
+    let name = format_lookup(name);
+    REMOVALS
+        .iter()
+        .find(|removal| format_lookup(removal.old) == name)
+}
+
+/// Rewrites an encoded node tree exported before fastnoise2 0.4.0 for the linked library, see
+/// [Encoded node trees](self#encoded-node-trees).
+///
+/// Old node and member names are replaced by the current ones from [`RENAMES`], without logging them. Members that
+/// were added since keep their default.
+///
+/// # Errors
+/// Returns [`FastNoiseError::InvalidLegacyEncodedNodeTree`] if the tree doesn't match the node table of FastNoise2
+/// 0.10, [`FastNoiseError::MetadataNameNotFound`] for a node listed in [`REMOVALS`], with its replacements as
+/// suggestions, and the errors of [`Node::set`] for members that no longer exist or changed type.
+///
+/// # Examples
+///
+/// ```rust
+/// use fastnoise2::{migration::migrate_encoded_node_tree, Node};
+///
+/// // A fractal of simplex noise exported by FastNoise2 0.10.
+/// let encoded = migrate_encoded_node_tree("DQAFAAAAAAAAQAgAAAAAAD8AAAAAAA==").unwrap();
+/// let node = Node::from_encoded_node_tree(&encoded).unwrap();
+/// ```
+pub fn migrate_encoded_node_tree(encoded_node_tree: &str) -> Result<String, FastNoiseError> {
+    let legacy = encoded::decode_legacy(encoded_node_tree, &LEGACY_LAYOUTS)
+        .map_err(|error| FastNoiseError::InvalidLegacyEncodedNodeTree { error })?;
+    Ok(crate::encoded::encode_tree(&migrated_node(&legacy)?))
+}
+
+/// Creates the node of a legacy tree with the current names, setting every member it writes.
+fn migrated_node(legacy: &EncodedNode) -> Result<Node, FastNoiseError> {
+    let layout = &LEGACY_NODES[legacy.id];
+    let name = node_rename(layout.name).map_or(layout.name, |rename| rename.new);
+    let mut node = Node::from_name(name)?;
+    let member =
+        |old: &str| member_rename(layout.name, old).map_or(old.to_string(), |(_, new)| new);
+    for (index, value) in &legacy.variables {
+        let (old, kind) = layout.variables[*index];
+        let name = member(old);
+        match (value, kind) {
+            (VariableValue::Float(value), _) => node.set(&name, *value)?,
+            (VariableValue::Int(value), LegacyVariable::Enum(variants)) => {
+                node.set(&name, variants[*value as usize])?
+            }
+            (VariableValue::Int(value), _) => node.set(&name, *value)?,
+        }
+    }
+    for (old, lookup) in layout.node_lookups.iter().zip(&legacy.node_lookups) {
+        node.set(&member(old), migrated_node(lookup)?)?;
+    }
+    for (index, value) in &legacy.hybrids {
+        let name = member(layout.hybrids[*index]);
+        match value {
+            HybridValue::Float(value) => node.set(&name, *value)?,
+            HybridValue::Node(child) => node.set(&name, migrated_node(child)?)?,
+        }
+    }
+    Ok(node)
+}
+
+/// Node of FastNoise2 0.10, with its members in index order for each kind of member.
+struct LegacyNode {
+    name: &'static str,
+    variables: &'static [(&'static str, LegacyVariable)],
+    node_lookups: &'static [&'static str],
+    hybrids: &'static [&'static str],
+}
+
+#[derive(Clone, Copy)]
+enum LegacyVariable {
+    Float,
+    Int,
+    /// Names of the variants, by index.
+    Enum(&'static [&'static str]),
+}
+
+/// Nodes of FastNoise2 0.10, indexed by id. Per-dimension variables are named with their dimension, e.g. `Scale X`.
+const LEGACY_NODES: &[LegacyNode] = &[
+    legacy("Constant", &[("Value", LegacyVariable::Float)], &[], &[]),
+    legacy("White", &[], &[], &[]),
+    legacy("Checkerboard", &[("Size", LegacyVariable::Float)], &[], &[]),
+    legacy("SineWave", &[("Scale", LegacyVariable::Float)], &[], &[]),
+    legacy(
+        "PositionOutput",
+        &[
+            ("Multiplier X", LegacyVariable::Float),
+            ("Multiplier Y", LegacyVariable::Float),
+            ("Multiplier Z", LegacyVariable::Float),
+            ("Multiplier W", LegacyVariable::Float),
+            ("Offset X", LegacyVariable::Float),
+            ("Offset Y", LegacyVariable::Float),
+            ("Offset Z", LegacyVariable::Float),
+            ("Offset W", LegacyVariable::Float),
+        ],
+        &[],
+        &[],
+    ),
+    legacy(
+        "DistanceToPoint",
+        &[
+            ("Distance Function", DISTANCE_FUNCTION),
+            ("Point X", LegacyVariable::Float),
+            ("Point Y", LegacyVariable::Float),
+            ("Point Z", LegacyVariable::Float),
+            ("Point W", LegacyVariable::Float),
+        ],
+        &[],
+        &[],
+    ),
+    legacy("Value", &[], &[], &[]),
+    legacy("Perlin", &[], &[], &[]),
+    legacy("Simplex", &[], &[], &[]),
+    legacy("OpenSimplex2", &[], &[], &[]),
+    legacy(
+        "CellularValue",
+        &[
+            ("Distance Function", DISTANCE_FUNCTION),
+            ("Value Index", LegacyVariable::Int),
+        ],
+        &[],
+        &["Jitter Modifier"],
+    ),
+    legacy(
+        "CellularDistance",
+        &[
+            ("Distance Function", DISTANCE_FUNCTION),
+            ("Distance Index 0", LegacyVariable::Int),
+            ("Distance Index 1", LegacyVariable::Int),
+            (
+                "Return Type",
+                LegacyVariable::Enum(&[
+                    "Index0",
+                    "Index0Add1",
+                    "Index0Sub1",
+                    "Index0Mul1",
+                    "Index0Div1",
+                ]),
+            ),
+        ],
+        &[],
+        &["Jitter Modifier"],
+    ),
+    legacy(
+        "CellularLookup",
+        &[
+            ("Distance Function", DISTANCE_FUNCTION),
+            ("Lookup Frequency", LegacyVariable::Float),
+        ],
+        &["Lookup"],
+        &["Jitter Modifier"],
+    ),
+    legacy("FractalFBm", FRACTAL, &["Source"], FRACTAL_HYBRIDS),
+    legacy(
+        "FractalPingPong",
+        FRACTAL,
+        &["Source"],
+        &["Gain", "Weighted Strength", "Ping Pong Strength"],
+    ),
+    legacy("FractalRidged", FRACTAL, &["Source"], FRACTAL_HYBRIDS),
+    legacy(
+        "DomainWarpGradient",
+        &[("Warp Frequency", LegacyVariable::Float)],
+        &["Source"],
+        &["Warp Amplitude"],
+    ),
+    legacy(
+        "DomainWarpFractalProgressive",
+        FRACTAL,
+        &["Domain Warp Source"],
+        FRACTAL_HYBRIDS,
+    ),
+    legacy(
+        "DomainWarpFractalIndependant",
+        FRACTAL,
+        &["Domain Warp Source"],
+        FRACTAL_HYBRIDS,
+    ),
+    legacy(
+        "DomainScale",
+        &[("Scale", LegacyVariable::Float)],
+        &["Source"],
+        &[],
+    ),
+    legacy(
+        "DomainOffset",
+        &[],
+        &["Source"],
+        &["Offset X", "Offset Y", "Offset Z", "Offset W"],
+    ),
+    legacy(
+        "DomainRotate",
+        &[
+            ("Yaw", LegacyVariable::Float),
+            ("Roll", LegacyVariable::Float),
+            ("Pitch", LegacyVariable::Float),
+        ],
+        &["Source"],
+        &[],
+    ),
+    legacy(
+        "SeedOffset",
+        &[("Seed Offset", LegacyVariable::Int)],
+        &["Source"],
+        &[],
+    ),
+    legacy(
+        "Remap",
+        &[
+            ("From Min", LegacyVariable::Float),
+            ("From Max", LegacyVariable::Float),
+            ("To Min", LegacyVariable::Float),
+            ("To Max", LegacyVariable::Float),
+        ],
+        &["Source"],
+        &[],
+    ),
+    legacy(
+        "ConvertRGBA8",
+        &[
+            ("Min", LegacyVariable::Float),
+            ("Max", LegacyVariable::Float),
+        ],
+        &["Source"],
+        &[],
+    ),
+    legacy("Add", &[], &["LHS"], &["RHS"]),
+    legacy("Subtract", &[], &[], &["LHS", "RHS"]),
+    legacy("Multiply", &[], &["LHS"], &["RHS"]),
+    legacy("Divide", &[], &[], &["LHS", "RHS"]),
+    legacy("Min", &[], &["LHS"], &["RHS"]),
+    legacy("Max", &[], &["LHS"], &["RHS"]),
+    legacy("MinSmooth", &[], &["LHS"], &["RHS", "Smoothness"]),
+    legacy("MaxSmooth", &[], &["LHS"], &["RHS", "Smoothness"]),
+    legacy("Fade", &[], &["A", "B"], &["Fade"]),
+    legacy(
+        "Terrace",
+        &[("Multiplier", LegacyVariable::Float)],
+        &["Source"],
+        &["Smoothness"],
+    ),
+    legacy("PowFloat", &[], &[], &["Value", "Pow"]),
+    legacy("PowInt", &[("Pow", LegacyVariable::Int)], &["Value"], &[]),
+    legacy(
+        "DomainAxisScale",
+        &[
+            ("Scale X", LegacyVariable::Float),
+            ("Scale Y", LegacyVariable::Float),
+            ("Scale Z", LegacyVariable::Float),
+            ("Scale W", LegacyVariable::Float),
+        ],
+        &["Source"],
+        &[],
+    ),
+    legacy(
+        "AddDimension",
+        &[],
+        &["Source"],
+        &["New Dimension Position"],
+    ),
+    legacy(
+        "RemoveDimension",
+        &[(
+            "Remove Dimension",
+            LegacyVariable::Enum(&["X", "Y", "Z", "W"]),
+        )],
+        &["Source"],
+        &[],
+    ),
+    legacy("GeneratorCache", &[], &["Source"], &[]),
+    legacy("OpenSimplex2S", &[], &[], &[]),
+];
+
+const DISTANCE_FUNCTION: LegacyVariable = LegacyVariable::Enum(&[
+    "Euclidean",
+    "Euclidean Squared",
+    "Manhattan",
+    "Hybrid",
+    "Max Axis",
+]);
+
+const FRACTAL: &[(&str, LegacyVariable)] = &[
+    ("Octaves", LegacyVariable::Int),
+    ("Lacunarity", LegacyVariable::Float),
+];
+
+const FRACTAL_HYBRIDS: &[&str] = &["Gain", "Weighted Strength"];
+
+/// [`LEGACY_NODES`] as read by [`encoded::decode_legacy`].
+static LEGACY_LAYOUTS: LazyLock<Vec<NodeLayout>> = LazyLock::new(|| {
+    LEGACY_NODES
+        .iter()
+        .map(|node| NodeLayout {
+            name: node.name.to_string(),
+            variables: node
+                .variables
+                .iter()
+                .map(|(name, kind)| VariableLayout {
+                    name: name.to_string(),
+                    kind: match kind {
+                        LegacyVariable::Float => VariableKind::Float,
+                        LegacyVariable::Int => VariableKind::Int,
+                        LegacyVariable::Enum(variants) => VariableKind::Enum {
+                            variants: variants.len(),
+                        },
+                    },
+                })
+                .collect(),
+            node_lookups: node
+                .node_lookups
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            hybrids: node.hybrids.iter().map(|name| name.to_string()).collect(),
+        })
+        .collect()
+});
+
+const fn legacy(
+    name: &'static str,
+    variables: &'static [(&'static str, LegacyVariable)],
+    node_lookups: &'static [&'static str],
+    hybrids: &'static [&'static str],
+) -> LegacyNode {
+    LegacyNode {
+        name,
+        variables,
+        node_lookups,
+        hybrids,
+    }
+}
+
+/// Reports the use of an old name.
+pub(crate) fn warn_deprecated(old: &str, new: &str, rename: &Rename) {
+    #[cfg(feature = "trace")]
+    tracing::warn!(
+        "'{old}' is deprecated since fastnoise2 {}, use '{new}' instead",
+        rename.since
+    );
+    #[cfg(not(feature = "trace"))]
+    let _ = (old, new, rename);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoded;
+
+    #[test]
+    fn test_renames_target_current_names() {
+        for rename in RENAMES {
+            match rename.node {
+                None => assert!(Node::from_name(rename.new).is_ok(), "{rename:?}"),
+                Some(node) => {
+                    let node = Node::from_name(node).unwrap();
+                    let dimensions = node.dimension_members(rename.new).is_some();
+                    assert!(node.member(rename.new).is_ok() || dimensions, "{rename:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_old_node_names() {
+        let node = Node::from_name("OpenSimplex2").unwrap();
+        let expected = Node::from_name("SuperSimplex").unwrap();
+        assert_eq!(node.metadata_id, expected.metadata_id);
+        assert_eq!(node_rename("Position Output").unwrap().new, "Gradient");
+        assert!(node_rename("Perlin").is_none());
+    }
+
+    #[test]
+    fn test_old_member_names() {
+        let mut terrace = Node::from_name("Terrace").unwrap();
+        terrace.set("Multiplier", 4.0).unwrap();
+        let mut scale = Node::from_name("DomainAxisScale").unwrap();
+        scale.set("ScaleY", 2.0).unwrap();
+        scale.set("Scale", [1.0, 2.0, 1.0, 1.0]).unwrap();
+        assert_eq!(
+            member_rename("DomainAxisScale", "scale w").unwrap().1,
+            "ScalingW"
+        );
+        assert!(member_rename("Terrace", "Smoothness").is_none());
+    }
+
+    #[test]
+    fn test_removed_node() {
+        let error = Node::from_name("FractalPingPong").unwrap_err();
+        let FastNoiseError::MetadataNameNotFound { suggestions, .. } = &error else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(suggestions, &["FractalFBm", "PingPong"]);
+    }
+
+    #[test]
+    fn test_migrate_encoded_node_tree() {
+        // Exported by the Node Editor of FastNoise2 0.10: FractalFBm of Simplex, 5 octaves.
+        let migrated = migrate_encoded_node_tree("DQAFAAAAAAAAQAgAAAAAAD8AAAAAAA==").unwrap();
+        let mut expected = Node::from_name("FractalFBm").unwrap();
+        expected.set("Octaves", 5).unwrap();
+        expected.set("Lacunarity", 2.0).unwrap();
+        expected
+            .set("Source", Node::from_name("Simplex").unwrap())
+            .unwrap();
+        expected.set("Gain", 0.5).unwrap();
+        expected.set("WeightedStrength", 0.0).unwrap();
+        assert_eq!(migrated, encoded::encode_tree(&expected));
+
+        // DomainScale of Perlin, with the old name of Scaling.
+        let migrated = migrate_encoded_node_tree("EwAAAABABwA=").unwrap();
+        let mut expected = Node::from_name("DomainScale").unwrap();
+        expected.set("Scaling", 2.0).unwrap();
+        expected
+            .set("Source", Node::from_name("Perlin").unwrap())
+            .unwrap();
+        assert_eq!(migrated, encoded::encode_tree(&expected));
+    }
+
+    #[test]
+    fn test_migrate_encoded_node_tree_errors() {
+        // FractalPingPong of Perlin.
+        let error = migrate_encoded_node_tree("DgADAAAAAAAAQAcAAAAAAD8AAAAAAAAAAABA").unwrap_err();
+        let FastNoiseError::MetadataNameNotFound { suggestions, .. } = &error else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(suggestions, &["FractalFBm", "PingPong"]);
+
+        // Truncated, and with an unknown node id.
+        for encoded in ["DQAFAAAAAAAAQAgA", "/wQ="] {
+            let error = migrate_encoded_node_tree(encoded).unwrap_err();
+            assert!(
+                matches!(error, FastNoiseError::InvalidLegacyEncodedNodeTree { .. }),
+                "unexpected error: {error}"
+            );
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    metadata::{format_lookup, Member, RecordedValue, NODE_METADATA},
+    FastNoiseError, GeneratorFolder, MemberData, Node, SafeNode,
+};
+
+impl SafeNode {
+    /// Returns an equivalent tree with fewer nodes, so that it is faster to generate. This tree is left unchanged.
+    ///
+    /// - Operators with constant operands are folded into a `Constant`, e.g. `constant(2.0) * 3.0`.
+    /// - Operators that don't change their left operand are removed: `x + 0.0`, `x - 0.0`, `x * 1.0` and `x / 1.0`.
+    /// - Chained operators with float right operands are fused, e.g. `(x + 1.0) + 2.0` into `x + 3.0`, and so
+    ///   `-(-x)` into `x`.
+    /// - Chained `DomainScale` nodes are fused, and scaling by `1.0` is removed.
+    /// - `Remap` of a `Remap` is fused if neither clamps its output, and remapping a range to itself is removed.
+    /// - `Abs` of an `Abs` is removed.
+    ///
+    /// Floats are combined in another order than FastNoise2 would, so outputs may differ by rounding errors. Trees
+    /// decoded from an encoded node tree are kept as is, and unchanged subtrees stay shared with this tree.
+    ///
+    /// # Errors
+    /// Returns an error if a replacing node can't be created or set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fastnoise2::generator::prelude::*;
+    ///
+    /// let node = (-(-perlin().domain_scale(2.0).domain_scale(0.5)) + 0.0).build();
+    /// assert_eq!(node.optimize().unwrap(), perlin().build().0);
+    /// ```
+    pub fn optimize(&self) -> Result<SafeNode, FastNoiseError> {
+        self.fold(&mut Optimizer)
+    }
+}
+
+/// Simplifies each node once its subtrees are simplified.
+struct Optimizer;
+
+impl GeneratorFolder for Optimizer {
+    fn fold_node(&mut self, mut node: SafeNode) -> Result<SafeNode, FastNoiseError> {
+        while let Some(simpler) = simplify(&node)? {
+            node = simpler;
+        }
+        Ok(node)
+    }
+}
+
+/// Returns a node with the same output as `node` and fewer nodes or members set to nodes, if a rule applies.
+fn simplify(node: &SafeNode) -> Result<Option<SafeNode>, FastNoiseError> {
+    if node.0.encoded.is_some() {
+        return Ok(None);
+    }
+    match name(node) {
+        "Add" => simplify_operator(node, Some(0.0), |a, b| a + b, |a, b| a + b),
+        "Subtract" => simplify_operator(node, Some(0.0), |a, b| a - b, |a, b| a + b),
+        "Multiply" => simplify_operator(node, Some(1.0), |a, b| a * b, |a, b| a * b),
+        "Divide" => simplify_operator(node, Some(1.0), |a, b| a / b, |a, b| a * b),
+        "Min" => simplify_operator(node, None, f32::min, f32::min),
+        "Max" => simplify_operator(node, None, f32::max, f32::max),
+        "DomainScale" => simplify_domain_scale(node),
+        "Remap" => simplify_remap(node),
+        "Abs" => Ok(child(node, "Source").filter(|source| is_plain(source, "Abs"))),
+        _ => Ok(None),
+    }
+}
+
+/// Simplifies an operator computing `operation(LHS, RHS)`.
+///
+/// `identity` is the right operand returning the left one unchanged. `fuse` combines the right operands of chained
+/// operators, so that `operation(operation(x, a), b)` equals `operation(x, fuse(a, b))`.
+fn simplify_operator(
+    node: &SafeNode,
+    identity: Option<f32>,
+    operation: fn(f32, f32) -> f32,
+    fuse: fn(f32, f32) -> f32,
+) -> Result<Option<SafeNode>, FastNoiseError> {
+    let rhs = constant_value(node, "RHS");
+    if let (Some(lhs), Some(rhs)) = (constant_value(node, "LHS"), rhs) {
+        return constant_node(node, operation(lhs, rhs)).map(Some);
+    }
+    let (Some(lhs), Some(rhs)) = (child(node, "LHS"), rhs) else {
+        return Ok(None);
+    };
+    if identity == Some(rhs) {
+        return Ok(Some(lhs));
+    }
+    if !is_plain(&lhs, name(node)) {
+        return Ok(None);
+    }
+    match (child(&lhs, "LHS"), constant_value(&lhs, "RHS")) {
+        (Some(source), Some(inner)) => copy_with(
+            node,
+            [
+                ("LHS", MemberData::Node(source)),
+                ("RHS", MemberData::Float(fuse(inner, rhs))),
+            ],
+        )
+        .map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn simplify_domain_scale(node: &SafeNode) -> Result<Option<SafeNode>, FastNoiseError> {
+    let (Some(source), Some(scaling)) = (child(node, "Source"), float(node, "Scaling")) else {
+        return Ok(None);
+    };
+    if scaling == 1.0 {
+        return Ok(Some(source));
+    }
+    if !is_plain(&source, "DomainScale") {
+        return Ok(None);
+    }
+    match (child(&source, "Source"), float(&source, "Scaling")) {
+        (Some(inner), Some(inner_scaling)) => copy_with(
+            node,
+            [
+                ("Source", MemberData::Node(inner)),
+                ("Scaling", MemberData::Float(scaling * inner_scaling)),
+            ],
+        )
+        .map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn simplify_remap(node: &SafeNode) -> Result<Option<SafeNode>, FastNoiseError> {
+    let (Some(source), Some(outer)) = (child(node, "Source"), RemapRange::new(node)) else {
+        return Ok(None);
+    };
+    if outer.clamp_output {
+        return Ok(None);
+    }
+    if outer.from == outer.to {
+        return Ok(Some(source));
+    }
+    if !is_plain(&source, "Remap") {
+        return Ok(None);
+    }
+    let (Some(inner_source), Some(inner)) = (child(&source, "Source"), RemapRange::new(&source))
+    else {
+        return Ok(None);
+    };
+    if inner.clamp_output || inner.from.0 == inner.from.1 || outer.from.0 == outer.from.1 {
+        return Ok(None);
+    }
+    // Both remaps are affine, so their composition maps the inner input range to the outer image of the inner output
+    // range.
+    copy_with(
+        node,
+        [
+            ("Source", MemberData::Node(inner_source)),
+            ("FromMin", MemberData::Float(inner.from.0)),
+            ("FromMax", MemberData::Float(inner.from.1)),
+            ("ToMin", MemberData::Float(outer.apply(inner.to.0))),
+            ("ToMax", MemberData::Float(outer.apply(inner.to.1))),
+        ],
+    )
+    .map(Some)
+}
+
+/// Float members of a `Remap` node.
+struct RemapRange {
+    from: (f32, f32),
+    to: (f32, f32),
+    clamp_output: bool,
+}
+
+impl RemapRange {
+    fn new(node: &SafeNode) -> Option<Self> {
+        Some(Self {
+            from: (float(node, "FromMin")?, float(node, "FromMax")?),
+            to: (float(node, "ToMin")?, float(node, "ToMax")?),
+            clamp_output: recorded(node, "ClampOutput").is_some_and(|(member, value)| {
+                matches!(value, RecordedValue::Int(index)
+                    if member.enum_display_names.get(*index as usize).is_some_and(|name| name == "True"))
+            }),
+        })
+    }
+
+    fn apply(&self, value: f32) -> f32 {
+        self.to.0 + (value - self.from.0) * (self.to.1 - self.to.0) / (self.from.1 - self.from.0)
+    }
+}
+
+/// Returns a copy of `node` with `members` set.
+fn copy_with<const N: usize>(
+    node: &SafeNode,
+    members: [(&str, MemberData); N],
+) -> Result<SafeNode, FastNoiseError> {
+    let mut copy = node.0.shallow_copy()?;
+    for (member_name, value) in members {
+        let member = copy.member(member_name)?;
+        value.apply(&mut copy, member)?;
+    }
+    Ok(SafeNode(copy.into()))
+}
+
+/// Returns a `Constant` node outputting `value`, at the SIMD level of `node`.
+fn constant_node(node: &SafeNode, value: f32) -> Result<SafeNode, FastNoiseError> {
+    let mut constant = Node::from_name_with_simd_level("Constant", node.get_simd_level())?;
+    constant.set("Value", value)?;
+    Ok(SafeNode(constant.into()))
+}
+
+fn name(node: &SafeNode) -> &'static str {
+    &NODE_METADATA[node.0.metadata_id as usize].display_name
+}
+
+/// Returns `true` if `node` is named `name` and wasn't decoded from an encoded node tree, so that its recorded
+/// members are all its members.
+fn is_plain(node: &SafeNode, name: &str) -> bool {
+    self::name(node) == name && node.0.encoded.is_none()
+}
+
+/// Returns the member named `member_name` and its recorded value, if it is set.
+fn recorded<'a>(
+    node: &'a SafeNode,
+    member_name: &str,
+) -> Option<(&'static Member, &'a RecordedValue)> {
+    let member = NODE_METADATA[node.0.metadata_id as usize]
+        .members
+        .get(&format_lookup(member_name))?;
+    Some((member, node.0.members.get(&member.slot())?))
+}
+
+fn float(node: &SafeNode, member_name: &str) -> Option<f32> {
+    match recorded(node, member_name)? {
+        (_, RecordedValue::Float(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn child(node: &SafeNode, member_name: &str) -> Option<SafeNode> {
+    match recorded(node, member_name)? {
+        (_, RecordedValue::Node(child)) => Some(SafeNode(child.clone())),
+        _ => None,
+    }
+}
+
+/// Returns the value of a float member, or of a `Constant` node set on it.
+fn constant_value(node: &SafeNode, member_name: &str) -> Option<f32> {
+    float(node, member_name).or_else(|| {
+        let child = child(node, member_name)?;
+        is_plain(&child, "Constant")
+            .then(|| float(&child, "Value"))
+            .flatten()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        generator::prelude::*,
+        test_utils::{generate_output, generate_output_3d},
+    };
+
+    fn optimized<G: Generator>(generator: G) -> SafeNode {
+        generator.build().0.optimize().unwrap()
+    }
+
+    #[test]
+    fn test_identity_operators() {
+        assert_eq!(optimized(perlin() + 0.0), perlin().build().0);
+        assert_eq!(optimized(perlin() - 0.0), perlin().build().0);
+        assert_eq!(optimized((perlin() * 1.0) / 1.0), perlin().build().0);
+        assert_eq!(optimized(perlin() + constant(0.0)), perlin().build().0);
+        // Not an identity.
+        assert_eq!(optimized(perlin() * 0.0), (perlin() * 0.0).build().0);
+    }
+
+    #[test]
+    fn test_double_negation() {
+        assert_eq!(optimized(-(-perlin())), perlin().build().0);
+        assert_eq!(optimized(-(-(-perlin()))), (-perlin()).build().0);
+    }
+
+    #[test]
+    fn test_constant_folding() {
+        assert_eq!(
+            optimized((constant(2.0) + constant(3.0)) * 2.0),
+            constant(10.0).build().0
+        );
+        assert_eq!(optimized(-constant(1.5)), constant(-1.5).build().0);
+        assert_eq!(
+            optimized(constant(1.0).min(constant(-2.0))),
+            constant(-2.0).build().0
+        );
+        assert_eq!(
+            optimized(GeneratorWrapper::from(2.0) / constant(4.0)),
+            constant(0.5).build().0
+        );
+    }
+
+    #[test]
+    fn test_fused_operators() {
+        assert_eq!(
+            optimized((perlin() + 1.0) + 2.0),
+            (perlin() + 3.0).build().0
+        );
+        assert_eq!(
+            optimized((perlin() - 1.0) - 2.0),
+            (perlin() - 3.0).build().0
+        );
+        assert_eq!(
+            optimized((perlin() * 2.0) * 3.0),
+            (perlin() * 6.0).build().0
+        );
+        assert_eq!(
+            optimized(perlin().max(0.5).max(0.25)),
+            perlin().max(0.5).build().0
+        );
+        // Different operators are kept.
+        assert_eq!(
+            optimized((perlin() + 1.0) * 2.0),
+            ((perlin() + 1.0) * 2.0).build().0
+        );
+    }
+
+    #[test]
+    fn test_domain_scale() {
+        assert_eq!(
+            optimized(perlin().domain_scale(2.0).domain_scale(3.0)),
+            perlin().domain_scale(6.0).build().0
+        );
+        assert_eq!(optimized(perlin().domain_scale(1.0)), perlin().build().0);
+        assert_eq!(
+            optimized(perlin().domain_scale(2.0).domain_scale(0.5)),
+            perlin().build().0
+        );
+    }
+
+    #[test]
+    fn test_remap() {
+        assert_eq!(
+            optimized(
+                perlin()
+                    .remap(-1.0, 1.0, 0.0, 1.0)
+                    .remap(0.0, 1.0, 0.0, 10.0)
+            ),
+            perlin().remap(-1.0, 1.0, 0.0, 10.0).build().0
+        );
+        assert_eq!(
+            optimized(perlin().remap(-1.0, 1.0, -1.0, 1.0)),
+            perlin().build().0
+        );
+        // Clamping is not affine.
+        let clamped = perlin()
+            .remap_clamped(-1.0, 1.0, 0.0, 1.0, true)
+            .remap(0.0, 1.0, 0.0, 10.0);
+        assert_eq!(optimized(clamped.clone()), clamped.build().0);
+    }
+
+    #[test]
+    fn test_double_abs() {
+        assert_eq!(optimized(perlin().abs().abs()), perlin().abs().build().0);
+    }
+
+    #[test]
+    fn test_unchanged_tree_is_shared() {
+        let tree = perlin().fbm(0.5, 0.0, 3, 2.0).domain_scale(2.0).build().0;
+        assert!(Arc::ptr_eq(&tree.optimize().unwrap().0, &tree.0));
+    }
+
+    #[test]
+    fn test_encoded_tree_kept() {
+        let mut encoded = Node::from_name("Constant").unwrap();
+        encoded.encoded = Some("EQACAAAAAAAg".into());
+        let encoded = SafeNode(encoded.into());
+        let tree = (GeneratorWrapper(encoded) + 1.0).build().0;
+        assert_eq!(tree.optimize().unwrap(), tree);
+    }
+
+    #[test]
+    fn test_optimized_output() {
+        let generators = [
+            (-(-perlin()) + 0.0).build().0,
+            ((simplex() * 2.0) * 0.5 + 0.25 + 0.25).build().0,
+            perlin()
+                .domain_scale(2.0)
+                .domain_scale(0.25)
+                .remap(-1.0, 1.0, 0.0, 1.0)
+                .remap(0.0, 1.0, -2.0, 2.0)
+                .abs()
+                .abs()
+                .build()
+                .0,
+            (perlin() + (constant(0.5) * 2.0))
+                .max(constant(1.0) - 2.0)
+                .build()
+                .0,
+        ];
+        for node in generators {
+            let optimized = node.optimize().unwrap();
+            assert_ne!(optimized, node);
+            for (a, b) in generate_output(&node)
+                .iter()
+                .zip(generate_output(&optimized))
+            {
+                assert!((a - b).abs() < 1e-5, "{a} != {b}");
+            }
+            for (a, b) in generate_output_3d(&node)
+                .iter()
+                .zip(generate_output_3d(&optimized))
+            {
+                assert!((a - b).abs() < 1e-5, "{a} != {b}");
+            }
+        }
+    }
+}
@@ -0,0 +1,479 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{
+    generator::{Generator, GeneratorWrapper},
+    metadata::{Member, MemberSlot, MemberValue, RecordedValue},
+    FastNoiseError, MemberType, Node, SafeNode,
+};
+
+/// Handle to a member of an already built tree, returned by [`SafeNode::param`].
+///
+/// Setting a value updates the FastNoise2 node in place. If the node is shared, by clones of the [`SafeNode`] or by
+/// several branches of the tree, it is copied first along with its ancestors, so other holders keep the old values.
+///
+/// # Examples
+///
+/// ```rust
+/// use fastnoise2::generator::prelude::*;
+///
+/// let mut node = perlin().fbm(0.5, 0.0, 4, 2.0).build().0;
+/// let before = node.clone();
+///
+/// node.param("root.Gain")?.set(0.65)?;
+/// node.param("root.Source.FeatureScale")?.set(50.0)?;
+///
+/// // `before` still uses the original values.
+/// assert_ne!(
+///     before.gen_single_2d(10.0, 10.0, 1337),
+///     node.gen_single_2d(10.0, 10.0, 1337)
+/// );
+/// # Ok::<(), fastnoise2::FastNoiseError>(())
+/// ```
+pub struct Param<'a> {
+    root: &'a mut SafeNode,
+    path: String,
+    /// Members leading from the root to the node holding the parameter.
+    nodes: Vec<MemberSlot>,
+    member: &'static Member,
+}
+
+impl SafeNode {
+    /// Returns a handle to set the member at `path`.
+    ///
+    /// A path is a list of member names separated by `.` or `/`, optionally starting with `root`. The last one is the
+    /// member to set, the others are node members leading to it from the root, e.g. `root.Source.Gain`.
+    ///
+    /// Paths can't go inside an encoded node tree: only the members of its root can be set.
+    ///
+    /// # Errors
+    /// Returns [`FastNoiseError::InvalidPath`] if a member on the path doesn't hold a node known on the Rust side,
+    /// which is the case of the children of an encoded node tree, or [`FastNoiseError::MemberNameNotFound`] if a
+    /// member doesn't exist.
+    pub fn param(&mut self, path: &str) -> Result<Param<'_>, FastNoiseError> {
+        let names = parse_path(path)?;
+        let (member_name, node_names) = names.split_last().expect("paths are not empty");
+        let (nodes, node) = resolve(&self.0, node_names, path)?;
+        let member = node.member(member_name)?;
+
+        Ok(Param {
+            root: self,
+            path: path.to_string(),
+            nodes,
+            member,
+        })
+    }
+}
+
+/// Editing of node trees by path.
+///
+/// Paths are the same as for [`SafeNode::param`], but name a node instead of a value, e.g. `LHS/Source`, and can be
+/// `root` (or empty) for the tree itself. They can only go through nodes known on the Rust side: the children of an
+/// encoded node tree can't be addressed, only replaced as a whole through the members of its root.
+///
+/// Like [`Param::set`], edits copy the shared nodes on the path first, so clones of the tree are not affected.
+///
+/// # Examples
+///
+/// ```rust
+/// use fastnoise2::generator::prelude::*;
+///
+/// let mut node = (perlin().fbm(0.5, 0.0, 4, 2.0) + simplex()).build().0;
+///
+/// // Offset the seed of one branch only.
+/// node.param("LHS/Source/SeedOffset")?.set(10)?;
+/// // Zoom out the other one, then change our mind.
+/// node.insert_above("RHS", |rhs| rhs.domain_scale(0.5))?;
+/// node.remove_wrapper("RHS", "Source")?;
+/// // Swap the fractal's source.
+/// node.replace("LHS/Source", supersimplex())?;
+/// # Ok::<(), fastnoise2::FastNoiseError>(())
+/// ```
+impl SafeNode {
+    /// Returns the node at `path`.
+    pub fn subtree(&self, path: &str) -> Result<SafeNode, FastNoiseError> {
+        let names = parse_node_path(path)?;
+        let (_, node) = resolve(&self.0, &names, path)?;
+        Ok(SafeNode(node.clone()))
+    }
+
+    /// Replaces the node at `path` by the tree built from `generator`.
+    ///
+    /// # Errors
+    /// Returns the error of [`Generator::try_build`], or an error if the path is invalid or the member doesn't accept
+    /// a node.
+    pub fn replace<G: Generator>(
+        &mut self,
+        path: &str,
+        generator: G,
+    ) -> Result<(), FastNoiseError> {
+        if parse_node_path(path)?.is_empty() {
+            *self = generator.try_build()?.0;
+            return Ok(());
+        }
+        self.param(path)?.set(generator)
+    }
+
+    /// Replaces the node at `path` by the generator returned by `wrap`, which is given that node, e.g. to insert a
+    /// [`DomainScale`][crate::generator::modifier::DomainScale] above it.
+    pub fn insert_above<G, F>(&mut self, path: &str, wrap: F) -> Result<(), FastNoiseError>
+    where
+        G: Generator,
+        F: FnOnce(GeneratorWrapper<SafeNode>) -> GeneratorWrapper<G>,
+    {
+        let node = self.subtree(path)?;
+        self.replace(path, wrap(node.into()))
+    }
+
+    /// Replaces the node at `path` by its child at `member`, removing a wrapper such as a
+    /// [`DomainScale`][crate::generator::modifier::DomainScale] from its `Source`.
+    pub fn remove_wrapper(&mut self, path: &str, member: &str) -> Result<(), FastNoiseError> {
+        let node = self.subtree(path)?;
+        let member = node.0.member(member)?;
+        let child = node.0.child(member).ok_or_else(|| {
+            invalid_path(
+                path,
+                format!("member '{}' doesn't hold a known node", member.name),
+            )
+        })?;
+        self.replace(path, SafeNode(child.clone()))
+    }
+}
+
+impl Param<'_> {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Type of the values accepted by [`Param::set`].
+    pub fn member_type(&self) -> MemberType {
+        self.member.member_type
+    }
+
+    /// Sets the value of the member, copying shared nodes first.
+    ///
+    /// # Errors
+    /// Returns an error if `value`'s type doesn't match [`Param::member_type`], or if FastNoise2 fails to create a copy
+    /// of a shared node.
+    #[allow(private_bounds)]
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "trace", skip(self)))]
+    pub fn set<V>(&mut self, value: V) -> Result<(), FastNoiseError>
+    where
+        V: MemberValue + Debug,
+    {
+        // Instances of a stateful tree made by other threads from the old values hold a weak reference to the root,
+        // so it is copied like a shared node, which invalidates them. The copy is unique, later sets reuse it.
+        let node = node_at_mut(&mut self.root.0, &self.nodes)?;
+        value.apply(node, self.member)?;
+        if node.stateful {
+            // The new value holds a cache, the whole path must now be instantiated per thread.
+            mark_stateful(&mut self.root.0, &self.nodes);
+        }
+        Ok(())
+    }
+}
+
+/// Splits a member path such as `root.Source.Gain` or `Source/Gain` into member names.
+pub(crate) fn parse_path(path: &str) -> Result<Vec<&str>, FastNoiseError> {
+    let names = parse_node_path(path)?;
+    if names.is_empty() {
+        return Err(invalid_path(path, "expected a member name after 'root'"));
+    }
+    Ok(names)
+}
+
+/// Splits a node path into member names, the root itself being `root` or an empty path.
+pub(crate) fn parse_node_path(path: &str) -> Result<Vec<&str>, FastNoiseError> {
+    if path.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<&str> = path.split(['.', '/']).map(str::trim).collect();
+    if names
+        .first()
+        .is_some_and(|name| name.eq_ignore_ascii_case("root"))
+    {
+        names.remove(0);
+    }
+    if names.iter().any(|name| name.is_empty()) {
+        return Err(invalid_path(
+            path,
+            "expected member names separated by '.' or '/'",
+        ));
+    }
+    Ok(names)
+}
+
+fn invalid_path(path: &str, reason: impl Into<String>) -> FastNoiseError {
+    FastNoiseError::InvalidPath {
+        path: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+/// Follows the node members `names` from `node`, returning the slots taken and the node reached.
+fn resolve<'a>(
+    mut node: &'a Arc<Node>,
+    names: &[&str],
+    path: &str,
+) -> Result<(Vec<MemberSlot>, &'a Arc<Node>), FastNoiseError> {
+    let mut slots = Vec::with_capacity(names.len());
+    for name in names {
+        let member = node.member(name)?;
+        if node.encoded.is_some() && node.child(member).is_none() {
+            return Err(invalid_path(
+                path,
+                format!(
+                    "member '{}' is inside an encoded node tree, only the members of its root can be set",
+                    member.name
+                ),
+            ));
+        }
+        node = node.child(member).ok_or_else(|| {
+            invalid_path(
+                path,
+                format!("member '{}' doesn't hold a known node", member.name),
+            )
+        })?;
+        slots.push(member.slot());
+    }
+    Ok((slots, node))
+}
+
+/// Returns `node` ready to be modified, replacing it by a copy if it is shared.
+pub(crate) fn make_mut(node: &mut Arc<Node>) -> Result<&mut Node, FastNoiseError> {
+    if Arc::get_mut(node).is_none() {
+        *node = node.shallow_copy()?.into();
+    }
+    Ok(Arc::get_mut(node).expect("copies are not shared"))
+}
+
+/// Returns the node reached from `node` through the `nodes` members, copying shared nodes on the way.
+pub(crate) fn node_at_mut<'a>(
+    node: &'a mut Arc<Node>,
+    nodes: &[MemberSlot],
+) -> Result<&'a mut Node, FastNoiseError> {
+    let node = make_mut(node)?;
+    let Some((&slot, nodes)) = nodes.split_first() else {
+        return Ok(node);
+    };
+
+    let Some(RecordedValue::Node(child)) = node.members.get_mut(&slot) else {
+        unreachable!("paths are resolved when creating a `Param`");
+    };
+    if Arc::get_mut(child).is_none() {
+        let copy: Arc<Node> = child.shallow_copy()?.into();
+        // Relink the parent to the copy, the other holders of the child keep the original.
+        node.apply_recorded(slot, RecordedValue::Node(copy))?;
+    }
+    let Some(RecordedValue::Node(child)) = node.members.get_mut(&slot) else {
+        unreachable!("a node was just set");
+    };
+    node_at_mut(child, nodes)
+}
+
+/// Marks every node from `node` through the `nodes` members as stateful. They must not be shared.
+pub(crate) fn mark_stateful(node: &mut Arc<Node>, nodes: &[MemberSlot]) {
+    let node = Arc::get_mut(node).expect("nodes on the path were made unique");
+    node.stateful = true;
+    if let Some((slot, nodes)) = nodes.split_first() {
+        if let Some(RecordedValue::Node(child)) = node.members.get_mut(slot) {
+            mark_stateful(child, nodes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encoded,
+        generator::{perlin::perlin, simplex::simplex},
+        test_utils::*,
+    };
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("root.Source.Gain").unwrap(),
+            vec!["Source", "Gain"]
+        );
+        assert_eq!(parse_path("LHS/Source").unwrap(), vec!["LHS", "Source"]);
+        assert!(parse_path("root").is_err());
+        assert!(parse_path("Source..Gain").is_err());
+    }
+
+    #[test]
+    fn test_set_in_place() {
+        let mut node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let before = generate_output(&node);
+        let handle = node.0.handle;
+
+        node.param("root.Gain").unwrap().set(0.8).unwrap();
+        assert_eq!(node.0.handle, handle);
+        assert_outputs_differ(&before, &generate_output(&node), "root.Gain");
+    }
+
+    #[test]
+    fn test_copy_on_write() {
+        let mut node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let shared = node.clone();
+        let before = generate_output(&shared);
+
+        node.param("root.Source.FeatureScale")
+            .unwrap()
+            .set(20.0)
+            .unwrap();
+        assert!(!Arc::ptr_eq(&node.0, &shared.0));
+        assert_eq!(generate_output(&shared), before);
+        assert_outputs_differ(&before, &generate_output(&node), "root.Source.FeatureScale");
+    }
+
+    #[test]
+    fn test_shared_branch() {
+        let source = perlin();
+        let mut node = source.clone().fade(source, 0.5).build().0;
+        node.param("B.SeedOffset").unwrap().set(3).unwrap();
+
+        let child = |name| {
+            let member = node.0.member(name).unwrap();
+            node.0.child(member).unwrap().handle
+        };
+        assert_ne!(child("A"), child("B"));
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        let mut node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        assert!(matches!(
+            node.param("root.Octaves.Gain"),
+            Err(FastNoiseError::InvalidPath { .. })
+        ));
+        assert!(matches!(
+            node.param("root.Gian"),
+            Err(FastNoiseError::MemberNameNotFound { .. })
+        ));
+        assert!(matches!(
+            node.param("root.Octaves").unwrap().set(0.5),
+            Err(FastNoiseError::InvalidMemberType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_set_cache_marks_path_stateful() {
+        let mut node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        assert!(!node.0.stateful);
+        node.param("Source").unwrap().set(perlin().cache()).unwrap();
+        assert!(node.0.stateful);
+    }
+
+    #[test]
+    fn test_stateful_copy_once() {
+        let mut node = perlin().cache().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let handle = node.0.handle;
+        node.param("Gain").unwrap().set(0.6).unwrap();
+        assert_eq!(node.0.handle, handle, "unused trees are edited in place");
+
+        // Another thread instantiates the tree and keeps its instance, which must not keep the old values.
+        let (instantiated, wait) = std::sync::mpsc::channel();
+        let (release, done) = std::sync::mpsc::channel::<()>();
+        let shared = node.clone();
+        let thread = std::thread::spawn(move || {
+            generate_output(&shared);
+            drop(shared);
+            instantiated.send(()).unwrap();
+            done.recv().unwrap();
+        });
+        wait.recv().unwrap();
+
+        let mut param = node.param("Gain").unwrap();
+        param.set(0.7).unwrap();
+        let copy = param.root.0.handle;
+        param.set(0.8).unwrap();
+        assert_ne!(copy, handle);
+        assert_eq!(
+            param.root.0.handle, copy,
+            "the copy is reused by later sets"
+        );
+        release.send(()).unwrap();
+        thread.join().unwrap();
+
+        let expected = perlin().cache().fbm(0.8, 0.0, 3, 2.0).build();
+        assert_eq!(generate_output(&node), generate_output(&expected));
+    }
+
+    #[test]
+    fn test_subtree() {
+        let node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        assert!(Arc::ptr_eq(&node.subtree("root").unwrap().0, &node.0));
+        assert!(Arc::ptr_eq(&node.subtree("").unwrap().0, &node.0));
+        let source = node.subtree("Source").unwrap();
+        assert_eq!(generate_output(&source), generate_output(&perlin().build()));
+    }
+
+    #[test]
+    fn test_replace() {
+        let mut node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let original = node.clone();
+        node.replace("Source", simplex()).unwrap();
+
+        let expected = simplex().fbm(0.5, 0.0, 3, 2.0).build();
+        assert_eq!(generate_output(&node), generate_output(&expected));
+        assert_outputs_differ(
+            &generate_output(&original),
+            &generate_output(&node),
+            "Source",
+        );
+
+        node.replace("root", perlin()).unwrap();
+        assert_eq!(generate_output(&node), generate_output(&perlin().build()));
+    }
+
+    #[test]
+    fn test_insert_above_and_remove_wrapper() {
+        let mut node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let before = generate_output(&node);
+
+        node.insert_above("Source", |source| source.domain_scale(2.0))
+            .unwrap();
+        let expected = perlin().domain_scale(2.0).fbm(0.5, 0.0, 3, 2.0).build();
+        assert_eq!(generate_output(&node), generate_output(&expected));
+
+        node.remove_wrapper("Source", "Source").unwrap();
+        assert_eq!(generate_output(&node), before);
+    }
+
+    #[test]
+    fn test_edit_one_branch() {
+        let mut node = (perlin() + perlin()).build().0;
+        node.param("LHS/SeedOffset").unwrap().set(10).unwrap();
+
+        let expected = (perlin().with_seed_offset(10) + perlin()).build();
+        assert_eq!(generate_output(&node), generate_output(&expected));
+    }
+
+    #[test]
+    fn test_edit_encoded_subtree_root() {
+        let encoded = encoded::encode_tree(&perlin().build().0 .0); // Simple Perlin
+        let source = SafeNode::from_encoded_node_tree(&encoded).unwrap();
+        let mut node = GeneratorWrapper(source).fbm(0.5, 0.0, 3, 2.0).build().0;
+        let before = generate_output(&node);
+        node.param("Source/SeedOffset").unwrap().set(10).unwrap();
+        assert_outputs_differ(&before, &generate_output(&node), "Source/SeedOffset");
+    }
+
+    #[test]
+    fn test_path_inside_encoded_tree() {
+        // A decoded fractal, whose source is not known on the Rust side.
+        let mut root = Node::from_name("FractalFBm").unwrap();
+        root.encoded = Some("".into());
+        let mut node = SafeNode(root.into());
+
+        node.param("Gain").unwrap().set(0.6).unwrap();
+        let error = node.param("Source/SeedOffset").err().unwrap();
+        assert!(matches!(error, FastNoiseError::InvalidPath { .. }));
+        assert!(error.to_string().contains("inside an encoded node tree"));
+        assert!(matches!(
+            node.subtree("Source"),
+            Err(FastNoiseError::InvalidPath { .. })
+        ));
+    }
+}
@@ -1,4 +1,10 @@
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::{Arc, Weak},
+    thread,
+};
 
 use crate::{FastNoiseError, Node, OutputMinMax};
 
@@ -8,12 +14,35 @@ use crate::{FastNoiseError, Node, OutputMinMax};
 /// You can create and test node trees using the [Web WASM Node Editor](https://auburn.github.io/fastnoise2nodeeditor/) or download desktop binaries from [FastNoise2 Releases](https://github.com/Auburn/FastNoise2/releases/latest).
 ///
 /// You can see how to use it in the [`generator`][`crate::generator`] module.
+///
+/// # Multithreading
+///
+/// Cloning a [`SafeNode`] doesn't create new nodes, clones can be sent to other threads and generate noise
+/// concurrently. Trees that keep state between generation calls, i.e. containing a
+/// [`GeneratorCache`][crate::generator::modifier::GeneratorCache], are the exception: each other thread transparently
+/// generates with its own instance of the stateful nodes, created the first time it uses the tree. Trees decoded
+/// from an encoded node tree can't be inspected, so they are always instantiated once per thread.
+///
+/// A thread keeps its instances, and their caches, after the original tree is dropped: they are released when the
+/// thread exits, or the next time it instantiates a tree. Generating with a stateful tree on another thread panics
+/// if FastNoise2 fails to create the instance.
 #[derive(Debug, Clone)]
 pub struct SafeNode(pub(crate) Arc<Node>);
 
 unsafe impl Send for SafeNode {}
 unsafe impl Sync for SafeNode {}
 
+/// Instance of a stateful tree for the current thread.
+struct Instance {
+    original: Weak<Node>,
+    node: Rc<Node>,
+}
+
+thread_local! {
+    /// Instances of stateful trees created by other threads, by address of the original node.
+    static INSTANCES: RefCell<HashMap<usize, Instance>> = RefCell::new(HashMap::new());
+}
+
 impl SafeNode {
     /// Creates a [`SafeNode`] instance from an encoded node tree.
     ///
@@ -25,10 +54,34 @@ impl SafeNode {
             .map(Self)
     }
 
+    /// Creates a [`SafeNode`] instance from an encoded node tree, requesting a specific SIMD level.
+    ///
+    /// Pass `u32::MAX` to let FastNoise2 pick the highest level supported by the CPU.
+    ///
+    /// # Errors
+    /// Returns an error if the encoded node tree is invalid or if creation fails.
+    pub fn from_encoded_node_tree_with_simd_level(
+        encoded_node_tree: &str,
+        simd_level: u32,
+    ) -> Result<Self, FastNoiseError> {
+        Node::from_encoded_node_tree_with_simd_level(encoded_node_tree, simd_level)
+            .map(Arc::new)
+            .map(Self)
+    }
+
     pub fn get_simd_level(&self) -> u32 {
         self.0.get_simd_level()
     }
 
+    /// Creates an independent copy of this tree, unlike [`Clone::clone`] which shares the nodes. See
+    /// [`Node::deep_clone`].
+    ///
+    /// # Errors
+    /// Returns an error if FastNoise2 fails to create one of the nodes.
+    pub fn deep_clone(&self) -> Result<Self, FastNoiseError> {
+        self.0.deep_clone().map(Arc::new).map(Self)
+    }
+
     /// # Panics
     /// Panics if `noise_out.len() < x_count * y_count`.
     pub fn gen_uniform_grid_2d(
@@ -44,8 +97,8 @@ impl SafeNode {
     ) -> OutputMinMax {
         assert!(noise_out.len() >= (x_count * y_count) as usize);
 
-        unsafe {
-            self.0.gen_uniform_grid_2d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_uniform_grid_2d_unchecked(
                 noise_out,
                 x_offset,
                 y_offset,
@@ -55,7 +108,7 @@ impl SafeNode {
                 y_step_size,
                 seed,
             )
-        }
+        })
     }
 
     /// # Panics
@@ -76,8 +129,8 @@ impl SafeNode {
     ) -> OutputMinMax {
         assert!(noise_out.len() >= (x_count * y_count * z_count) as usize);
 
-        unsafe {
-            self.0.gen_uniform_grid_3d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_uniform_grid_3d_unchecked(
                 noise_out,
                 x_offset,
                 y_offset,
@@ -90,7 +143,7 @@ impl SafeNode {
                 z_step_size,
                 seed,
             )
-        }
+        })
     }
 
     /// # Panics
@@ -114,8 +167,8 @@ impl SafeNode {
     ) -> OutputMinMax {
         assert!(noise_out.len() >= (x_count * y_count * z_count * w_count) as usize);
 
-        unsafe {
-            self.0.gen_uniform_grid_4d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_uniform_grid_4d_unchecked(
                 noise_out,
                 x_offset,
                 y_offset,
@@ -131,7 +184,7 @@ impl SafeNode {
                 w_step_size,
                 seed,
             )
-        }
+        })
     }
 
     /// # Panics
@@ -147,8 +200,8 @@ impl SafeNode {
     ) -> OutputMinMax {
         assert!(noise_out.len() == x_pos_array.len() && x_pos_array.len() == y_pos_array.len());
 
-        unsafe {
-            self.0.gen_position_array_2d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_position_array_2d_unchecked(
                 noise_out,
                 x_pos_array,
                 y_pos_array,
@@ -156,7 +209,7 @@ impl SafeNode {
                 y_offset,
                 seed,
             )
-        }
+        })
     }
 
     /// # Panics
@@ -178,8 +231,8 @@ impl SafeNode {
                 && y_pos_array.len() == z_pos_array.len()
         );
 
-        unsafe {
-            self.0.gen_position_array_3d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_position_array_3d_unchecked(
                 noise_out,
                 x_pos_array,
                 y_pos_array,
@@ -189,7 +242,7 @@ impl SafeNode {
                 z_offset,
                 seed,
             )
-        }
+        })
     }
 
     /// # Panics
@@ -213,8 +266,8 @@ impl SafeNode {
                 && y_pos_array.len() == z_pos_array.len()
         );
 
-        unsafe {
-            self.0.gen_position_array_4d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_position_array_4d_unchecked(
                 noise_out,
                 x_pos_array,
                 y_pos_array,
@@ -226,7 +279,7 @@ impl SafeNode {
                 w_offset,
                 seed,
             )
-        }
+        })
     }
 
     /// # Panics
@@ -242,8 +295,8 @@ impl SafeNode {
     ) -> OutputMinMax {
         assert!(noise_out.len() >= (x_size * y_size) as usize);
 
-        unsafe {
-            self.0.gen_tileable_2d_unchecked(
+        self.with_instance(|node| unsafe {
+            node.gen_tileable_2d_unchecked(
                 noise_out,
                 x_size,
                 y_size,
@@ -251,19 +304,54 @@ impl SafeNode {
                 y_step_size,
                 seed,
             )
-        }
+        })
     }
 
     pub fn gen_single_2d(&self, x: f32, y: f32, seed: i32) -> f32 {
-        unsafe { self.0.gen_single_2d_unchecked(x, y, seed) }
+        self.with_instance(|node| unsafe { node.gen_single_2d_unchecked(x, y, seed) })
     }
 
     pub fn gen_single_3d(&self, x: f32, y: f32, z: f32, seed: i32) -> f32 {
-        unsafe { self.0.gen_single_3d_unchecked(x, y, z, seed) }
+        self.with_instance(|node| unsafe { node.gen_single_3d_unchecked(x, y, z, seed) })
     }
 
     pub fn gen_single_4d(&self, x: f32, y: f32, z: f32, w: f32, seed: i32) -> f32 {
-        unsafe { self.0.gen_single_4d_unchecked(x, y, z, w, seed) }
+        self.with_instance(|node| unsafe { node.gen_single_4d_unchecked(x, y, z, w, seed) })
+    }
+
+    /// Calls `f` with the node this thread must generate with, see [Multithreading](SafeNode#multithreading).
+    ///
+    /// # Panics
+    /// Panics if the tree is stateful and FastNoise2 fails to create the instance of this thread.
+    fn with_instance<R>(&self, f: impl FnOnce(&Node) -> R) -> R {
+        if !self.0.stateful || self.0.owner == thread::current().id() {
+            return f(&self.0);
+        }
+
+        let key = Arc::as_ptr(&self.0) as usize;
+        let instance = INSTANCES.with_borrow_mut(|instances| {
+            if let Some(instance) = instances.get(&key) {
+                // The address may have been reused by another tree since the original was dropped.
+                if instance.original.strong_count() > 0
+                    && Weak::as_ptr(&instance.original) == Arc::as_ptr(&self.0)
+                {
+                    return instance.node.clone();
+                }
+            }
+            instances.retain(|_, instance| instance.original.strong_count() > 0);
+
+            // Sharing the original would race on its state with the owner thread.
+            let node = Rc::new(self.0.replicate_stateful().unwrap_or_else(|error| {
+                panic!("failed to create the instance of a stateful tree for this thread: {error}")
+            }));
+            let instance = Instance {
+                original: Arc::downgrade(&self.0),
+                node: node.clone(),
+            };
+            instances.insert(key, instance);
+            node
+        });
+        f(&instance)
     }
 }
 
@@ -271,18 +359,16 @@ impl SafeNode {
 mod tests {
     use super::*;
     use crate::{
+        encoded,
         generator::{perlin::perlin, Generator},
         test_utils::*,
     };
 
     #[test]
     fn test_encoded_node_tree() {
-        let encoded = "DQAFAAAAAAAAQAgAAAAAAD8="; // Simple Perlin
-        let node = SafeNode::from_encoded_node_tree(encoded);
-        // This might fail if the encoded string is invalid
-        if let Ok(node) = node {
-            test_generator_produces_output(node);
-        }
+        let encoded = encoded::encode_tree(&perlin().build().0 .0); // Simple Perlin
+        let node = SafeNode::from_encoded_node_tree(&encoded).unwrap();
+        test_generator_produces_output(node);
     }
 
     #[test]
@@ -408,6 +494,52 @@ mod tests {
         assert!(value.is_finite());
     }
 
+    #[test]
+    fn test_stateful_tree() {
+        assert!(!perlin().build().0 .0.stateful);
+        assert!(perlin().cache().build().0 .0.stateful);
+        assert!(perlin().cache().fbm(0.5, 0.0, 3, 2.0).build().0 .0.stateful);
+    }
+
+    #[test]
+    fn test_per_thread_instance() {
+        let node = perlin().cache().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let expected = generate_output(&node);
+        let original = node.with_instance(|node| node.handle as usize);
+
+        let (output, instances) = std::thread::spawn(move || {
+            let instances = [
+                node.with_instance(|node| node.handle as usize),
+                node.with_instance(|node| node.handle as usize),
+            ];
+            (generate_output(&node), instances)
+        })
+        .join()
+        .unwrap();
+        assert_eq!(output, expected);
+        assert_ne!(instances[0], original);
+        assert_eq!(instances[0], instances[1]);
+    }
+
+    #[test]
+    fn test_stateless_tree_shared_between_threads() {
+        let node = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let original = node.with_instance(|node| node.handle as usize);
+        let instance = std::thread::spawn(move || node.with_instance(|node| node.handle as usize))
+            .join()
+            .unwrap();
+        assert_eq!(instance, original);
+    }
+
+    #[test]
+    fn test_deep_clone() {
+        let node = perlin().fbm(0.5, 0.0, 3, 2.0).cache().build().0;
+        let copy = node.deep_clone().unwrap();
+        assert!(!Arc::ptr_eq(&node.0, &copy.0));
+        assert!(copy.0.stateful);
+        assert_eq!(generate_output(&node), generate_output(&copy));
+    }
+
     #[test]
     fn test_get_simd_level() {
         let node = perlin().build();
@@ -0,0 +1,1186 @@
+//! JSON Schema of node tree documents, generated from the metadata of the linked FastNoise2 library.
+//!
+//! External tools such as config editors can validate node trees against [`json_schema`], and stay in sync with the
+//! nodes, members and enum values of the linked library. A node tree document is a JSON object naming its node, with
+//! its members by the names accepted by [`Node::set`][crate::Node::set]:
+//!
+//! ```json
+//! {
+//!   "node": "FractalFBm",
+//!   "Source": { "node": "Perlin", "FeatureScale": 50 },
+//!   "Gain": 0.5,
+//!   "WeightedStrength": 0,
+//!   "Octaves": 3,
+//!   "Lacunarity": 2
+//! }
+//! ```
+//!
+//! - Float and int members are numbers, and enum members the name of their value as given by FastNoise2, e.g.
+//!   `"Euclidean Squared"`.
+//! - Node members are nested trees, and hybrid members either numbers or nested trees.
+//! - Per-dimension members are separate properties, e.g. `OffsetX` to `OffsetW`.
+//! - A tree exported by the Node Editor is an object holding its encoded string, e.g. `{ "encoded": "DQAFAAAA..." }`.
+//!
+//! Node members are required. Other members are optional, with the default of the typed generators when they have
+//! one, e.g. `100` for `FeatureScale`.
+//!
+//! [`SafeNode::from_json`] builds the tree of a document:
+//!
+//! ```rust
+//! use fastnoise2::{generator::prelude::*, SafeNode};
+//!
+//! let node = SafeNode::from_json(
+//!     r#"{ "node": "FractalFBm", "Source": { "node": "Perlin", "FeatureScale": 50 },
+//!          "Gain": 0.5, "WeightedStrength": 0, "Octaves": 3, "Lacunarity": 2 }"#,
+//! )
+//! .unwrap();
+//! assert_eq!(node, perlin().with_feature_scale(50.0).fbm(0.5, 0.0, 3, 2.0).build().0);
+//! ```
+//!
+//! Documents are read and written by a small JSON implementation in this module rather than serde_json, so that the
+//! crate depends on little more than the FastNoise2 bindings. It follows RFC 8259, and is tested against serde_json on
+//! random and mutated documents. Unlike serde_json, it rejects duplicate properties and numbers out of the range of an
+//! [`f32`], and like it, nesting deeper than 127 levels. Float and hybrid members accept integers up to 2^24 in
+//! magnitude, above which not every integer is an [`f32`].
+use std::{fmt, iter::Peekable, str::CharIndices};
+
+use crate::{
+    codegen::ordered_members,
+    library_info,
+    metadata::{format_lookup, DefaultValue, Member, Metadata, NODE_METADATA},
+    FastNoiseError, MemberType, Node, SafeNode,
+};
+
+const DIMENSIONS: [char; 4] = ['X', 'Y', 'Z', 'W'];
+
+/// Largest integer magnitude read exactly into a float member, 2^24.
+const MAX_EXACT_FLOAT_INT: u32 = 1 << 24;
+
+/// Maximum nesting of arrays and objects in [`Json::parse`], as in serde_json.
+const MAX_DEPTH: usize = 127;
+
+/// Returns a JSON Schema (draft 2020-12) of the node tree documents described in the [module](self) documentation,
+/// covering every node of the linked library.
+///
+/// # Examples
+///
+/// ```rust
+/// let schema = fastnoise2::schema::json_schema();
+/// std::fs::write(std::env::temp_dir().join("fastnoise2.schema.json"), schema).unwrap();
+/// ```
+pub fn json_schema() -> String {
+    schema().to_string()
+}
+
+fn schema() -> Json {
+    let mut nodes: Vec<_> = NODE_METADATA.iter().collect();
+    nodes.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+
+    let info = library_info();
+    let commit = info.commit.unwrap_or(info.bundled_commit);
+    let mut defs = vec![
+        (
+            "Node".to_string(),
+            Json::object([(
+                "oneOf",
+                Json::Array(
+                    std::iter::once(reference("EncodedNodeTree"))
+                        .chain(
+                            nodes
+                                .iter()
+                                .map(|metadata| reference(&metadata.display_name)),
+                        )
+                        .collect(),
+                ),
+            )]),
+        ),
+        (
+            "EncodedNodeTree".to_string(),
+            Json::object([
+                ("type", Json::from("object")),
+                (
+                    "properties",
+                    Json::object([(
+                        "encoded",
+                        Json::object([
+                            ("type", Json::from("string")),
+                            (
+                                "description",
+                                Json::from("Encoded node tree exported by the Node Editor"),
+                            ),
+                        ]),
+                    )]),
+                ),
+                ("required", Json::Array(vec![Json::from("encoded")])),
+                ("additionalProperties", Json::Bool(false)),
+            ]),
+        ),
+    ];
+    defs.extend(
+        nodes
+            .iter()
+            .map(|metadata| (metadata.display_name.clone(), node_schema(metadata))),
+    );
+
+    Json::object([
+        (
+            "$schema",
+            Json::from("https://json-schema.org/draft/2020-12/schema"),
+        ),
+        ("title", Json::from("FastNoise2 node tree")),
+        (
+            "description",
+            Json::String(format!("Node tree of FastNoise2 at commit {commit}")),
+        ),
+        ("$ref", ref_path("Node")),
+        ("$defs", Json::Object(defs)),
+    ])
+}
+
+fn node_schema(metadata: &Metadata) -> Json {
+    let members = ordered_members(metadata);
+    let mut properties = vec![(
+        "node".to_string(),
+        Json::object([("const", Json::from(metadata.display_name.as_str()))]),
+    )];
+    properties.extend(
+        members
+            .iter()
+            .map(|member| (member.set_name(), member_schema(member))),
+    );
+    let required = std::iter::once(Json::from("node"))
+        .chain(
+            members
+                .iter()
+                .filter(|member| matches!(member.member_type, MemberType::NodeLookup))
+                .map(|member| Json::String(member.set_name())),
+        )
+        .collect();
+
+    Json::object([
+        ("type", Json::from("object")),
+        ("properties", Json::Object(properties)),
+        ("required", Json::Array(required)),
+        ("additionalProperties", Json::Bool(false)),
+    ])
+}
+
+fn member_schema(member: &Member) -> Json {
+    let mut schema = match member.member_type {
+        MemberType::Float => vec![("type", Json::from("number"))],
+        MemberType::Int => vec![("type", Json::from("integer"))],
+        MemberType::Enum => vec![(
+            "enum",
+            Json::Array(
+                member
+                    .enum_display_names
+                    .iter()
+                    .map(|name| Json::from(name.as_str()))
+                    .collect(),
+            ),
+        )],
+        MemberType::NodeLookup => vec![("$ref", ref_path("Node"))],
+        MemberType::Hybrid => vec![(
+            "oneOf",
+            Json::Array(vec![
+                Json::object([("type", Json::from("number"))]),
+                reference("Node"),
+            ]),
+        )],
+    };
+    if let Some(dimension) = member.dimension {
+        schema.push((
+            "description",
+            Json::String(format!(
+                "{} component of {}",
+                DIMENSIONS[dimension], member.display_name
+            )),
+        ));
+    }
+    if let Some(default) = default_value(member) {
+        schema.push(("default", default));
+    }
+    Json::object(schema)
+}
+
+/// Default of an optional member, or `None` for a member without default or a variant the library doesn't have.
+fn default_value(member: &Member) -> Option<Json> {
+    match (member.default_value()?, member.member_type) {
+        (DefaultValue::Float(value), MemberType::Float | MemberType::Hybrid) => {
+            value.is_finite().then_some(Json::Float(*value))
+        }
+        (DefaultValue::Int(value), MemberType::Int) => Some(Json::Int(*value)),
+        (DefaultValue::Variant(variant), MemberType::Enum) => member
+            .enum_display_names
+            .iter()
+            .find(|name| format_lookup(name) == *variant)
+            .map(|name| Json::from(name.as_str())),
+        _ => None,
+    }
+}
+
+impl SafeNode {
+    /// Builds the tree of a node tree document, see the [`schema`](crate::schema) module. Omitted members with a
+    /// default are set to it, so that a document builds the same tree as the typed generators it describes.
+    ///
+    /// # Errors
+    /// Returns [`FastNoiseError::InvalidDocument`] if the document is not valid JSON, doesn't describe a tree, or
+    /// gives a float member an integer above 2^24 in magnitude, the
+    /// errors of [`SafeNode::from_encoded_node_tree`] for encoded trees, and the errors of [`Node::set`] wrapped in
+    /// [`FastNoiseError::BuildFailed`], naming the node and member that failed.
+    pub fn from_json(document: &str) -> Result<SafeNode, FastNoiseError> {
+        let json =
+            Json::parse(document).map_err(|reason| FastNoiseError::InvalidDocument { reason })?;
+        node_from_json(&json)
+    }
+}
+
+fn node_from_json(json: &Json) -> Result<SafeNode, FastNoiseError> {
+    let invalid = |reason: String| FastNoiseError::InvalidDocument { reason };
+    let Json::Object(properties) = json else {
+        return Err(invalid(format!("expected a node object, found {json}")));
+    };
+    if let [(key, value)] = properties.as_slice() {
+        if key == "encoded" {
+            let Json::String(encoded) = value else {
+                return Err(invalid(format!(
+                    "expected an encoded string, found {value}"
+                )));
+            };
+            return SafeNode::from_encoded_node_tree(encoded);
+        }
+    }
+    let name = match properties.iter().find(|(key, _)| key == "node") {
+        Some((_, Json::String(name))) => name.as_str(),
+        Some((_, value)) => return Err(invalid(format!("expected a node name, found {value}"))),
+        None => return Err(invalid("node object without `node` property".to_string())),
+    };
+    let build_failed = |member: Option<&str>, error| FastNoiseError::BuildFailed {
+        node: name.to_string(),
+        member: member.map(str::to_string),
+        error: Box::new(error),
+    };
+
+    let mut node = Node::from_name(name).map_err(|error| build_failed(None, error))?;
+    let metadata = &NODE_METADATA[node.metadata_id as usize];
+    let mut set = Vec::new();
+    for (key, value) in properties.iter().filter(|(key, _)| key != "node") {
+        let member = node.member(key).ok();
+        set_json(&mut node, key, member, value).map_err(|error| build_failed(Some(key), error))?;
+        set.extend(member.map(|member| &member.name));
+    }
+    for member in ordered_members(metadata) {
+        if set.contains(&&member.name) {
+            continue;
+        }
+        let member_name = member.set_name();
+        let result = match (member.member_type, member.default_value()) {
+            (MemberType::NodeLookup, _) => {
+                return Err(invalid(format!(
+                    "'{name}' is missing its node member '{member_name}'"
+                )))
+            }
+            (_, Some(DefaultValue::Float(value))) => node.set(&member_name, *value),
+            (_, Some(DefaultValue::Int(value))) => node.set(&member_name, *value),
+            (_, Some(DefaultValue::Variant(variant))) => node.set(&member_name, *variant),
+            (_, None) => Ok(()),
+        };
+        result.map_err(|error| build_failed(Some(&member_name), error))?;
+    }
+    Ok(SafeNode(node.into()))
+}
+
+/// Sets the member `key`, whose metadata is `member` if it names a single member, to a JSON value.
+fn set_json(
+    node: &mut Node,
+    key: &str,
+    member: Option<&Member>,
+    value: &Json,
+) -> Result<(), FastNoiseError> {
+    let float = member
+        .is_some_and(|member| matches!(member.member_type, MemberType::Float | MemberType::Hybrid));
+    match value {
+        Json::Int(value) if float && value.unsigned_abs() > MAX_EXACT_FLOAT_INT => {
+            Err(FastNoiseError::InvalidDocument {
+                reason: format!("integer {value} for '{key}' is not exactly an f32, above 2^24"),
+            })
+        }
+        Json::Int(value) if float => node.set(key, *value as f32),
+        Json::Int(value) => node.set(key, *value),
+        Json::Float(value) => node.set(key, *value),
+        Json::String(value) => node.set(key, value.as_str()),
+        Json::Object(_) => node.set(key, node_from_json(value)?.0),
+        Json::Null | Json::Bool(_) | Json::Array(_) => Err(FastNoiseError::InvalidDocument {
+            reason: format!("unexpected value {value} for '{key}'"),
+        }),
+    }
+}
+
+/// Returns `{"$ref": "#/$defs/<name>"}`.
+fn reference(name: &str) -> Json {
+    Json::object([("$ref", ref_path(name))])
+}
+
+fn ref_path(name: &str) -> Json {
+    Json::String(format!("#/$defs/{name}"))
+}
+
+/// JSON value, written indented by [`fmt::Display`].
+#[derive(Clone, Debug, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Int(i32),
+    /// A finite float, JSON has no NaN or infinity.
+    Float(f32),
+    String(String),
+    Array(Vec<Json>),
+    /// Properties in the order they are written.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn object<K: Into<String>>(properties: impl IntoIterator<Item = (K, Json)>) -> Self {
+        Self::Object(
+            properties
+                .into_iter()
+                .map(|(key, value)| (key.into(), value))
+                .collect(),
+        )
+    }
+
+    /// Parses a JSON text. Integers in the range of an [`i32`] are read as [`Json::Int`], other numbers as
+    /// [`Json::Float`].
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            text,
+            chars: text.char_indices().peekable(),
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            Some((index, c)) => Err(format!("unexpected {c:?} at {index} after the value")),
+            None => Ok(value),
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        match self {
+            Self::Null => f.write_str("null"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Float(value) => {
+                debug_assert!(value.is_finite(), "JSON has no NaN or infinity");
+                write!(f, "{value}")
+            }
+            Self::String(value) => write_string(f, value),
+            Self::Array(items) if items.is_empty() => f.write_str("[]"),
+            Self::Array(items) => {
+                f.write_str("[\n")?;
+                for (i, item) in items.iter().enumerate() {
+                    write!(f, "{:1$}", "", indent + 2)?;
+                    item.write(f, indent + 2)?;
+                    f.write_str(if i + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                write!(f, "{:1$}]", "", indent)
+            }
+            Self::Object(properties) if properties.is_empty() => f.write_str("{}"),
+            Self::Object(properties) => {
+                f.write_str("{\n")?;
+                for (i, (key, value)) in properties.iter().enumerate() {
+                    write!(f, "{:1$}", "", indent + 2)?;
+                    write_string(f, key)?;
+                    f.write_str(": ")?;
+                    value.write(f, indent + 2)?;
+                    f.write_str(if i + 1 < properties.len() {
+                        ",\n"
+                    } else {
+                        "\n"
+                    })?;
+                }
+                write!(f, "{:1$}}}", "", indent)
+            }
+        }
+    }
+
+    /// Returns the property `key` of an object.
+    #[cfg(test)]
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(properties) => properties
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Recursive descent parser of [`Json::parse`]. Errors give the byte offset in the text.
+struct Parser<'a> {
+    text: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    /// Number of arrays and objects being read.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .next_if(|(_, c)| matches!(c, ' ' | '\t' | '\n' | '\r'))
+            .is_some()
+        {}
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((index, c)) => Err(format!("expected {expected:?} at {index}, found {c:?}")),
+            None => Err(format!("expected {expected:?}, found the end of the text")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        let Some(&(index, c)) = self.chars.peek() else {
+            return Err("expected a value, found the end of the text".to_string());
+        };
+        match c {
+            '{' | '[' if self.depth == MAX_DEPTH => Err(format!(
+                "{c:?} at {index} nests deeper than {MAX_DEPTH} levels"
+            )),
+            '{' | '[' => {
+                self.depth += 1;
+                let value = if c == '{' {
+                    self.object()
+                } else {
+                    self.array()
+                };
+                self.depth -= 1;
+                value
+            }
+            '"' => self.string().map(Json::String),
+            '-' | '0'..='9' => self.number(),
+            _ => {
+                let word: String = std::iter::from_fn(|| {
+                    self.chars
+                        .next_if(|(_, c)| c.is_ascii_alphabetic())
+                        .map(|(_, c)| c)
+                })
+                .collect();
+                match word.as_str() {
+                    "null" => Ok(Json::Null),
+                    "true" => Ok(Json::Bool(true)),
+                    "false" => Ok(Json::Bool(false)),
+                    _ => Err(format!("unexpected {c:?} at {index}")),
+                }
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut properties: Vec<(String, Json)> = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == '}').is_some() {
+            return Ok(Json::Object(properties));
+        }
+        loop {
+            self.skip_whitespace();
+            let index = self
+                .chars
+                .peek()
+                .map_or(self.text.len(), |(index, _)| *index);
+            let key = self.string()?;
+            if properties.iter().any(|(name, _)| *name == key) {
+                return Err(format!("duplicate property {key:?} at {index}"));
+            }
+            self.expect(':')?;
+            properties.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => {}
+                Some((_, '}')) => return Ok(Json::Object(properties)),
+                Some((index, c)) => {
+                    return Err(format!("expected ',' or '}}' at {index}, found {c:?}"))
+                }
+                None => return Err("unterminated object".to_string()),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.next_if(|(_, c)| *c == ']').is_some() {
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.chars.next() {
+                Some((_, ',')) => {}
+                Some((_, ']')) => return Ok(Json::Array(items)),
+                Some((index, c)) => {
+                    return Err(format!("expected ',' or ']' at {index}, found {c:?}"))
+                }
+                None => return Err("unterminated array".to_string()),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((index, '\\')) => {
+                    let c = match self.chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, '/')) => '/',
+                        Some((_, 'b')) => '\u{8}',
+                        Some((_, 'f')) => '\u{c}',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'u')) => self.unicode_escape(index)?,
+                        _ => return Err(format!("invalid escape at {index}")),
+                    };
+                    value.push(c);
+                }
+                Some((index, c)) if c < ' ' => {
+                    return Err(format!("unescaped control character at {index}"))
+                }
+                Some((_, c)) => value.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+
+    /// Reads the digits of a `\u` escape at `index`, and the low surrogate following a high one.
+    fn unicode_escape(&mut self, index: usize) -> Result<char, String> {
+        let mut code = self.hex4(index)?;
+        if (0xd800..0xdc00).contains(&code) {
+            if self.chars.next().map(|(_, c)| c) != Some('\\')
+                || self.chars.next().map(|(_, c)| c) != Some('u')
+            {
+                return Err(format!("unpaired surrogate at {index}"));
+            }
+            let low = self.hex4(index)?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(format!("unpaired surrogate at {index}"));
+            }
+            code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+        }
+        char::from_u32(code).ok_or_else(|| format!("invalid code point at {index}"))
+    }
+
+    fn hex4(&mut self, index: usize) -> Result<u32, String> {
+        (0..4).try_fold(0, |code, _| {
+            self.chars
+                .next()
+                .and_then(|(_, c)| c.to_digit(16))
+                .map(|digit| code * 16 + digit)
+                .ok_or_else(|| format!("invalid unicode escape at {index}"))
+        })
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self
+            .chars
+            .peek()
+            .map_or(self.text.len(), |(index, _)| *index);
+        let mut end = start;
+        while let Some((index, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            end = index + c.len_utf8();
+        }
+        let literal = &self.text[start..end];
+        if !is_number(literal) {
+            return Err(format!("invalid number {literal:?} at {start}"));
+        }
+        if let Ok(value) = literal.parse::<i32>() {
+            return Ok(Json::Int(value));
+        }
+        match literal.parse::<f32>() {
+            Ok(value) if value.is_finite() => Ok(Json::Float(value)),
+            Ok(_) => Err(format!(
+                "number {literal} at {start} is out of the range of an f32"
+            )),
+            Err(_) => Err(format!("invalid number {literal:?} at {start}")),
+        }
+    }
+}
+
+/// Returns `true` if `literal` follows the JSON number grammar: no leading zeros, `+`, or `.` without digits.
+fn is_number(literal: &str) -> bool {
+    fn digits(text: &str) -> (&str, &str) {
+        text.split_at(
+            text.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(text.len()),
+        )
+    }
+
+    let (integer, rest) = digits(literal.strip_prefix('-').unwrap_or(literal));
+    if integer.is_empty() || (integer.len() > 1 && integer.starts_with('0')) {
+        return false;
+    }
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => match digits(fraction) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+    match rest.strip_prefix(['e', 'E']) {
+        Some(exponent) => {
+            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+            matches!(digits(exponent), (digits, "") if !digits.is_empty())
+        }
+        None => rest.is_empty(),
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::generator::{prelude::*, DistanceFunction};
+
+    fn def(name: &str) -> Json {
+        schema()
+            .get("$defs")
+            .and_then(|defs| defs.get(name))
+            .cloned()
+            .unwrap_or_else(|| panic!("no definition of {name}"))
+    }
+
+    fn property(node: &str, member: &str) -> Json {
+        def(node)
+            .get("properties")
+            .and_then(|properties| properties.get(member))
+            .cloned()
+            .unwrap_or_else(|| panic!("no property {member} in {node}"))
+    }
+
+    #[test]
+    fn test_every_node() {
+        let Some(Json::Array(nodes)) = def("Node").get("oneOf").cloned() else {
+            panic!("Node is not a oneOf");
+        };
+        assert_eq!(nodes.len(), NODE_METADATA.len() + 1);
+        assert_eq!(nodes[0], reference("EncodedNodeTree"));
+        for metadata in NODE_METADATA.iter() {
+            assert!(nodes.contains(&reference(&metadata.display_name)));
+            let node = def(&metadata.display_name);
+            let Some(Json::Object(properties)) = node.get("properties") else {
+                panic!("{} has no properties", metadata.display_name);
+            };
+            // The node name, and every member.
+            assert_eq!(properties.len(), metadata.members.len() + 1);
+        }
+    }
+
+    #[test]
+    fn test_member_types() {
+        assert_eq!(
+            property("FractalFBm", "node"),
+            Json::object([("const", Json::from("FractalFBm"))])
+        );
+        assert_eq!(property("FractalFBm", "Source"), reference("Node"));
+        assert_eq!(
+            property("FractalFBm", "Gain"),
+            Json::object([(
+                "oneOf",
+                Json::Array(vec![
+                    Json::object([("type", Json::from("number"))]),
+                    reference("Node"),
+                ]),
+            )])
+        );
+        assert_eq!(
+            property("FractalFBm", "Octaves"),
+            Json::object([("type", Json::from("integer"))])
+        );
+        assert_eq!(
+            def("FractalFBm").get("required"),
+            Some(&Json::Array(vec![Json::from("node"), Json::from("Source")]))
+        );
+        assert_eq!(
+            def("FractalFBm").get("additionalProperties"),
+            Some(&Json::Bool(false))
+        );
+    }
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(
+            property("Perlin", "FeatureScale"),
+            Json::object([
+                ("type", Json::from("number")),
+                ("default", Json::Float(100.0)),
+            ])
+        );
+        assert_eq!(
+            property("Perlin", "SeedOffset").get("default"),
+            Some(&Json::Int(0))
+        );
+        assert_eq!(property("FractalFBm", "Gain").get("default"), None);
+        // Defaults belong to a node, not to every member of the same name.
+        assert_eq!(property("SeedOffset", "SeedOffset").get("default"), None);
+        assert_eq!(
+            property("DistanceToPoint", "DistanceFunction").get("default"),
+            Some(&Json::from("Euclidean Squared"))
+        );
+        assert_eq!(
+            property("CellularValue", "DistanceFunction").get("default"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_enum_values() {
+        let node = NODE_METADATA
+            .iter()
+            .find(|metadata| metadata.display_name == "CellularDistance")
+            .unwrap();
+        let member = &node.members[&format_lookup("DistanceFunction")];
+        let distance_function = property("CellularDistance", "DistanceFunction");
+        assert_eq!(
+            distance_function.get("enum"),
+            Some(&Json::Array(
+                member
+                    .enum_display_names
+                    .iter()
+                    .map(|name| Json::from(name.as_str()))
+                    .collect()
+            ))
+        );
+        assert_eq!(distance_function.get("default"), None);
+    }
+
+    #[test]
+    fn test_dimension_members() {
+        for (dimension, name) in ["OffsetX", "OffsetY", "OffsetZ", "OffsetW"]
+            .iter()
+            .enumerate()
+        {
+            let description = Some(Json::String(format!(
+                "{} component of Offset",
+                DIMENSIONS[dimension]
+            )));
+            let offset = property("Gradient", name);
+            assert_eq!(offset.get("description"), description.as_ref());
+            assert_eq!(offset.get("default"), Some(&Json::Float(0.0)));
+            let offset = property("DomainOffset", name);
+            assert_eq!(offset.get("description"), description.as_ref());
+            assert_eq!(offset.get("default"), None);
+        }
+    }
+
+    #[test]
+    fn test_json_output() {
+        let json = Json::object([
+            ("a", Json::Array(vec![Json::Int(1), Json::Float(0.5)])),
+            ("b \"quoted\"\n", Json::Object(Vec::new())),
+            ("c", Json::Array(Vec::new())),
+            ("d", Json::Bool(true)),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            "{\n  \"a\": [\n    1,\n    0.5\n  ],\n  \"b \\\"quoted\\\"\\n\": {},\n  \"c\": [],\n  \"d\": true\n}"
+        );
+        assert!(json_schema()
+            .starts_with("{\n  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\",\n"));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Json::parse(r#" { "a": [1, -2.5e1, true, null], "b\u00e9\n": {}, "c": [] } "#),
+            Ok(Json::object([
+                (
+                    "a",
+                    Json::Array(vec![
+                        Json::Int(1),
+                        Json::Float(-25.0),
+                        Json::Bool(true),
+                        Json::Null,
+                    ]),
+                ),
+                ("b\u{e9}\n", Json::Object(Vec::new())),
+                ("c", Json::Array(Vec::new())),
+            ]))
+        );
+        assert_eq!(
+            Json::parse("\"\\ud83d\\ude00\""),
+            Ok(Json::from("\u{1f600}"))
+        );
+        assert_eq!(Json::parse("3000000000"), Ok(Json::Float(3e9)));
+        assert_eq!(Json::parse("\"\u{7f}\""), Ok(Json::from("\u{7f}")));
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+        assert!(Json::parse(&nested(MAX_DEPTH + 1)).is_err());
+        assert!(Json::parse(&nested(100_000)).is_err());
+        for invalid in [
+            "",
+            "{",
+            "[1,]",
+            "01",
+            "1.",
+            "-",
+            "+1",
+            ".5",
+            "1e",
+            "NaN",
+            "Infinity",
+            "1e39",
+            "\"a",
+            "{\"a\" 1}",
+            "{\"a\": 1, \"a\": 2}",
+            "1 2",
+            "nul",
+            "\"\u{1f}\"",
+            "\"\\udc00\"",
+        ] {
+            assert!(Json::parse(invalid).is_err(), "{invalid:?} parsed");
+        }
+    }
+
+    #[test]
+    fn test_parse_schema() {
+        // Integral floats such as defaults are written, and read back, as integers.
+        let schema = json_schema();
+        assert_eq!(Json::parse(&schema).unwrap().to_string(), schema);
+    }
+
+    /// Validates `value` against `schema`, for the keywords used by [`json_schema`].
+    fn validate(root: &Json, schema: &Json, value: &Json) -> Result<(), String> {
+        let Json::Object(keywords) = schema else {
+            panic!("schema is not an object");
+        };
+        for (keyword, argument) in keywords {
+            match (keyword.as_str(), argument) {
+                ("$ref", Json::String(path)) => {
+                    let name = path.strip_prefix("#/$defs/").expect("local reference");
+                    let schema = root.get("$defs").and_then(|defs| defs.get(name)).unwrap();
+                    validate(root, schema, value)?;
+                }
+                ("oneOf", Json::Array(schemas)) => {
+                    let matches = schemas
+                        .iter()
+                        .filter(|schema| validate(root, schema, value).is_ok())
+                        .count();
+                    if matches != 1 {
+                        return Err(format!("{value} matches {matches} schemas of oneOf"));
+                    }
+                }
+                ("type", Json::String(ty)) => {
+                    let valid = matches!(
+                        (ty.as_str(), value),
+                        ("object", Json::Object(_))
+                            | ("string", Json::String(_))
+                            | ("integer", Json::Int(_))
+                            | ("number", Json::Int(_) | Json::Float(_))
+                    );
+                    if !valid {
+                        return Err(format!("{value} is not of type {ty}"));
+                    }
+                }
+                ("const", expected) if value != expected => {
+                    return Err(format!("{value} is not {expected}"));
+                }
+                ("enum", Json::Array(values)) if !values.contains(value) => {
+                    return Err(format!("{value} is not one of {argument}"));
+                }
+                ("properties", Json::Object(properties)) => {
+                    let Json::Object(members) = value else {
+                        continue;
+                    };
+                    for (name, member) in members {
+                        match properties.iter().find(|(property, _)| property == name) {
+                            Some((_, schema)) => validate(root, schema, member)?,
+                            None if schema.get("additionalProperties")
+                                == Some(&Json::Bool(false)) =>
+                            {
+                                return Err(format!("unexpected property {name}"));
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                ("required", Json::Array(required)) => {
+                    for name in required {
+                        let Json::String(name) = name else {
+                            panic!("required names are strings");
+                        };
+                        if let Json::Object(_) = value {
+                            if value.get(name).is_none() {
+                                return Err(format!("missing property {name}"));
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_document_round_trip() {
+        let schema = Json::parse(&json_schema()).unwrap();
+        let documents = [
+            (
+                r#"{
+                    "node": "FractalFBm",
+                    "Source": { "node": "Perlin", "FeatureScale": 50 },
+                    "Gain": 0.5,
+                    "WeightedStrength": 0,
+                    "Octaves": 3,
+                    "Lacunarity": 2
+                }"#,
+                perlin()
+                    .with_feature_scale(50.0)
+                    .fbm(0.5, 0.0, 3, 2.0)
+                    .build()
+                    .0,
+            ),
+            (
+                r#"{
+                    "node": "Add",
+                    "LHS": { "node": "Perlin" },
+                    "RHS": {
+                        "node": "CellularValue",
+                        "GridJitter": 1,
+                        "DistanceFunction": "Manhattan",
+                        "ValueIndex": 0
+                    }
+                }"#,
+                (perlin() + cellular_value(1.0, DistanceFunction::Manhattan, 0))
+                    .build()
+                    .0,
+            ),
+        ];
+        for (document, expected) in documents {
+            let json = Json::parse(document).unwrap();
+            validate(&schema, &schema, &json).unwrap();
+            assert_eq!(SafeNode::from_json(document).unwrap(), expected);
+        }
+
+        let invalid = Json::parse(r#"{ "node": "Perlin", "Octaves": 3 }"#).unwrap();
+        assert!(validate(&schema, &schema, &invalid).is_err());
+        assert!(SafeNode::from_json(r#"{ "node": "Perlin", "Octaves": 3 }"#).is_err());
+    }
+
+    #[test]
+    fn test_document_errors() {
+        let invalid_document = |document| {
+            SafeNode::from_json(document).is_err_and(|error| {
+                matches!(error.root_cause(), FastNoiseError::InvalidDocument { .. })
+            })
+        };
+        assert!(invalid_document("{ \"node\": \"Perlin\", "));
+        assert!(invalid_document("[]"));
+        assert!(invalid_document("{ \"FeatureScale\": 1 }"));
+        assert!(invalid_document(
+            r#"{ "node": "Perlin", "FeatureScale": 1e39 }"#
+        ));
+        assert!(invalid_document(
+            r#"{ "node": "DomainScale", "Scaling": 2 }"#
+        ));
+        assert!(SafeNode::from_json(r#"{ "node": "Perlin", "FeatureScale": 16777216 }"#).is_ok());
+        assert!(invalid_document(
+            r#"{ "node": "Perlin", "FeatureScale": 16777217 }"#
+        ));
+        assert!(invalid_document(
+            r#"{ "node": "Perlin", "FeatureScale": [1] }"#
+        ));
+
+        let error = SafeNode::from_json(
+            r#"{ "node": "DomainScale", "Source": { "node": "Perlin", "FeatureScale": "large" } }"#,
+        )
+        .unwrap_err();
+        assert_eq!(error.path().as_deref(), Some("root.Source"));
+        assert!(matches!(
+            error.root_cause(),
+            FastNoiseError::InvalidMemberType { .. }
+        ));
+        assert!(matches!(
+            SafeNode::from_json(r#"{ "node": "Perlim" }"#)
+                .unwrap_err()
+                .root_cause(),
+            FastNoiseError::MetadataNameNotFound { .. }
+        ));
+    }
+
+    /// JSON values with finite floats.
+    fn arbitrary_json() -> impl Strategy<Value = Json> {
+        let leaf = prop_oneof![
+            Just(Json::Null),
+            any::<bool>().prop_map(Json::Bool),
+            any::<i32>().prop_map(Json::Int),
+            any::<f32>()
+                .prop_filter("JSON has no NaN or infinity", |value| value.is_finite())
+                .prop_map(Json::Float),
+            any::<String>().prop_map(Json::String),
+        ];
+        leaf.prop_recursive(4, 32, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(Json::Array),
+                prop::collection::btree_map(any::<String>(), inner, 0..4)
+                    .prop_map(|properties| Json::Object(properties.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// Returns `json` with integral floats as integers, as they are written, and properties sorted like serde_json.
+    fn normalize(json: Json) -> Json {
+        match json {
+            Json::Float(value)
+                if value.fract() == 0.0
+                    && (i32::MIN as f32..-(i32::MIN as f32)).contains(&value) =>
+            {
+                Json::Int(value as i32)
+            }
+            Json::Array(items) => Json::Array(items.into_iter().map(normalize).collect()),
+            Json::Object(properties) => {
+                let mut properties: Vec<_> = properties
+                    .into_iter()
+                    .map(|(key, value)| (key, normalize(value)))
+                    .collect();
+                properties.sort_by(|(a, _), (b, _)| a.cmp(b));
+                Json::Object(properties)
+            }
+            json => json,
+        }
+    }
+
+    /// Converts a value read by serde_json to [`Json`], reading numbers like [`Json::parse`] up to the rounding of
+    /// the f64 in between.
+    fn from_serde(value: serde_json::Value) -> Json {
+        use serde_json::Value;
+        match value {
+            Value::Null => Json::Null,
+            Value::Bool(value) => Json::Bool(value),
+            Value::Number(number) => {
+                match number.as_i64().and_then(|value| i32::try_from(value).ok()) {
+                    Some(value) => Json::Int(value),
+                    None => Json::Float(number.as_f64().unwrap() as f32),
+                }
+            }
+            Value::String(value) => Json::String(value),
+            Value::Array(items) => Json::Array(items.into_iter().map(from_serde).collect()),
+            Value::Object(properties) => Json::Object(
+                properties
+                    .into_iter()
+                    .map(|(key, value)| (key, from_serde(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns `true` if normalized values are equal, with floats within an epsilon.
+    fn same(a: &Json, b: &Json) -> bool {
+        match (a, b) {
+            (Json::Float(a), Json::Float(b)) => {
+                (a - b).abs() <= a.abs().max(b.abs()) * f32::EPSILON
+            }
+            (Json::Array(a), Json::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same(a, b))
+            }
+            (Json::Object(a), Json::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((a_key, a), (b_key, b))| a_key == b_key && same(a, b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Checks that [`Json::parse`] reads `text` as serde_json does, but for the documented differences.
+    fn check_against_serde(text: &str) -> Result<(), TestCaseError> {
+        let expected = serde_json::from_str::<serde_json::Value>(text);
+        match (Json::parse(text), expected) {
+            (Ok(json), Ok(expected)) => {
+                let (json, expected) = (normalize(json), normalize(from_serde(expected)));
+                prop_assert!(same(&json, &expected), "{text:?}: {json:?} != {expected:?}");
+            }
+            (Err(reason), Ok(_)) => prop_assert!(
+                reason.starts_with("duplicate property")
+                    || reason.contains("out of the range of an f32"),
+                "{text:?} rejected: {reason}"
+            ),
+            (Ok(json), Err(error)) => {
+                prop_assert!(false, "{text:?} read as {json:?}, serde_json: {error}")
+            }
+            (Err(_), Err(_)) => {}
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_written(json in arbitrary_json()) {
+            let json = normalize(json);
+            prop_assert_eq!(Json::parse(&json.to_string()).map(normalize), Ok(json));
+        }
+
+        #[test]
+        fn test_parse_like_serde(text in r#"[\[\]{}",:0-9eE.+\-nultrfase \\/u\t\n\x7f\u{e9}]{0,32}"#) {
+            check_against_serde(&text)?;
+        }
+
+        #[test]
+        fn test_parse_mutated_like_serde(
+            json in arbitrary_json(),
+            edits in prop::collection::vec((any::<prop::sample::Index>(), r#"[\[\]{}",:0-9eE.+\-\\ \x7f\u{e9}]?"#), 1..4),
+        ) {
+            let mut text = json.to_string();
+            for (index, replacement) in edits {
+                let index = index.index(text.len() + 1);
+                if text.is_char_boundary(index) {
+                    let end = text[index..].chars().next().map_or(index, |c| index + c.len_utf8());
+                    text.replace_range(index..end, &replacement);
+                }
+            }
+            check_against_serde(&text)?;
+        }
+    }
+}
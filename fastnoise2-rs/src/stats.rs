@@ -0,0 +1,561 @@
+//! Statistical analysis of noise output.
+//!
+//! [`OutputMinMax`] only reports the extremes of one generation call. The tools in this module describe the whole
+//! distribution of a tree over a sampled region: histogram, mean, variance, percentiles and empirical range,
+//! as well as its frequency content through a radial power spectrum. They are meant to help tuning parameters
+//! such as [`Remap`][crate::generator::modifier::Remap] or [`Terrace`][crate::generator::modifier::Terrace] bounds,
+//! and to spot output regressions when the FastNoise2 submodule is bumped.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use fastnoise2::{
+//!     generator::prelude::*,
+//!     stats::{NoiseStats, SampleRegion},
+//! };
+//!
+//! let node = perlin().fbm(0.5, 0.0, 4, 2.0).build();
+//! let stats = NoiseStats::sample(&node, &SampleRegion::grid_2d([0.0, 0.0], [256, 256], 4.0), 1337);
+//! println!(
+//!     "mean {}, std dev {}, 1%-99% range {:?}",
+//!     stats.mean(),
+//!     stats.std_dev(),
+//!     (stats.percentile(1.0), stats.percentile(99.0))
+//! );
+//! ```
+use std::f64::consts::TAU;
+
+use crate::{FastNoiseError, OutputMinMax, SafeNode};
+
+/// Maximum number of samples in a [`SampleRegion`], 2^28 samples taking 1 GiB.
+pub const MAX_SAMPLES: usize = 1 << 28;
+
+/// Region of space sampled with a uniform grid.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SampleRegion {
+    Grid2D {
+        offset: [f32; 2],
+        count: [i32; 2],
+        step_size: [f32; 2],
+    },
+    Grid3D {
+        offset: [f32; 3],
+        count: [i32; 3],
+        step_size: [f32; 3],
+    },
+}
+
+impl SampleRegion {
+    /// Creates a 2D grid region with the same step size on both axes.
+    pub fn grid_2d(offset: [f32; 2], count: [i32; 2], step_size: f32) -> Self {
+        Self::Grid2D {
+            offset,
+            count,
+            step_size: [step_size; 2],
+        }
+    }
+
+    /// Creates a 3D grid region with the same step size on all axes.
+    pub fn grid_3d(offset: [f32; 3], count: [i32; 3], step_size: f32) -> Self {
+        Self::Grid3D {
+            offset,
+            count,
+            step_size: [step_size; 3],
+        }
+    }
+
+    /// Number of samples in the region.
+    ///
+    /// # Panics
+    /// Panics if the region is invalid, see [`try_len`](Self::try_len).
+    pub fn len(&self) -> usize {
+        self.try_len().unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Number of samples in the region.
+    ///
+    /// Returns [`FastNoiseError::InvalidSampleRegion`] if one of the counts is negative, or if the number of samples
+    /// is above [`MAX_SAMPLES`].
+    pub fn try_len(&self) -> Result<usize, FastNoiseError> {
+        let count: &[i32] = match self {
+            Self::Grid2D { count, .. } => count,
+            Self::Grid3D { count, .. } => count,
+        };
+        let too_many = || FastNoiseError::InvalidSampleRegion {
+            reason: format!("{count:?} samples are more than the maximum of {MAX_SAMPLES}"),
+        };
+        count.iter().try_fold(1usize, |len, &axis_count| {
+            let axis_count =
+                usize::try_from(axis_count).map_err(|_| FastNoiseError::InvalidSampleRegion {
+                    reason: format!("negative count {axis_count}"),
+                })?;
+            len.checked_mul(axis_count)
+                .filter(|&len| len <= MAX_SAMPLES)
+                .ok_or_else(too_many)
+        })
+    }
+
+    /// Returns `true` if the region contains no sample.
+    ///
+    /// # Panics
+    /// Panics if the region is invalid, see [`try_len`](Self::try_len).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Generates `node` over the region, x fastest.
+    ///
+    /// # Panics
+    /// Panics if the region is invalid, see [`try_len`](Self::try_len).
+    pub fn sample(&self, node: &SafeNode, seed: i32) -> Vec<f32> {
+        self.try_sample(node, seed)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Generates `node` over the region, x fastest.
+    ///
+    /// Returns [`FastNoiseError::InvalidSampleRegion`] if the region is invalid, see [`try_len`](Self::try_len), or if
+    /// the output can't be allocated.
+    pub fn try_sample(&self, node: &SafeNode, seed: i32) -> Result<Vec<f32>, FastNoiseError> {
+        let len = self.try_len()?;
+        let mut noise_out = Vec::new();
+        noise_out
+            .try_reserve_exact(len)
+            .map_err(|error| FastNoiseError::InvalidSampleRegion {
+                reason: format!("can't allocate {len} samples: {error}"),
+            })?;
+        noise_out.resize(len, 0.0);
+        if noise_out.is_empty() {
+            return Ok(noise_out);
+        }
+        match *self {
+            Self::Grid2D {
+                offset: [x_offset, y_offset],
+                count: [x_count, y_count],
+                step_size: [x_step_size, y_step_size],
+            } => {
+                node.gen_uniform_grid_2d(
+                    &mut noise_out,
+                    x_offset,
+                    y_offset,
+                    x_count,
+                    y_count,
+                    x_step_size,
+                    y_step_size,
+                    seed,
+                );
+            }
+            Self::Grid3D {
+                offset: [x_offset, y_offset, z_offset],
+                count: [x_count, y_count, z_count],
+                step_size: [x_step_size, y_step_size, z_step_size],
+            } => {
+                node.gen_uniform_grid_3d(
+                    &mut noise_out,
+                    x_offset,
+                    y_offset,
+                    z_offset,
+                    x_count,
+                    y_count,
+                    z_count,
+                    x_step_size,
+                    y_step_size,
+                    z_step_size,
+                    seed,
+                );
+            }
+        }
+        Ok(noise_out)
+    }
+}
+
+/// Summary statistics of a set of noise samples.
+///
+/// Non-finite samples (NaN and infinities) are counted in [`NoiseStats::non_finite_count`] and excluded from
+/// every other statistic.
+#[derive(Clone, Debug)]
+pub struct NoiseStats {
+    /// Finite samples, sorted in ascending order.
+    sorted: Vec<f32>,
+    non_finite_count: usize,
+    mean: f64,
+    variance: f64,
+}
+
+impl NoiseStats {
+    /// Computes statistics of `samples`.
+    pub fn from_samples(samples: &[f32]) -> Self {
+        let mut sorted: Vec<f32> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        sorted.sort_unstable_by(f32::total_cmp);
+        let non_finite_count = samples.len() - sorted.len();
+
+        // Welford's algorithm, accumulated in f64 to stay accurate over millions of samples.
+        let (mut mean, mut m2) = (0.0, 0.0);
+        for (i, &value) in sorted.iter().enumerate() {
+            let value = value as f64;
+            let delta = value - mean;
+            mean += delta / (i + 1) as f64;
+            m2 += delta * (value - mean);
+        }
+        let variance = if sorted.is_empty() {
+            0.0
+        } else {
+            m2 / sorted.len() as f64
+        };
+
+        Self {
+            sorted,
+            non_finite_count,
+            mean,
+            variance,
+        }
+    }
+
+    /// Generates `node` over `region` with `seed` and computes statistics of the output.
+    pub fn sample(node: &SafeNode, region: &SampleRegion, seed: i32) -> Self {
+        Self::from_samples(&region.sample(node, seed))
+    }
+
+    /// Number of finite samples.
+    pub fn count(&self) -> usize {
+        self.sorted.len()
+    }
+
+    /// Number of NaN or infinite samples, which are excluded from the statistics.
+    pub fn non_finite_count(&self) -> usize {
+        self.non_finite_count
+    }
+
+    pub fn mean(&self) -> f32 {
+        self.mean as f32
+    }
+
+    /// Population variance.
+    pub fn variance(&self) -> f32 {
+        self.variance as f32
+    }
+
+    /// Population standard deviation.
+    pub fn std_dev(&self) -> f32 {
+        self.variance.sqrt() as f32
+    }
+
+    /// Smallest and largest finite samples.
+    ///
+    /// # Panics
+    /// Panics if there is no finite sample.
+    pub fn range(&self) -> OutputMinMax {
+        OutputMinMax {
+            min: *self.sorted.first().expect("no finite sample"),
+            max: *self.sorted.last().expect("no finite sample"),
+        }
+    }
+
+    /// Returns the value below which `percent` % of the samples fall, linearly interpolated between samples.
+    ///
+    /// `percent` is clamped to `0.0..=100.0`, so `percentile(0.0)` and `percentile(100.0)` give the [`range`](Self::range).
+    ///
+    /// # Panics
+    /// Panics if there is no finite sample.
+    pub fn percentile(&self, percent: f32) -> f32 {
+        assert!(!self.sorted.is_empty(), "no finite sample");
+        let rank = (percent.clamp(0.0, 100.0) / 100.0) * (self.sorted.len() - 1) as f32;
+        let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+        let t = rank - lower as f32;
+        self.sorted[lower] + (self.sorted[upper] - self.sorted[lower]) * t
+    }
+
+    /// Builds a histogram of `bin_count` equally sized bins spanning the [`range`](Self::range).
+    ///
+    /// # Panics
+    /// Panics if `bin_count` is zero or if there is no finite sample.
+    pub fn histogram(&self, bin_count: usize) -> Histogram {
+        let OutputMinMax { min, max } = self.range();
+        Histogram::from_samples(&self.sorted, bin_count, min, max)
+    }
+}
+
+/// Percentage of samples clipped on each side by [`estimate_range`] when used for normalization.
+pub const NORMALIZATION_CLIP_PERCENT: f32 = 0.5;
+
+/// Estimates a robust output range of `node` over `region`, sampled with seeds `0..seed_count`.
+///
+/// Outliers are discarded by clipping `clip_percent` % of the samples at each end, so a handful of extreme values
+/// (e.g. fractal peaks) don't squash the rest of the distribution.
+///
+/// # Panics
+/// Panics if `seed_count` is not positive or if the node produces no finite sample over `region`.
+pub fn estimate_range(
+    node: &SafeNode,
+    region: &SampleRegion,
+    seed_count: i32,
+    clip_percent: f32,
+) -> OutputMinMax {
+    assert!(seed_count > 0, "at least one seed must be sampled");
+    let samples: Vec<f32> = (0..seed_count)
+        .flat_map(|seed| region.sample(node, seed))
+        .collect();
+    let stats = NoiseStats::from_samples(&samples);
+    OutputMinMax {
+        min: stats.percentile(clip_percent),
+        max: stats.percentile(100.0 - clip_percent),
+    }
+}
+
+/// Sample counts over equally sized bins.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram {
+    /// Lower bound of the first bin.
+    pub min: f32,
+    /// Upper bound of the last bin, inclusive.
+    pub max: f32,
+    /// Number of samples in each bin.
+    pub counts: Vec<usize>,
+}
+
+impl Histogram {
+    /// Counts `samples` in `bin_count` bins spanning `min..=max`. Samples outside this range, NaN included,
+    /// are ignored.
+    ///
+    /// # Panics
+    /// Panics if `bin_count` is zero.
+    pub fn from_samples(samples: &[f32], bin_count: usize, min: f32, max: f32) -> Self {
+        assert!(bin_count > 0, "a histogram needs at least one bin");
+        let mut counts = vec![0; bin_count];
+        let width = (max - min) / bin_count as f32;
+        for &value in samples.iter().filter(|&&v| v >= min && v <= max) {
+            let bin = if width > 0.0 {
+                (((value - min) / width) as usize).min(bin_count - 1)
+            } else {
+                0
+            };
+            counts[bin] += 1;
+        }
+        Self { min, max, counts }
+    }
+
+    /// Width of each bin.
+    pub fn bin_width(&self) -> f32 {
+        (self.max - self.min) / self.counts.len() as f32
+    }
+
+    /// Lower and upper bounds of the bin at `index`.
+    pub fn bin_range(&self, index: usize) -> (f32, f32) {
+        let width = self.bin_width();
+        (
+            self.min + width * index as f32,
+            self.min + width * (index + 1) as f32,
+        )
+    }
+}
+
+/// Radially averaged power spectrum of a square 2D sample grid.
+///
+/// `power[i]` is the mean power of all frequencies whose distance to the origin, in cycles per grid, rounds to `i`.
+/// The mean of the samples is removed beforehand, so `power[0]` only holds what is left of the constant component.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RadialPowerSpectrum {
+    /// Center frequency of each bin, in cycles per world unit.
+    pub frequencies: Vec<f32>,
+    /// Mean power of each bin.
+    pub power: Vec<f32>,
+}
+
+impl RadialPowerSpectrum {
+    /// Generates `node` over a `size` x `size` 2D grid starting at `offset` and computes its spectrum.
+    ///
+    /// # Panics
+    /// Panics if `size` is not positive.
+    pub fn sample(node: &SafeNode, offset: [f32; 2], size: i32, step_size: f32, seed: i32) -> Self {
+        assert!(size > 0, "spectrum grid size must be positive");
+        let samples = SampleRegion::grid_2d(offset, [size, size], step_size).sample(node, seed);
+        Self::from_grid(&samples, size as usize, step_size)
+    }
+
+    /// Computes the spectrum of a `size` x `size` grid of samples (x fastest) spaced by `step_size` world units.
+    ///
+    /// Uses a separable discrete Fourier transform, O(size³), which is fast enough for the grid sizes useful to
+    /// characterize noise (up to a few hundred samples per side).
+    ///
+    /// # Panics
+    /// Panics if `samples.len() != size * size` or if `size` is zero.
+    pub fn from_grid(samples: &[f32], size: usize, step_size: f32) -> Self {
+        assert!(size > 0, "spectrum grid size must be positive");
+        assert_eq!(
+            samples.len(),
+            size * size,
+            "samples do not form a square grid"
+        );
+
+        let mean = samples.iter().map(|&v| v as f64).sum::<f64>() / samples.len() as f64;
+        let mut re: Vec<f64> = samples.iter().map(|&v| v as f64 - mean).collect();
+        let mut im = vec![0.0; re.len()];
+
+        let twiddles: Vec<(f64, f64)> = (0..size)
+            .map(|k| {
+                let angle = -TAU * k as f64 / size as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        let mut line_re = vec![0.0; size];
+        let mut line_im = vec![0.0; size];
+
+        // Rows, then columns.
+        for (outer_stride, inner_stride) in [(size, 1), (1, size)] {
+            for line in 0..size {
+                let start = line * outer_stride;
+                for k in 0..size {
+                    let (mut sum_re, mut sum_im) = (0.0, 0.0);
+                    for n in 0..size {
+                        let (cos, sin) = twiddles[(k * n) % size];
+                        let (x_re, x_im) =
+                            (re[start + n * inner_stride], im[start + n * inner_stride]);
+                        sum_re += x_re * cos - x_im * sin;
+                        sum_im += x_re * sin + x_im * cos;
+                    }
+                    line_re[k] = sum_re;
+                    line_im[k] = sum_im;
+                }
+                for k in 0..size {
+                    re[start + k * inner_stride] = line_re[k];
+                    im[start + k * inner_stride] = line_im[k];
+                }
+            }
+        }
+
+        let bin_count = size / 2 + 1;
+        let mut power_sums = vec![0.0; bin_count];
+        let mut bin_sizes = vec![0usize; bin_count];
+        let signed = |k: usize| {
+            if k <= size / 2 {
+                k as f64
+            } else {
+                k as f64 - size as f64
+            }
+        };
+        let normalization = (samples.len() * samples.len()) as f64;
+        for ky in 0..size {
+            for kx in 0..size {
+                let radius = signed(kx).hypot(signed(ky)).round() as usize;
+                if radius < bin_count {
+                    let index = ky * size + kx;
+                    power_sums[radius] +=
+                        (re[index] * re[index] + im[index] * im[index]) / normalization;
+                    bin_sizes[radius] += 1;
+                }
+            }
+        }
+
+        let world_size = size as f32 * step_size;
+        Self {
+            frequencies: (0..bin_count).map(|i| i as f32 / world_size).collect(),
+            power: power_sums
+                .iter()
+                .zip(&bin_sizes)
+                .map(|(&sum, &n)| if n == 0 { 0.0 } else { (sum / n as f64) as f32 })
+                .collect(),
+        }
+    }
+
+    /// Frequency of the bin holding the most power, in cycles per world unit.
+    pub fn peak_frequency(&self) -> f32 {
+        let peak = self
+            .power
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(i, _)| i);
+        self.frequencies[peak]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{perlin::perlin, Generator};
+
+    #[test]
+    fn test_from_samples() {
+        let stats = NoiseStats::from_samples(&[1.0, 2.0, 3.0, 4.0, f32::NAN]);
+        assert_eq!(stats.count(), 4);
+        assert_eq!(stats.non_finite_count(), 1);
+        assert_eq!(stats.mean(), 2.5);
+        assert_eq!(stats.variance(), 1.25);
+        assert_eq!(stats.range().min, 1.0);
+        assert_eq!(stats.range().max, 4.0);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let samples: Vec<f32> = (0..=100).map(|v| v as f32).collect();
+        let stats = NoiseStats::from_samples(&samples);
+        assert_eq!(stats.percentile(0.0), 0.0);
+        assert_eq!(stats.percentile(50.0), 50.0);
+        assert_eq!(stats.percentile(99.5), 99.5);
+        assert_eq!(stats.percentile(150.0), 100.0);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let stats = NoiseStats::from_samples(&[0.0, 0.1, 0.5, 0.9, 1.0]);
+        let histogram = stats.histogram(2);
+        assert_eq!(histogram.counts, vec![2, 3]);
+        assert_eq!(histogram.bin_range(1), (0.5, 1.0));
+    }
+
+    #[test]
+    fn test_spectrum_of_sine() {
+        // 4 cycles over a 32 samples grid along x.
+        let size = 32;
+        let samples: Vec<f32> = (0..size * size)
+            .map(|i| (TAU * 4.0 * (i % size) as f64 / size as f64).sin() as f32)
+            .collect();
+        let spectrum = RadialPowerSpectrum::from_grid(&samples, size, 2.0);
+        assert_eq!(spectrum.power.len(), size / 2 + 1);
+        assert_eq!(spectrum.peak_frequency(), 4.0 / 64.0);
+        assert!(spectrum.power[0] < 1e-6);
+    }
+
+    #[test]
+    fn test_sample_node() {
+        let node = perlin().build();
+        let region = SampleRegion::grid_2d([0.0, 0.0], [64, 64], 4.0);
+        let stats = NoiseStats::sample(&node, &region, 1337);
+        assert_eq!(stats.count(), 64 * 64);
+        assert!(stats.range().min >= -1.0 && stats.range().max <= 1.0);
+        assert!(stats.std_dev() > 0.0);
+
+        let spectrum = RadialPowerSpectrum::sample(&node, [0.0, 0.0], 32, 4.0, 1337);
+        assert!(spectrum.power.iter().all(|p| p.is_finite()));
+
+        let OutputMinMax { min, max } = estimate_range(&node, &region, 2, 1.0);
+        assert!(min < max);
+        assert!(min >= -1.0 && max <= 1.0);
+    }
+
+    #[test]
+    fn test_region_len() {
+        assert_eq!(SampleRegion::grid_2d([0.0; 2], [16, 8], 1.0).len(), 128);
+        assert!(SampleRegion::grid_3d([0.0; 3], [4, 0, 4], 1.0).is_empty());
+
+        let negative = SampleRegion::grid_2d([0.0; 2], [-2, -2], 1.0);
+        assert!(matches!(
+            negative.try_len(),
+            Err(FastNoiseError::InvalidSampleRegion { .. })
+        ));
+        let overflowing = SampleRegion::grid_3d([0.0; 3], [i32::MAX; 3], 1.0);
+        assert!(matches!(
+            overflowing.try_sample(&perlin().build(), 1337),
+            Err(FastNoiseError::InvalidSampleRegion { .. })
+        ));
+        let too_large = SampleRegion::grid_2d([0.0; 2], [i32::MAX; 2], 1.0);
+        assert!(matches!(
+            too_large.try_sample(&perlin().build(), 1337),
+            Err(FastNoiseError::InvalidSampleRegion { .. })
+        ));
+        assert_eq!(
+            SampleRegion::grid_2d([0.0; 2], [1 << 14, 1 << 14], 1.0).len(),
+            MAX_SAMPLES
+        );
+    }
+}
@@ -0,0 +1,721 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    codegen::ordered_members,
+    error::suggestions,
+    generator::{Generator, GeneratorWrapper, Hybrid},
+    metadata::{format_lookup, Member, MemberValue, RecordedValue, NODE_METADATA},
+    FastNoiseError, MemberType, Node, SafeNode,
+};
+
+/// Value of a member, as passed to a [`GeneratorFolder`].
+///
+/// Hybrid members hold either a [`MemberData::Float`] or a [`MemberData::Node`].
+#[derive(Clone, Debug)]
+pub enum MemberData {
+    Float(f32),
+    Int(i32),
+    /// Display name of the enum value, e.g. `"Euclidean Squared"`.
+    Enum(String),
+    Node(SafeNode),
+}
+
+impl MemberData {
+    fn new(member: &'static Member, value: &RecordedValue) -> Self {
+        match value {
+            RecordedValue::Float(value) => Self::Float(*value),
+            RecordedValue::Int(value) if member.enum_display_names.is_empty() => Self::Int(*value),
+            RecordedValue::Int(index) => enum_name(member, *index)
+                .map_or(Self::Int(*index), |name| Self::Enum(name.to_string())),
+            RecordedValue::Node(child) => Self::Node(SafeNode(child.clone())),
+        }
+    }
+
+    /// Returns `true` if setting this value would record `value`: same float bits, same index or name, same node.
+    fn is_recorded(&self, member: &'static Member, value: &RecordedValue) -> bool {
+        match (self, value) {
+            (Self::Float(a), RecordedValue::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::Int(a), RecordedValue::Int(b)) => a == b,
+            (Self::Enum(name), RecordedValue::Int(index)) => {
+                enum_name(member, *index).is_some_and(|recorded| recorded == name)
+            }
+            (Self::Node(a), RecordedValue::Node(b)) => Arc::ptr_eq(&a.0, b),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn apply(self, node: &mut Node, member: &Member) -> Result<(), FastNoiseError> {
+        let name = member.set_name();
+        match self {
+            Self::Float(value) => node.set(&name, value),
+            Self::Int(value) => node.set(&name, value),
+            Self::Enum(value) => node.set(&name, value.as_str()),
+            Self::Node(value) => node.set(&name, value.0),
+        }
+    }
+
+    /// Type of the member this value can be set on, hybrids aside.
+    fn member_type(&self) -> MemberType {
+        match self {
+            Self::Float(_) => MemberType::Float,
+            Self::Int(_) => MemberType::Int,
+            Self::Enum(_) => MemberType::Enum,
+            Self::Node(_) => MemberType::NodeLookup,
+        }
+    }
+}
+
+fn enum_name(member: &'static Member, index: i32) -> Option<&'static str> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| member.enum_display_names.get(index))
+        .map(String::as_str)
+}
+
+/// Value of a member, as passed to a [`GeneratorVisitor`].
+#[derive(Clone, Copy, Debug)]
+pub enum MemberRef<'a> {
+    Float(f32),
+    Int(i32),
+    /// Display name of the enum value, e.g. `"Euclidean Squared"`.
+    Enum(&'a str),
+    /// A subtree, visited right after the member holding it.
+    Node,
+}
+
+/// Walks a tree without changing it, see [`Generator::visit`] and [`SafeNode::visit`].
+///
+/// Typed generators are walked through their fields and built trees through their members, so the same visitor
+/// works on both. Members are visited in the order of the fields of the typed generators: node lookups, hybrids, then
+/// variables. All methods do nothing by default, implement the ones needed.
+///
+/// # Examples
+///
+/// Counting the nodes of a tree:
+///
+/// ```rust
+/// use fastnoise2::{generator::prelude::*, GeneratorVisitor};
+///
+/// struct Count(usize);
+///
+/// impl GeneratorVisitor for Count {
+///     fn enter_node(&mut self, _name: &str) -> bool {
+///         self.0 += 1;
+///         true
+///     }
+/// }
+///
+/// let mut count = Count(0);
+/// (perlin().fbm(0.5, 0.0, 3, 2.0) + constant(0.25)).visit(&mut count);
+/// assert_eq!(count.0, 4);
+/// ```
+pub trait GeneratorVisitor {
+    /// Called when entering a node named `name`, e.g. `"FractalFBm"`, before its members. Returning `false` skips
+    /// its members and subtrees.
+    fn enter_node(&mut self, _name: &str) -> bool {
+        true
+    }
+
+    /// Called after [`enter_node`](Self::enter_node) for a tree decoded from an encoded node tree, whose nodes
+    /// below the root are not known on the Rust side.
+    fn visit_encoded(&mut self, _encoded: &str) {}
+
+    /// Called for each member of the node, named as in [`crate::Node::set`], e.g. `"OffsetX"`. A subtree is
+    /// visited right after the member holding it.
+    fn visit_member(&mut self, _member: &str, _member_type: MemberType, _value: MemberRef<'_>) {}
+
+    /// Called when leaving a node, after its members and subtrees.
+    fn leave_node(&mut self, _name: &str) {}
+}
+
+/// Rewrites a tree bottom-up, see [`Generator::fold`] and [`SafeNode::fold`].
+///
+/// Subtrees are folded before the members holding them. Both methods keep the tree unchanged by default.
+///
+/// Typed generators keep their types: their float, int and enum values are passed to
+/// [`fold_member`](Self::fold_member) and must be returned with the same type, while their subtrees are folded in
+/// place. [`fold_node`](Self::fold_node) is only called for built trees.
+pub trait GeneratorFolder {
+    /// Returns the value to set on `member` of the rewritten node named `node`, given its value with subtrees already
+    /// folded.
+    ///
+    /// # Errors
+    /// Errors are returned by [`Generator::fold`] and [`SafeNode::fold`].
+    fn fold_member(
+        &mut self,
+        _node: &str,
+        _member: &str,
+        _member_type: MemberType,
+        value: MemberData,
+    ) -> Result<MemberData, FastNoiseError> {
+        Ok(value)
+    }
+
+    /// Returns the node replacing `node` of a built tree, once its members are folded.
+    ///
+    /// # Errors
+    /// Errors are returned by [`SafeNode::fold`].
+    fn fold_node(&mut self, node: SafeNode) -> Result<SafeNode, FastNoiseError> {
+        Ok(node)
+    }
+}
+
+impl SafeNode {
+    /// Walks the tree depth-first with `visitor`. A subtree shared by several members is visited each time.
+    ///
+    /// Trees decoded from an encoded node tree are visited as a single node, with the members set afterwards.
+    pub fn visit<V: GeneratorVisitor + ?Sized>(&self, visitor: &mut V) {
+        let name = NODE_METADATA[self.0.metadata_id as usize]
+            .display_name
+            .as_str();
+        if !visitor.enter_node(name) {
+            return;
+        }
+        if let Some(encoded) = &self.0.encoded {
+            visitor.visit_encoded(encoded);
+        }
+        for (member, value) in members(&self.0) {
+            let member_name = member.set_name();
+            match value {
+                RecordedValue::Float(value) => {
+                    visitor.visit_member(
+                        &member_name,
+                        member.member_type,
+                        MemberRef::Float(*value),
+                    );
+                }
+                RecordedValue::Int(value) => {
+                    let value = match enum_name(member, *value) {
+                        Some(name) => MemberRef::Enum(name),
+                        None => MemberRef::Int(*value),
+                    };
+                    visitor.visit_member(&member_name, member.member_type, value);
+                }
+                RecordedValue::Node(child) => {
+                    visitor.visit_member(&member_name, member.member_type, MemberRef::Node);
+                    SafeNode(child.clone()).visit(visitor);
+                }
+            }
+        }
+        visitor.leave_node(name);
+    }
+
+    /// Rewrites the tree with `folder`, returning the new tree. This tree is left unchanged.
+    ///
+    /// Only the nodes with changed members are recreated, with the other members copied, so that unchanged subtrees
+    /// stay shared with this tree. A subtree shared by several members is folded once.
+    ///
+    /// # Errors
+    /// Returns the errors of `folder`, and of setting the values it returns, e.g.
+    /// [`FastNoiseError::InvalidMemberType`] for a node set on a float member.
+    pub fn fold<F: GeneratorFolder + ?Sized>(
+        &self,
+        folder: &mut F,
+    ) -> Result<SafeNode, FastNoiseError> {
+        fold_node(self, folder, &mut HashMap::new())
+    }
+}
+
+fn fold_node<F: GeneratorFolder + ?Sized>(
+    node: &SafeNode,
+    folder: &mut F,
+    folded: &mut HashMap<*const Node, SafeNode>,
+) -> Result<SafeNode, FastNoiseError> {
+    if let Some(node) = folded.get(&Arc::as_ptr(&node.0)) {
+        return Ok(node.clone());
+    }
+    let name = NODE_METADATA[node.0.metadata_id as usize]
+        .display_name
+        .as_str();
+    let mut changes = Vec::new();
+    for (member, value) in members(&node.0) {
+        let data = match value {
+            RecordedValue::Node(child) => {
+                MemberData::Node(fold_node(&SafeNode(child.clone()), folder, folded)?)
+            }
+            value => MemberData::new(member, value),
+        };
+        let data = folder.fold_member(name, &member.set_name(), member.member_type, data)?;
+        if !data.is_recorded(member, value) {
+            changes.push((member, data));
+        }
+    }
+    let rebuilt = if changes.is_empty() {
+        node.clone()
+    } else {
+        let mut copy = node.0.shallow_copy()?;
+        for (member, data) in changes {
+            data.apply(&mut copy, member)?;
+        }
+        SafeNode(copy.into())
+    };
+    let rebuilt = folder.fold_node(rebuilt)?;
+    folded.insert(Arc::as_ptr(&node.0), rebuilt.clone());
+    Ok(rebuilt)
+}
+
+/// Members set on `node`, node lookups first, then hybrids and variables.
+fn members(node: &Node) -> impl Iterator<Item = (&'static Member, &RecordedValue)> {
+    ordered_members(&NODE_METADATA[node.metadata_id as usize])
+        .into_iter()
+        .filter_map(|member| Some((member, node.members.get(&member.slot())?)))
+}
+
+/// Field of a typed generator, visited and folded without building it.
+pub trait TypedMember: Sized {
+    fn visit_typed<V: GeneratorVisitor + ?Sized>(
+        &self,
+        member: &str,
+        member_type: MemberType,
+        visitor: &mut V,
+    );
+
+    fn fold_typed<F: GeneratorFolder + ?Sized>(
+        &self,
+        node: &str,
+        member: &str,
+        member_type: MemberType,
+        folder: &mut F,
+    ) -> Result<Self, FastNoiseError>;
+}
+
+impl<T: Generator> TypedMember for T {
+    fn visit_typed<V: GeneratorVisitor + ?Sized>(
+        &self,
+        member: &str,
+        member_type: MemberType,
+        visitor: &mut V,
+    ) {
+        visitor.visit_member(member, member_type, MemberRef::Node);
+        self.visit(visitor);
+    }
+
+    fn fold_typed<F: GeneratorFolder + ?Sized>(
+        &self,
+        _node: &str,
+        _member: &str,
+        _member_type: MemberType,
+        folder: &mut F,
+    ) -> Result<Self, FastNoiseError> {
+        self.fold(folder)
+    }
+}
+
+impl TypedMember for f32 {
+    fn visit_typed<V: GeneratorVisitor + ?Sized>(
+        &self,
+        member: &str,
+        member_type: MemberType,
+        visitor: &mut V,
+    ) {
+        visitor.visit_member(member, member_type, MemberRef::Float(*self));
+    }
+
+    fn fold_typed<F: GeneratorFolder + ?Sized>(
+        &self,
+        node: &str,
+        member: &str,
+        member_type: MemberType,
+        folder: &mut F,
+    ) -> Result<Self, FastNoiseError> {
+        match folder.fold_member(node, member, member_type, MemberData::Float(*self))? {
+            MemberData::Float(value) => Ok(value),
+            value => Err(type_error(member, member_type, &value)),
+        }
+    }
+}
+
+impl TypedMember for GeneratorWrapper<f32> {
+    fn visit_typed<V: GeneratorVisitor + ?Sized>(
+        &self,
+        member: &str,
+        member_type: MemberType,
+        visitor: &mut V,
+    ) {
+        self.0.visit_typed(member, member_type, visitor);
+    }
+
+    fn fold_typed<F: GeneratorFolder + ?Sized>(
+        &self,
+        node: &str,
+        member: &str,
+        member_type: MemberType,
+        folder: &mut F,
+    ) -> Result<Self, FastNoiseError> {
+        self.0
+            .fold_typed(node, member, member_type, folder)
+            .map(GeneratorWrapper)
+    }
+}
+
+impl TypedMember for i32 {
+    fn visit_typed<V: GeneratorVisitor + ?Sized>(
+        &self,
+        member: &str,
+        member_type: MemberType,
+        visitor: &mut V,
+    ) {
+        visitor.visit_member(member, member_type, MemberRef::Int(*self));
+    }
+
+    fn fold_typed<F: GeneratorFolder + ?Sized>(
+        &self,
+        node: &str,
+        member: &str,
+        member_type: MemberType,
+        folder: &mut F,
+    ) -> Result<Self, FastNoiseError> {
+        match folder.fold_member(node, member, member_type, MemberData::Int(*self))? {
+            MemberData::Int(value) => Ok(value),
+            value => Err(type_error(member, member_type, &value)),
+        }
+    }
+}
+
+/// `False`/`True` enum members, e.g. `ClampOutput`.
+impl TypedMember for bool {
+    fn visit_typed<V: GeneratorVisitor + ?Sized>(
+        &self,
+        member: &str,
+        member_type: MemberType,
+        visitor: &mut V,
+    ) {
+        let name = if *self { "True" } else { "False" };
+        visitor.visit_member(member, member_type, MemberRef::Enum(name));
+    }
+
+    fn fold_typed<F: GeneratorFolder + ?Sized>(
+        &self,
+        node: &str,
+        member: &str,
+        member_type: MemberType,
+        folder: &mut F,
+    ) -> Result<Self, FastNoiseError> {
+        let name = if *self { "True" } else { "False" };
+        let value = folder.fold_member(node, member, member_type, MemberData::Enum(name.into()))?;
+        fold_enum(member, member_type, value, [false, true], |value| {
+            if *value { "True" } else { "False" }.to_string()
+        })
+    }
+}
+
+/// Returns the variant named by the enum `value` returned by a folder, ignoring case and spaces.
+pub(crate) fn fold_enum<T, const N: usize>(
+    member: &str,
+    member_type: MemberType,
+    value: MemberData,
+    variants: [T; N],
+    name: impl Fn(&T) -> String,
+) -> Result<T, FastNoiseError> {
+    let MemberData::Enum(found) = value else {
+        return Err(type_error(member, member_type, &value));
+    };
+    let names: Vec<String> = variants.iter().map(&name).collect();
+    match names
+        .iter()
+        .position(|name| format_lookup(name) == format_lookup(&found))
+    {
+        Some(index) => Ok(variants
+            .into_iter()
+            .nth(index)
+            .expect("names match variants")),
+        None => Err(FastNoiseError::EnumValueNotFound {
+            member_name: member.to_string(),
+            suggestions: suggestions(&found, names.iter().map(String::as_str)),
+            expected: names,
+            found,
+        }),
+    }
+}
+
+fn type_error(member: &str, member_type: MemberType, value: &MemberData) -> FastNoiseError {
+    FastNoiseError::InvalidMemberType {
+        member_name: member.to_string(),
+        expected: member_type,
+        found: value.member_type(),
+    }
+}
+
+/// Walks the fields of a typed generator, see [`Generator::visit`].
+pub(crate) struct TypedVisit<'a, V: ?Sized> {
+    name: &'static str,
+    /// `None` if the visitor skips the node.
+    visitor: Option<&'a mut V>,
+}
+
+impl<'a, V: GeneratorVisitor + ?Sized> TypedVisit<'a, V> {
+    pub(crate) fn new(name: &'static str, visitor: &'a mut V) -> Self {
+        let visitor = visitor.enter_node(name).then_some(visitor);
+        Self { name, visitor }
+    }
+
+    pub(crate) fn node<S: Generator>(self, member: &str, value: &S) -> Self {
+        self.member(member, MemberType::NodeLookup, value)
+    }
+
+    pub(crate) fn hybrid<H: Hybrid>(self, member: &str, value: &H) -> Self {
+        self.member(member, MemberType::Hybrid, value)
+    }
+
+    pub(crate) fn variable<T: TypedMember + MemberValue>(self, member: &str, value: &T) -> Self {
+        self.member(member, T::TYPE, value)
+    }
+
+    fn member<T: TypedMember>(mut self, member: &str, member_type: MemberType, value: &T) -> Self {
+        if let Some(visitor) = self.visitor.as_deref_mut() {
+            value.visit_typed(member, member_type, visitor);
+        }
+        self
+    }
+
+    pub(crate) fn leave(self) {
+        if let Some(visitor) = self.visitor {
+            visitor.leave_node(self.name);
+        }
+    }
+}
+
+/// Folds the fields of a typed generator, see [`Generator::fold`].
+pub(crate) struct TypedFold<'a, F: ?Sized> {
+    name: &'static str,
+    folder: &'a mut F,
+}
+
+impl<'a, F: GeneratorFolder + ?Sized> TypedFold<'a, F> {
+    pub(crate) fn new(name: &'static str, folder: &'a mut F) -> Self {
+        Self { name, folder }
+    }
+
+    pub(crate) fn node<S: Generator>(
+        &mut self,
+        member: &str,
+        value: &S,
+    ) -> Result<S, FastNoiseError> {
+        value.fold_typed(self.name, member, MemberType::NodeLookup, self.folder)
+    }
+
+    pub(crate) fn hybrid<H: Hybrid>(
+        &mut self,
+        member: &str,
+        value: &H,
+    ) -> Result<H, FastNoiseError> {
+        value.fold_typed(self.name, member, MemberType::Hybrid, self.folder)
+    }
+
+    pub(crate) fn variable<T: TypedMember + MemberValue>(
+        &mut self,
+        member: &str,
+        value: &T,
+    ) -> Result<T, FastNoiseError> {
+        value.fold_typed(self.name, member, T::TYPE, self.folder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::{prelude::*, DistanceFunction};
+
+    /// Records the visited nodes and members.
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl GeneratorVisitor for Trace {
+        fn enter_node(&mut self, name: &str) -> bool {
+            self.0.push(format!("enter {name}"));
+            name != "Perlin"
+        }
+
+        fn visit_member(&mut self, member: &str, member_type: MemberType, value: MemberRef<'_>) {
+            let value = match value {
+                MemberRef::Float(value) => value.to_string(),
+                MemberRef::Int(value) => value.to_string(),
+                MemberRef::Enum(value) => value.to_string(),
+                MemberRef::Node => "node".to_string(),
+            };
+            self.0.push(format!("{member}: {member_type:?} = {value}"));
+        }
+
+        fn leave_node(&mut self, name: &str) {
+            self.0.push(format!("leave {name}"));
+        }
+    }
+
+    #[test]
+    fn test_visit_order() {
+        let mut trace = Trace::default();
+        (perlin().domain_scale(2.0) + 0.5).visit(&mut trace);
+        assert_eq!(
+            trace.0,
+            [
+                "enter Add",
+                "LHS: NodeLookup = node",
+                "enter DomainScale",
+                "Source: NodeLookup = node",
+                "enter Perlin",
+                "Scaling: Float = 2",
+                "leave DomainScale",
+                "RHS: Hybrid = 0.5",
+                "leave Add",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_hybrid_subtree() {
+        let mut trace = Trace::default();
+        (constant(1.0) + constant(2.0)).visit(&mut trace);
+        assert!(trace.0.contains(&"RHS: Hybrid = node".to_string()));
+        assert_eq!(
+            trace
+                .0
+                .iter()
+                .filter(|line| *line == "enter Constant")
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_visit_typed_matches_built() {
+        let tree = perlin().fbm(0.5, constant(0.2), 3, 2.0).domain_scale(2.0)
+            + cellular_value(1.0, DistanceFunction::Manhattan, 0);
+        let mut typed = Trace::default();
+        tree.visit(&mut typed);
+        let mut built = Trace::default();
+        tree.build().0.visit(&mut built);
+        assert_eq!(typed.0, built.0);
+        assert!(typed
+            .0
+            .contains(&"DistanceFunction: Enum = Manhattan".to_string()));
+    }
+
+    /// Doubles every float and replaces constants by their negation.
+    struct Rewrite;
+
+    impl GeneratorFolder for Rewrite {
+        fn fold_member(
+            &mut self,
+            _node: &str,
+            _member: &str,
+            _member_type: MemberType,
+            value: MemberData,
+        ) -> Result<MemberData, FastNoiseError> {
+            Ok(match value {
+                MemberData::Float(value) => MemberData::Float(value * 2.0),
+                value => value,
+            })
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        let tree = (constant(1.0) + constant(2.0).domain_scale(3.0)).build().0;
+        let folded = tree.fold(&mut Rewrite).unwrap();
+        assert_eq!(
+            folded,
+            (constant(2.0) + constant(4.0).domain_scale(6.0)).build().0
+        );
+        // The original tree is unchanged.
+        assert_eq!(
+            tree,
+            (constant(1.0) + constant(2.0).domain_scale(3.0)).build().0
+        );
+    }
+
+    #[test]
+    fn test_fold_typed() {
+        let tree = constant(1.0) + constant(2.0).domain_scale(3.0);
+        let folded = tree.fold(&mut Rewrite).unwrap();
+        assert_eq!(folded.0.lhs.value, 2.0);
+        assert_eq!(folded.0.rhs.scaling, 6.0);
+        assert_eq!(folded.0.rhs.source.value, 4.0);
+        assert_eq!(folded.build().0, tree.build().0.fold(&mut Rewrite).unwrap());
+    }
+
+    #[test]
+    fn test_fold_typed_enum() {
+        /// Switches every distance function to Manhattan.
+        struct Manhattan;
+
+        impl GeneratorFolder for Manhattan {
+            fn fold_member(
+                &mut self,
+                _node: &str,
+                member: &str,
+                _member_type: MemberType,
+                value: MemberData,
+            ) -> Result<MemberData, FastNoiseError> {
+                Ok(match member {
+                    "DistanceFunction" => MemberData::Enum("manhattan".to_string()),
+                    _ => value,
+                })
+            }
+        }
+
+        let folded = cellular_value(1.0, DistanceFunction::Euclidean, 0)
+            .fold(&mut Manhattan)
+            .unwrap();
+        assert!(matches!(
+            folded.0.distance_function,
+            DistanceFunction::Manhattan
+        ));
+    }
+
+    #[test]
+    fn test_fold_unchanged_is_shared() {
+        struct Identity;
+        impl GeneratorFolder for Identity {}
+
+        let tree = perlin().fbm(0.5, 0.0, 3, 2.0).build().0;
+        let folded = tree.fold(&mut Identity).unwrap();
+        assert!(Arc::ptr_eq(&tree.0, &folded.0));
+    }
+
+    #[test]
+    fn test_fold_replace_node() {
+        /// Replaces `DomainScale` nodes by their source.
+        struct Unscale;
+
+        impl GeneratorFolder for Unscale {
+            fn fold_node(&mut self, node: SafeNode) -> Result<SafeNode, FastNoiseError> {
+                if node.pretty().to_string().starts_with("DomainScale") {
+                    node.subtree("Source")
+                } else {
+                    Ok(node)
+                }
+            }
+        }
+
+        let tree = (perlin().domain_scale(2.0) + 1.0).build().0;
+        let folded = tree.fold(&mut Unscale).unwrap();
+        assert_eq!(folded, (perlin() + 1.0).build().0);
+    }
+
+    #[test]
+    fn test_fold_error() {
+        struct Wrong;
+
+        impl GeneratorFolder for Wrong {
+            fn fold_member(
+                &mut self,
+                _node: &str,
+                _member: &str,
+                _member_type: MemberType,
+                _value: MemberData,
+            ) -> Result<MemberData, FastNoiseError> {
+                Ok(MemberData::Enum("NotAnEnum".to_string()))
+            }
+        }
+
+        assert!(constant(1.0).build().0.fold(&mut Wrong).is_err());
+        assert!(matches!(
+            constant(1.0).fold(&mut Wrong),
+            Err(FastNoiseError::InvalidMemberType { .. })
+        ));
+        assert!(matches!(
+            cellular_value(1.0, DistanceFunction::Euclidean, 0).fold(&mut Wrong),
+            Err(FastNoiseError::InvalidMemberType { .. })
+        ));
+    }
+}
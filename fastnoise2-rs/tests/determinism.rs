@@ -0,0 +1,156 @@
+//! Golden-value tests: every generator must give the same output at every SIMD level, and match the reference
+//! data checked into `tests/golden` within [`DETERMINISM_TOLERANCE`].
+//!
+//! Reference files are produced by running this suite with `FASTNOISE2_BLESS=1`. A generator without a reference
+//! file fails, so new generators can't be added to `golden!` without blessing and reviewing their file.
+//! Re-bless only when an output change is intended (e.g. a FastNoise2 submodule bump), and say so in the changelog.
+use std::{fs, path::PathBuf};
+
+use fastnoise2::{
+    determinism::{assert_deterministic, first_mismatch, reference_samples, DETERMINISM_TOLERANCE},
+    generator::{
+        cellular::CellularDistanceReturnType, modifier::PlaneRotationType, prelude::*,
+        simplex::simplex_scaled, Dimension, DistanceFunction, FadeInterpolation,
+    },
+};
+
+const BLESS_KEY: &str = "FASTNOISE2_BLESS";
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.txt"))
+}
+
+fn check<G: Generator>(name: &str, expression: &str, generator: &G) {
+    assert_deterministic(generator);
+
+    let samples = reference_samples(&generator.build());
+    let path = golden_path(name);
+
+    if std::env::var_os(BLESS_KEY).is_some() {
+        let mut contents = format!(
+            "# Reference output of `{expression}`, see tests/determinism.rs.\n\
+             # 16x16 2D grid followed by an 8x8x8 3D grid, x fastest, seed 1337.\n"
+        );
+        for sample in &samples {
+            contents.push_str(&format!("{sample:?}\n"));
+        }
+        fs::write(&path, contents).expect("failed to write reference data");
+        return;
+    }
+
+    let Ok(contents) = fs::read_to_string(&path) else {
+        panic!(
+            "no reference data for '{name}', run with {BLESS_KEY}=1 to create {}",
+            path.display()
+        );
+    };
+    let expected: Vec<f32> = contents
+        .lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| line.trim().parse().expect("invalid reference value"))
+        .collect();
+
+    assert_eq!(
+        expected.len(),
+        samples.len(),
+        "reference data for '{name}' has the wrong number of samples"
+    );
+    if let Some(index) = first_mismatch(&expected, &samples) {
+        panic!(
+            "'{name}' differs from its reference data by more than {DETERMINISM_TOLERANCE} at sample {index}: \
+             expected {}, found {}",
+            expected[index], samples[index]
+        );
+    }
+}
+
+macro_rules! golden {
+    ($($name:ident => $generator:expr;)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn golden() {
+                    check(stringify!($name), stringify!($generator), &$generator);
+                }
+            }
+        )*
+    };
+}
+
+golden! {
+    // basic
+    constant => constant(0.5);
+    white => white();
+    checkerboard => checkerboard(10.0);
+    sinewave => sinewave(30.0);
+    gradient => gradient().with_multipliers([0.01, 0.02, 0.03, 0.0]);
+    distance_to_point => distance_to_point().with_point([10.0, -20.0, 5.0, 0.0]);
+
+    // noise
+    perlin => perlin();
+    simplex => simplex();
+    simplex_small_scale => simplex_scaled(20.0);
+    supersimplex => supersimplex();
+    value => value();
+
+    // cellular
+    cellular_value => cellular_value(1.0, DistanceFunction::Euclidean, 0);
+    cellular_distance => cellular_distance(
+        1.0,
+        DistanceFunction::EuclideanSquared,
+        0,
+        1,
+        CellularDistanceReturnType::Index0Sub1,
+    );
+    cellular_lookup => cellular_lookup(perlin(), 1.0, DistanceFunction::Manhattan);
+
+    // fractal
+    fractal_fbm => perlin().fbm(0.5, 0.0, 4, 2.0);
+    fractal_ridged => simplex().ridged(0.5, 0.2, 4, 2.0);
+
+    // domain warp
+    domain_warp_gradient => perlin().domain_warp_gradient(20.0, 100.0);
+    domain_warp_simplex => perlin().domain_warp_simplex(20.0, 100.0);
+    domain_warp_super_simplex => perlin().domain_warp_super_simplex(20.0, 100.0);
+    domain_warp_progressive => perlin()
+        .domain_warp_gradient(20.0, 100.0)
+        .domain_warp_progressive(0.5, 0.0, 3, 2.0);
+    domain_warp_independent => perlin()
+        .domain_warp_gradient(20.0, 100.0)
+        .domain_warp_independent(0.5, 0.0, 3, 2.0);
+
+    // blend
+    add => perlin() + simplex();
+    subtract => perlin() - 0.25;
+    multiply => perlin() * value();
+    divide => perlin() / 2.0;
+    modulus => perlin() % 0.3;
+    min => perlin().min(simplex());
+    max => perlin().max(simplex());
+    min_smooth => perlin().min_smooth(simplex(), 0.2);
+    max_smooth => perlin().max_smooth(simplex(), 0.2);
+    fade => perlin().fade_with_range(simplex(), value(), -1.0, 1.0, FadeInterpolation::Hermite);
+    pow_float => perlin().abs().powf(1.5);
+    pow_int => perlin().powi(3);
+
+    // modifier
+    domain_scale => perlin().domain_scale(0.5);
+    domain_offset => perlin().domain_offset(10.0, 20.0, 30.0, 0.0);
+    domain_rotate => perlin().domain_rotate(0.5, 0.25, 0.1);
+    seed_offset => perlin().seed_offset(42);
+    remap => perlin().remap_clamped(-0.5, 0.5, 0.0, 1.0, true);
+    terrace => perlin().terrace(4.0, 0.2);
+    domain_axis_scale => perlin().domain_axis_scale([1.0, 2.0, 0.5, 1.0]);
+    add_dimension => perlin().add_dimension(12.5);
+    remove_dimension => perlin().remove_dimension(Dimension::Y);
+    cache => perlin().cache();
+    ping_pong => perlin().ping_pong(2.0);
+    abs => perlin().abs();
+    signed_sqrt => perlin().signed_sqrt();
+    domain_rotate_plane => perlin().domain_rotate_plane_with_type(PlaneRotationType::ImproveXZPlanes);
+}
@@ -1,21 +1,33 @@
-use std::{env, path::PathBuf};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 const SOURCE_DIR_KEY: &str = "FASTNOISE2_SOURCE_DIR";
 const LIB_DIR_KEY: &str = "FASTNOISE2_LIB_DIR";
+const LIB_COMMIT_KEY: &str = "FASTNOISE2_LIB_COMMIT";
 const BINDINGS_CACHE_KEY: &str = "FASTNOISE2_BINDINGS_DIR";
 const LIB_NAME: &str = "FastNoise";
 const HEADER_NAME: &str = "FastNoise_C.h";
+/// Commit of the FastNoise2 submodule, used when the sources are not a git checkout (e.g. from crates.io).
+/// Update it with the submodule.
+const BUNDLED_COMMIT: &str = "3728fde069704509fcf2973825b2d385348bf336";
 
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(fastnoise2_unlinked)");
     if env::var("DOCS_RS").is_ok() {
         println!("cargo:warning=docs.rs compilation detected, only bindings will be generated");
+        println!("cargo:rustc-cfg=fastnoise2_unlinked");
         generate_bindings(default_source_path());
+        emit_library_info("docs.rs", None, "");
         return;
     }
 
     println!("cargo:rerun-if-env-changed={SOURCE_DIR_KEY}");
     println!("cargo:rerun-if-env-changed={LIB_DIR_KEY}");
     println!("cargo:rerun-if-env-changed={BINDINGS_CACHE_KEY}");
+    println!("cargo:rerun-if-env-changed={LIB_COMMIT_KEY}");
     println!("cargo:rerun-if-env-changed=EMSDK");
 
     let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
@@ -40,6 +52,7 @@ fn main() {
         println!("cargo:rustc-link-lib=static={LIB_NAME}");
 
         generate_bindings(default_source_path());
+        emit_library_info("prebuilt", env::var(LIB_COMMIT_KEY).ok(), "");
     } else {
         println!("cargo:warning={LIB_DIR_KEY} is not set; falling back to building from source");
         build_from_source();
@@ -92,6 +105,7 @@ fn build_wasm() {
     let wasm_flags = "-msimd128";
     config.define("CMAKE_C_FLAGS", wasm_flags);
     config.define("CMAKE_CXX_FLAGS", wasm_flags);
+    emit_library_info("wasm", source_commit(&source_path), wasm_flags);
 
     let out_path = config.build();
     let lib_path = out_path.join("lib");
@@ -175,6 +189,11 @@ fn build_from_source() {
      CMAKE_CXX_FLAGS_RELEASE='{cmake_cxx_flags_release}'"
     );
     config.define("CMAKE_CXX_FLAGS_RELEASE", cmake_cxx_flags_release);
+    emit_library_info(
+        "source",
+        source_commit(&source_path),
+        cmake_cxx_flags_release,
+    );
 
     let out_path = config.build();
     let lib_path = out_path.join("lib");
@@ -277,6 +296,38 @@ fn generate_bindings(source_path: PathBuf) {
     }
 }
 
+/// Records how the library was obtained, read by `fastnoise2::library_info()`.
+fn emit_library_info(build_kind: &str, commit: Option<String>, build_flags: &str) {
+    let commit = commit.unwrap_or_default();
+    println!("cargo:rustc-env=FASTNOISE2_BUILD_KIND={build_kind}");
+    println!("cargo:rustc-env=FASTNOISE2_COMMIT={commit}");
+    println!("cargo:rustc-env=FASTNOISE2_BUILD_FLAGS={build_flags}");
+    println!("cargo:rustc-env=FASTNOISE2_BUNDLED_COMMIT={BUNDLED_COMMIT}");
+}
+
+/// Returns the commit of the FastNoise2 sources at `source_path`, if known.
+fn source_commit(source_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(source_path)
+        .args(["rev-parse", "--show-toplevel", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    if let Some(output) = output {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut lines = stdout.lines();
+        // An uninitialized submodule belongs to the checkout of this crate, whose commit is not the one of FastNoise2.
+        if let (Some(top_level), Some(commit)) = (lines.next(), lines.next()) {
+            if Path::new(top_level).canonicalize().ok() == source_path.canonicalize().ok() {
+                return Some(commit.to_string());
+            }
+        }
+    }
+    // Not a git checkout, only the bundled sources are known.
+    (source_path == default_source_path()).then(|| BUNDLED_COMMIT.to_string())
+}
+
 fn default_source_path() -> PathBuf {
     let mut path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
     path.push("build");
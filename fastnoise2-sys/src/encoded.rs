@@ -0,0 +1,1008 @@
+//! Reading and writing encoded node trees, the strings exported by the FastNoise2 Node Editor, in pure Rust.
+//!
+//! FastNoise2 only reports that it failed to decode a tree. This module decodes trees against a table of node layouts,
+//! usually [`library_layouts`], to report what is wrong with them, without creating any node: the `encoded_tree!` macro
+//! of fastnoise2 checks trees at compile time with it.
+//!
+//! # Format
+//!
+//! The text is base64, where `@` followed by a digit `d` stands for a run of `3 + d` `A` digits, padded with `=`. The
+//! decoded bytes are the root node, which is:
+//!
+//! - the metadata id of the node, in one byte,
+//! - its members that are not at their default, each a tag byte holding the kind of the member in its low 3 bits and
+//!   an index in the others, followed by the value:
+//!   - `0`: the variable of that index, in 4 little-endian bytes, an `f32` for floats and an `i32` for ints and enums,
+//!   - `1`: the node lookup of that index minus one, followed by the node,
+//!   - `2`: the hybrid of that index set to a node, followed by the node,
+//!   - `3`: the hybrid of that index set to a float, in 4 little-endian bytes,
+//! - an end tag, of kind `4`, whose index counts the nodes that end right after this one.
+//!
+//! Members are written as variables, node lookups then hybrids. The format is not documented by FastNoise2, this
+//! layout is inferred from trees exported by the Node Editor.
+//!
+//! Trees exported before fastnoise2 0.4.0, by FastNoise2 0.10, use the older format read by [`decode_legacy`].
+
+use std::{error::Error, fmt};
+
+/// Nodes nested deeper than this are rejected, so that decoding can't overflow the stack.
+pub const MAX_DEPTH: usize = 256;
+
+const VARIABLE: u8 = 0;
+const NODE_LOOKUP: u8 = 1;
+const HYBRID_NODE: u8 = 2;
+const HYBRID_FLOAT: u8 = 3;
+const END: u8 = 4;
+/// Ends merged in a single end tag, at most.
+const MAX_ENDS: usize = 32;
+/// Id of a legacy node that refers to an earlier node instead of encoding one.
+const LEGACY_REFERENCE: u16 = u16::MAX;
+
+/// Members of a node, in index order for each kind of member.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeLayout {
+    /// Name of the node.
+    pub name: String,
+    /// Variables of the node.
+    pub variables: Vec<VariableLayout>,
+    /// Names of the node lookups of the node.
+    pub node_lookups: Vec<String>,
+    /// Names of the hybrids of the node.
+    pub hybrids: Vec<String>,
+}
+
+/// A variable of a [`NodeLayout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariableLayout {
+    /// Name of the variable.
+    pub name: String,
+    /// Type of the variable.
+    pub kind: VariableKind,
+}
+
+/// Type of a [`VariableLayout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VariableKind {
+    Float,
+    Int,
+    /// An enum, stored as the index of its variant.
+    Enum {
+        /// Number of variants of the enum.
+        variants: usize,
+    },
+}
+
+/// A decoded node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodedNode {
+    /// Metadata id of the node.
+    pub id: usize,
+    /// Variables written in the tree, by index. The others are at their default.
+    pub variables: Vec<(usize, VariableValue)>,
+    /// Node lookups of the node, all of them in index order.
+    pub node_lookups: Vec<EncodedNode>,
+    /// Hybrids written in the tree, by index. The others are at their default.
+    pub hybrids: Vec<(usize, HybridValue)>,
+}
+
+/// Value of a variable of an [`EncodedNode`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VariableValue {
+    Float(f32),
+    /// An int, or the index of the variant of an enum.
+    Int(i32),
+}
+
+/// Value of a hybrid of an [`EncodedNode`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum HybridValue {
+    Float(f32),
+    Node(EncodedNode),
+}
+
+/// Reasons an encoded node tree can't be decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The text is empty.
+    Empty,
+    /// The text holds a character other than base64 digits, `@` runs and the final padding.
+    InvalidCharacter { character: char, index: usize },
+    /// An `@` is followed by a character that is not a base64 digit.
+    InvalidRunLength { character: char, index: usize },
+    /// The text ends with an `@`.
+    UnfinishedRun { index: usize },
+    /// A `=` is followed by other characters.
+    MisplacedPadding { index: usize },
+    /// The text ends with more than two `=`.
+    TooMuchPadding,
+    /// The base64 digits and padding don't make whole bytes.
+    Truncated { digits: usize, padding: usize },
+    /// The data ends inside a node.
+    UnexpectedEnd,
+    /// Bytes follow the root node, starting at `offset`.
+    TrailingData { offset: usize },
+    /// An end tag ends more nodes than are open.
+    UnbalancedEnd { offset: usize },
+    /// Nodes are nested deeper than [`MAX_DEPTH`].
+    TooDeep { offset: usize },
+    /// No node has the id read at `offset`.
+    UnknownNode { id: usize, offset: usize },
+    /// The tag at `offset` is not a member of the node.
+    UnknownMember {
+        node: String,
+        tag: u8,
+        offset: usize,
+    },
+    /// A member is written twice.
+    DuplicateMember {
+        node: String,
+        member: String,
+        offset: usize,
+    },
+    /// A node ends without one of its node lookups.
+    MissingNodeLookup {
+        node: String,
+        member: String,
+        offset: usize,
+    },
+    /// An enum variable holds an index outside of its variants.
+    InvalidEnumValue {
+        node: String,
+        member: String,
+        value: i32,
+        offset: usize,
+    },
+    /// A legacy hybrid is neither a float nor a node.
+    InvalidHybridFlag {
+        node: String,
+        member: String,
+        flag: u8,
+        offset: usize,
+    },
+    /// A legacy node refers to a node that was not decoded before it.
+    InvalidReference { reference: usize, offset: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "encoded node tree is empty"),
+            Self::InvalidCharacter { character, index } => {
+                write!(f, "invalid character {character:?} at {index} in encoded node tree")
+            }
+            Self::InvalidRunLength { character, index } => {
+                write!(f, "invalid run length {character:?} at {index} in encoded node tree")
+            }
+            Self::UnfinishedRun { index } => {
+                write!(f, "`@` at {index} ends the encoded node tree without a run length")
+            }
+            Self::MisplacedPadding { index } => {
+                write!(f, "padding `=` at {index} is not at the end of the encoded node tree")
+            }
+            Self::TooMuchPadding => write!(f, "encoded node tree ends with more than two `=`"),
+            Self::Truncated { digits, padding } => write!(
+                f,
+                "encoded node tree is truncated: {digits} base64 digits and {padding} `=` don't make whole bytes"
+            ),
+            Self::UnexpectedEnd => write!(f, "encoded node tree ends inside a node"),
+            Self::TrailingData { offset } => {
+                write!(f, "encoded node tree has data after its root node, at byte {offset}")
+            }
+            Self::UnbalancedEnd { offset } => write!(
+                f,
+                "end tag at byte {offset} of encoded node tree ends more nodes than are open"
+            ),
+            Self::TooDeep { offset } => write!(
+                f,
+                "encoded node tree nests more than {MAX_DEPTH} nodes, at byte {offset}"
+            ),
+            Self::UnknownNode { id, offset } => {
+                write!(f, "unknown node id {id} at byte {offset} of encoded node tree")
+            }
+            Self::UnknownMember { node, tag, offset } => write!(
+                f,
+                "'{node}' has no member for tag {tag:#04x} at byte {offset} of encoded node tree"
+            ),
+            Self::DuplicateMember {
+                node,
+                member,
+                offset,
+            } => write!(
+                f,
+                "'{member}' of '{node}' is set again at byte {offset} of encoded node tree"
+            ),
+            Self::MissingNodeLookup {
+                node,
+                member,
+                offset,
+            } => write!(
+                f,
+                "'{node}' ends without '{member}' at byte {offset} of encoded node tree"
+            ),
+            Self::InvalidEnumValue {
+                node,
+                member,
+                value,
+                offset,
+            } => write!(
+                f,
+                "'{member}' of '{node}' is set to {value}, which is not one of its variants, at byte {offset} of encoded node tree"
+            ),
+            Self::InvalidHybridFlag {
+                node,
+                member,
+                flag,
+                offset,
+            } => write!(
+                f,
+                "'{member}' of '{node}' is neither a float nor a node (flag {flag}) at byte {offset} of encoded node tree"
+            ),
+            Self::InvalidReference { reference, offset } => write!(
+                f,
+                "reference to node {reference} at byte {offset} of encoded node tree is not an earlier node"
+            ),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Returns the layouts of the nodes of the linked FastNoise2 library, indexed by metadata id.
+///
+/// Nothing is linked for docs.rs builds, where the list is empty.
+pub fn library_layouts() -> Vec<NodeLayout> {
+    #[cfg(fastnoise2_unlinked)]
+    return Vec::new();
+    // SAFETY: the metadata getters accept any id and member index in the ranges they report.
+    #[cfg(not(fastnoise2_unlinked))]
+    unsafe {
+        (0..crate::fnGetMetadataCount())
+            .map(|id| NodeLayout {
+                name: c_str(crate::fnGetMetadataName(id)),
+                variables: (0..crate::fnGetMetadataVariableCount(id))
+                    .map(|index| VariableLayout {
+                        name: c_str(crate::fnGetMetadataVariableName(id, index)),
+                        kind: match crate::fnGetMetadataVariableType(id, index) {
+                            0 => VariableKind::Float,
+                            1 => VariableKind::Int,
+                            _ => VariableKind::Enum {
+                                variants: crate::fnGetMetadataEnumCount(id, index) as usize,
+                            },
+                        },
+                    })
+                    .collect(),
+                node_lookups: (0..crate::fnGetMetadataNodeLookupCount(id))
+                    .map(|index| c_str(crate::fnGetMetadataNodeLookupName(id, index)))
+                    .collect(),
+                hybrids: (0..crate::fnGetMetadataHybridCount(id))
+                    .map(|index| c_str(crate::fnGetMetadataHybridName(id, index)))
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(fastnoise2_unlinked))]
+unsafe fn c_str(ptr: *const core::ffi::c_char) -> String {
+    std::ffi::CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+/// Decodes an encoded node tree, checking its nodes and members against `nodes`, indexed by metadata id.
+pub fn decode(encoded: &str, nodes: &[NodeLayout]) -> Result<EncodedNode, DecodeError> {
+    let bytes = decode_bytes(encoded)?;
+    let mut reader = Reader::new(&bytes, nodes);
+    let (root, ends) = reader.node(0)?;
+    if ends > 0 {
+        return Err(DecodeError::UnbalancedEnd {
+            offset: reader.offset - 1,
+        });
+    }
+    reader.finish(root)
+}
+
+/// Decodes an encoded node tree exported before fastnoise2 0.4.0, by FastNoise2 0.10, checking it against `nodes`, the
+/// layouts of the nodes of FastNoise2 0.10 indexed by their id.
+///
+/// In that format, a node is its id in two little-endian bytes then all of its members in index order: variables in
+/// 4 bytes, node lookups as nodes, and hybrids as a `0` byte followed by a float or a `1` byte followed by a node. A
+/// node used several times is written once, then referred to by the id `0xFFFF` followed by its index, in two bytes,
+/// among the nodes in the order they end. The text is plain base64.
+///
+/// Every variable of a legacy node is written, including those at their default.
+pub fn decode_legacy(encoded: &str, nodes: &[NodeLayout]) -> Result<EncodedNode, DecodeError> {
+    let bytes = decode_bytes(encoded)?;
+    let mut reader = Reader::new(&bytes, nodes);
+    let root = reader.legacy_node(0)?;
+    reader.finish(root)
+}
+
+/// Encodes a node tree in the format read by [`decode`], merging end tags and writing runs of `A` with `@`.
+///
+/// # Panics
+///
+/// Panics if a node id doesn't fit in a byte.
+pub fn encode(node: &EncodedNode) -> String {
+    let mut bytes = Vec::new();
+    let mut last_end = None;
+    write_node(node, &mut bytes, &mut last_end);
+    encode_bytes(&bytes)
+}
+
+fn write_node(node: &EncodedNode, bytes: &mut Vec<u8>, last_end: &mut Option<usize>) {
+    let id = u8::try_from(node.id).expect("node ids of encoded node trees fit in a byte");
+    bytes.push(id);
+    for (index, value) in &node.variables {
+        bytes.push(tag(VARIABLE, *index));
+        match value {
+            VariableValue::Float(value) => bytes.extend(value.to_le_bytes()),
+            VariableValue::Int(value) => bytes.extend(value.to_le_bytes()),
+        }
+    }
+    for (index, lookup) in node.node_lookups.iter().enumerate() {
+        bytes.push(tag(NODE_LOOKUP, index + 1));
+        write_node(lookup, bytes, last_end);
+    }
+    for (index, value) in &node.hybrids {
+        match value {
+            HybridValue::Float(value) => {
+                bytes.push(tag(HYBRID_FLOAT, *index));
+                bytes.extend(value.to_le_bytes());
+            }
+            HybridValue::Node(hybrid) => {
+                bytes.push(tag(HYBRID_NODE, *index));
+                write_node(hybrid, bytes, last_end);
+            }
+        }
+    }
+    // The end of the last child, right before this one, ends this node too.
+    match *last_end {
+        Some(end) if end + 1 == bytes.len() && usize::from(bytes[end] >> 3) + 1 < MAX_ENDS => {
+            bytes[end] += 1 << 3
+        }
+        _ => {
+            bytes.push(END);
+            *last_end = Some(bytes.len() - 1);
+        }
+    }
+}
+
+fn tag(kind: u8, index: usize) -> u8 {
+    u8::try_from(index << 3).expect("member indices of encoded node trees fit in 5 bits") | kind
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    nodes: &'a [NodeLayout],
+    offset: usize,
+    /// Legacy nodes decoded so far, in the order they end, for references.
+    references: Vec<EncodedNode>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], nodes: &'a [NodeLayout]) -> Self {
+        Self {
+            bytes,
+            nodes,
+            offset: 0,
+            references: Vec::new(),
+        }
+    }
+
+    fn finish(&self, root: EncodedNode) -> Result<EncodedNode, DecodeError> {
+        match self.offset < self.bytes.len() {
+            true => Err(DecodeError::TrailingData {
+                offset: self.offset,
+            }),
+            false => Ok(root),
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + N)
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        self.offset += N;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    fn layout(
+        &self,
+        id: usize,
+        offset: usize,
+        depth: usize,
+    ) -> Result<&'a NodeLayout, DecodeError> {
+        if depth > MAX_DEPTH {
+            return Err(DecodeError::TooDeep { offset });
+        }
+        self.nodes
+            .get(id)
+            .ok_or(DecodeError::UnknownNode { id, offset })
+    }
+
+    fn variable(
+        &mut self,
+        layout: &NodeLayout,
+        index: usize,
+        offset: usize,
+    ) -> Result<VariableValue, DecodeError> {
+        let bytes = self.take::<4>()?;
+        let variable = &layout.variables[index];
+        Ok(match variable.kind {
+            VariableKind::Float => VariableValue::Float(f32::from_le_bytes(bytes)),
+            VariableKind::Int => VariableValue::Int(i32::from_le_bytes(bytes)),
+            VariableKind::Enum { variants } => {
+                let value = i32::from_le_bytes(bytes);
+                if usize::try_from(value).is_ok_and(|value| value < variants) {
+                    VariableValue::Int(value)
+                } else {
+                    return Err(DecodeError::InvalidEnumValue {
+                        node: layout.name.clone(),
+                        member: variable.name.clone(),
+                        value,
+                        offset,
+                    });
+                }
+            }
+        })
+    }
+
+    /// Reads a node, and returns it with the number of its ancestors that end with it.
+    fn node(&mut self, depth: usize) -> Result<(EncodedNode, usize), DecodeError> {
+        let offset = self.offset;
+        let [id] = self.take()?;
+        let layout = self.layout(id.into(), offset, depth)?;
+        let mut node = EncodedNode {
+            id: id.into(),
+            variables: Vec::new(),
+            node_lookups: Vec::new(),
+            hybrids: Vec::new(),
+        };
+        let mut lookups = vec![None; layout.node_lookups.len()];
+        let ends = loop {
+            let offset = self.offset;
+            let [tag] = self.take()?;
+            let index = usize::from(tag >> 3);
+            let unknown_member = || DecodeError::UnknownMember {
+                node: layout.name.clone(),
+                tag,
+                offset,
+            };
+            let duplicate_member = |member: &String| DecodeError::DuplicateMember {
+                node: layout.name.clone(),
+                member: member.clone(),
+                offset,
+            };
+            let ends = match tag & 7 {
+                VARIABLE => {
+                    let variable = layout.variables.get(index).ok_or_else(unknown_member)?;
+                    if node.variables.iter().any(|(set, _)| *set == index) {
+                        return Err(duplicate_member(&variable.name));
+                    }
+                    let value = self.variable(layout, index, offset)?;
+                    node.variables.push((index, value));
+                    0
+                }
+                NODE_LOOKUP => {
+                    let lookup = index
+                        .checked_sub(1)
+                        .and_then(|index| lookups.get_mut(index))
+                        .ok_or_else(unknown_member)?;
+                    if lookup.is_some() {
+                        return Err(duplicate_member(&layout.node_lookups[index - 1]));
+                    }
+                    let (child, ends) = self.node(depth + 1)?;
+                    *lookup = Some(child);
+                    ends
+                }
+                HYBRID_NODE | HYBRID_FLOAT => {
+                    let name = layout.hybrids.get(index).ok_or_else(unknown_member)?;
+                    if node.hybrids.iter().any(|(set, _)| *set == index) {
+                        return Err(duplicate_member(name));
+                    }
+                    let (value, ends) = match tag & 7 {
+                        HYBRID_NODE => {
+                            let (child, ends) = self.node(depth + 1)?;
+                            (HybridValue::Node(child), ends)
+                        }
+                        _ => (HybridValue::Float(f32::from_le_bytes(self.take()?)), 0),
+                    };
+                    node.hybrids.push((index, value));
+                    ends
+                }
+                END => break index,
+                _ => return Err(unknown_member()),
+            };
+            // The end tag of the child ends this node too.
+            if ends > 0 {
+                break ends - 1;
+            }
+        };
+        for (lookup, name) in lookups.into_iter().zip(&layout.node_lookups) {
+            node.node_lookups
+                .push(lookup.ok_or_else(|| DecodeError::MissingNodeLookup {
+                    node: layout.name.clone(),
+                    member: name.clone(),
+                    offset: self.offset - 1,
+                })?);
+        }
+        Ok((node, ends))
+    }
+
+    fn legacy_node(&mut self, depth: usize) -> Result<EncodedNode, DecodeError> {
+        let offset = self.offset;
+        let id = u16::from_le_bytes(self.take()?);
+        if id == LEGACY_REFERENCE {
+            let reference = u16::from_le_bytes(self.take()?).into();
+            return self
+                .references
+                .get(reference)
+                .cloned()
+                .ok_or(DecodeError::InvalidReference { reference, offset });
+        }
+        let layout = self.layout(id.into(), offset, depth)?;
+        let mut node = EncodedNode {
+            id: id.into(),
+            variables: Vec::new(),
+            node_lookups: Vec::new(),
+            hybrids: Vec::new(),
+        };
+        for index in 0..layout.variables.len() {
+            let value = self.variable(layout, index, self.offset)?;
+            node.variables.push((index, value));
+        }
+        for _ in &layout.node_lookups {
+            let lookup = self.legacy_node(depth + 1)?;
+            node.node_lookups.push(lookup);
+        }
+        for (index, name) in layout.hybrids.iter().enumerate() {
+            let offset = self.offset;
+            let value = match self.take()? {
+                [0] => HybridValue::Float(f32::from_le_bytes(self.take()?)),
+                [1] => HybridValue::Node(self.legacy_node(depth + 1)?),
+                [flag] => {
+                    return Err(DecodeError::InvalidHybridFlag {
+                        node: layout.name.clone(),
+                        member: name.clone(),
+                        flag,
+                        offset,
+                    })
+                }
+            };
+            node.hybrids.push((index, value));
+        }
+        self.references.push(node.clone());
+        Ok(node)
+    }
+}
+
+/// Decodes the text of an encoded node tree to bytes, without reading them: base64 with `@` runs of `A`, padded with
+/// at most two `=`.
+pub fn decode_bytes(encoded: &str) -> Result<Vec<u8>, DecodeError> {
+    if encoded.is_empty() {
+        return Err(DecodeError::Empty);
+    }
+    let data = encoded.trim_end_matches('=');
+    let padding = encoded.len() - data.len();
+    if padding > 2 {
+        return Err(DecodeError::TooMuchPadding);
+    }
+
+    let mut digits = Vec::with_capacity(data.len());
+    let mut chars = data.char_indices();
+    while let Some((index, character)) = chars.next() {
+        match character {
+            '@' => match chars.next() {
+                Some((_, run)) if base64_value(run).is_some() => {
+                    let length = 3 + usize::from(base64_value(run).unwrap());
+                    digits.resize(digits.len() + length, 0);
+                }
+                Some((index, character)) => {
+                    return Err(DecodeError::InvalidRunLength { character, index })
+                }
+                None => return Err(DecodeError::UnfinishedRun { index }),
+            },
+            '=' => return Err(DecodeError::MisplacedPadding { index }),
+            character => match base64_value(character) {
+                Some(value) => digits.push(value),
+                None => return Err(DecodeError::InvalidCharacter { character, index }),
+            },
+        }
+    }
+
+    if digits.len() % 4 == 1 || (padding > 0 && !(digits.len() + padding).is_multiple_of(4)) {
+        return Err(DecodeError::Truncated {
+            digits: digits.len(),
+            padding,
+        });
+    }
+    Ok(digits
+        .chunks(4)
+        .flat_map(|chunk| {
+            let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, digit)| {
+                bits | u32::from(*digit) << (18 - 6 * i)
+            });
+            let [_, bytes @ ..] = bits.to_be_bytes();
+            bytes.into_iter().take(chunk.len() - 1)
+        })
+        .collect())
+}
+
+/// Encodes bytes as the text of an encoded node tree, the reverse of [`decode_bytes`].
+fn encode_bytes(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut digits = Vec::new();
+    for chunk in bytes.chunks(3) {
+        let mut padded = [0; 4];
+        padded[1..=chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes(padded);
+        for i in 0..=chunk.len() {
+            digits.push(DIGITS[(bits >> (18 - 6 * i) & 63) as usize]);
+        }
+    }
+
+    let mut encoded = String::new();
+    let mut digits = digits.as_slice();
+    while let Some(&digit) = digits.first() {
+        let run = digits
+            .iter()
+            .take(3 + 63)
+            .take_while(|&&d| d == b'A')
+            .count();
+        if run >= 3 {
+            encoded.push('@');
+            encoded.push(DIGITS[run - 3] as char);
+            digits = &digits[run..];
+        } else {
+            encoded.push(digit as char);
+            digits = &digits[1..];
+        }
+    }
+    encoded.push_str(match bytes.len() % 3 {
+        1 => "==",
+        2 => "=",
+        _ => "",
+    });
+    encoded
+}
+
+fn base64_value(c: char) -> Option<u8> {
+    Some(match c {
+        'A'..='Z' => c as u8 - b'A',
+        'a'..='z' => c as u8 - b'a' + 26,
+        '0'..='9' => c as u8 - b'0' + 52,
+        '+' => 62,
+        '/' => 63,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exported by the Node Editor: a domain warped mountain range.
+    const MOUNTAINS: &str =
+        "E@BBZEG@BD8JFgIECArXIzwECiQIw/UoPwkuAAE@BJDQAH@BC@AIEAJBw@ABZEED0KV78YZmZmPwQDmpkZPwsAAIA/HAMAAHBCBA==";
+
+    /// Exported by the Node Editor of FastNoise2 0.10: a fractal of simplex noise.
+    const LEGACY_FBM: &str = "DQAFAAAAAAAAQAgAAAAAAD8AAAAAAA==";
+
+    fn layout(
+        name: &str,
+        variables: &[(&str, VariableKind)],
+        node_lookups: &[&str],
+        hybrids: &[&str],
+    ) -> NodeLayout {
+        NodeLayout {
+            name: name.to_string(),
+            variables: variables
+                .iter()
+                .map(|(name, kind)| VariableLayout {
+                    name: name.to_string(),
+                    kind: *kind,
+                })
+                .collect(),
+            node_lookups: node_lookups.iter().map(|name| name.to_string()).collect(),
+            hybrids: hybrids.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    fn fractal(name: &str) -> NodeLayout {
+        use VariableKind::*;
+        layout(
+            name,
+            &[("Octaves", Int), ("Lacunarity", Float)],
+            &["Source"],
+            &["Gain", "Weighted Strength"],
+        )
+    }
+
+    /// The nodes of the mountains, at the ids of the library that exported them.
+    fn nodes() -> Vec<NodeLayout> {
+        use VariableKind::*;
+        let mut nodes = vec![layout("Unused", &[], &[], &[]); 47];
+        let output = [
+            ("Feature Scale", Float),
+            ("Seed Offset", Int),
+            ("Output Min", Float),
+            ("Output Max", Float),
+        ];
+        let per_dimension = |name: &str| {
+            ["X", "Y", "Z", "W"].map(|dimension| (format!("{name} {dimension}"), Float))
+        };
+        let multiplier = per_dimension("Multiplier");
+        let multiplier = multiplier
+            .iter()
+            .map(|(name, kind)| (name.as_str(), *kind))
+            .collect::<Vec<_>>();
+        let scaling = per_dimension("Scaling");
+        let scaling = scaling
+            .iter()
+            .map(|(name, kind)| (name.as_str(), *kind))
+            .collect::<Vec<_>>();
+        nodes[4] = layout("Gradient", &multiplier, &[], &[]);
+        nodes[7] = layout("Perlin", &output, &[], &[]);
+        nodes[13] = fractal("Fractal FBm");
+        nodes[16] = layout(
+            "Domain Warp Gradient",
+            &[
+                output[0],
+                output[1],
+                ("Warp Seed", Int),
+                ("Warp Frequency", Float),
+            ],
+            &["Source"],
+            &["Warp Amplitude"],
+        );
+        nodes[22] = layout("Subtract", &[], &[], &["LHS", "RHS"]);
+        nodes[36] = layout("Domain Axis Scale", &scaling, &["Source"], &[]);
+        nodes[46] = layout(
+            "Seed Offset",
+            &[("Seed Offset", Int), ("Mode", Enum { variants: 2 })],
+            &["Source"],
+            &[],
+        );
+        nodes
+    }
+
+    fn node(id: usize) -> EncodedNode {
+        EncodedNode {
+            id,
+            variables: Vec::new(),
+            node_lookups: Vec::new(),
+            hybrids: Vec::new(),
+        }
+    }
+
+    fn mountains() -> EncodedNode {
+        use HybridValue as H;
+        use VariableValue::*;
+        let perlin = EncodedNode {
+            variables: vec![(0, Float(600.0)), (2, Float(-0.84)), (3, Float(0.9))],
+            ..node(7)
+        };
+        let fbm = EncodedNode {
+            variables: vec![(0, Int(7)), (1, Float(2.5))],
+            node_lookups: vec![perlin],
+            hybrids: vec![(0, H::Float(0.6)), (1, H::Float(1.0))],
+            ..node(13)
+        };
+        let seed_offset = EncodedNode {
+            variables: vec![(0, Int(1))],
+            node_lookups: vec![fbm],
+            ..node(46)
+        };
+        let scale = EncodedNode {
+            variables: vec![(1, Float(0.66))],
+            node_lookups: vec![seed_offset],
+            ..node(36)
+        };
+        let gradient = EncodedNode {
+            variables: vec![(1, Float(0.01))],
+            ..node(4)
+        };
+        let subtract = EncodedNode {
+            hybrids: vec![(0, H::Node(gradient)), (1, H::Node(scale))],
+            ..node(22)
+        };
+        EncodedNode {
+            variables: vec![(0, Float(600.0)), (3, Float(0.5))],
+            node_lookups: vec![subtract],
+            hybrids: vec![(0, H::Float(60.0))],
+            ..node(16)
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(MOUNTAINS, &nodes()), Ok(mountains()));
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&mountains()), MOUNTAINS);
+        let gradient = node(4);
+        assert_eq!(decode(&encode(&gradient), &nodes()), Ok(gradient));
+    }
+
+    #[test]
+    fn test_encode_merges_ends() {
+        let mut tree = node(4);
+        for _ in 0..40 {
+            tree = EncodedNode {
+                node_lookups: vec![tree],
+                ..node(36)
+            };
+        }
+        let bytes = decode_bytes(&encode(&tree)).unwrap();
+        assert_eq!(bytes[bytes.len() - 2..], [4 | 31 << 3, 4 | 8 << 3]);
+        assert_eq!(decode(&encode(&tree), &nodes()), Ok(tree));
+    }
+
+    #[test]
+    fn test_decode_legacy() {
+        use VariableKind::*;
+        let mut nodes = vec![layout("Unused", &[], &[], &[]); 14];
+        nodes[8] = layout("Simplex", &[], &[], &[]);
+        nodes[13] = fractal("FractalFBm");
+        let fbm = EncodedNode {
+            variables: vec![(0, VariableValue::Int(5)), (1, VariableValue::Float(2.0))],
+            node_lookups: vec![node(8)],
+            hybrids: vec![(0, HybridValue::Float(0.5)), (1, HybridValue::Float(0.0))],
+            ..node(13)
+        };
+        assert_eq!(decode_legacy(LEGACY_FBM, &nodes), Ok(fbm));
+        assert_eq!(
+            decode_legacy("DQAFAAAAAAAAQAgAAAAAAD8=", &nodes),
+            Err(DecodeError::UnexpectedEnd)
+        );
+
+        // A hybrid of the root refers to its first node, the lookup.
+        nodes[0] = layout("Add", &[("Unused", Int)], &["LHS"], &["RHS"]);
+        let add = EncodedNode {
+            variables: vec![(0, VariableValue::Int(0))],
+            node_lookups: vec![node(8)],
+            hybrids: vec![(0, HybridValue::Node(node(8)))],
+            ..node(0)
+        };
+        assert_eq!(decode_legacy("AAAAAAAACAAB//8AAA==", &nodes), Ok(add));
+        assert_eq!(
+            decode_legacy("AAAAAAAACAAB//8BAA==", &nodes),
+            Err(DecodeError::InvalidReference {
+                reference: 1,
+                offset: 9
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let nodes = nodes();
+        let decode = |bytes: &[u8]| decode(&encode_bytes(bytes), &nodes);
+        assert_eq!(decode(&[4]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            decode(&[4, 4, 4]),
+            Err(DecodeError::TrailingData { offset: 2 })
+        );
+        assert_eq!(
+            decode(&[4, 12]),
+            Err(DecodeError::UnbalancedEnd { offset: 1 })
+        );
+        assert_eq!(
+            decode(&[47, 4]),
+            Err(DecodeError::UnknownNode { id: 47, offset: 0 })
+        );
+        assert_eq!(
+            decode(&[4, 0x20, 0, 0, 0, 0, 4]),
+            Err(DecodeError::UnknownMember {
+                node: "Gradient".to_string(),
+                tag: 0x20,
+                offset: 1
+            })
+        );
+        assert_eq!(
+            decode(&[36, 0x01, 4, 4, 4]),
+            Err(DecodeError::UnknownMember {
+                node: "Domain Axis Scale".to_string(),
+                tag: 0x01,
+                offset: 1
+            })
+        );
+        assert_eq!(
+            decode(&[4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4]),
+            Err(DecodeError::DuplicateMember {
+                node: "Gradient".to_string(),
+                member: "Multiplier X".to_string(),
+                offset: 6
+            })
+        );
+        assert_eq!(
+            decode(&[36, 4]),
+            Err(DecodeError::MissingNodeLookup {
+                node: "Domain Axis Scale".to_string(),
+                member: "Source".to_string(),
+                offset: 1
+            })
+        );
+        assert_eq!(
+            decode(&[46, 0x08, 2, 0, 0, 0, 0x09, 4, 4, 4]),
+            Err(DecodeError::InvalidEnumValue {
+                node: "Seed Offset".to_string(),
+                member: "Mode".to_string(),
+                value: 2,
+                offset: 1
+            })
+        );
+
+        let mut bytes = [36, 0x09].repeat(MAX_DEPTH + 1);
+        bytes.extend([4, 4]);
+        assert_eq!(
+            decode(&bytes),
+            Err(DecodeError::TooDeep {
+                offset: 2 * MAX_DEPTH + 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_bytes() {
+        assert_eq!(
+            decode_bytes(MOUNTAINS).unwrap()[..6],
+            [0x10, 0, 0, 0, 0x16, 0x44]
+        );
+        assert_eq!(decode_bytes(LEGACY_FBM).unwrap().len(), 22);
+        assert_eq!(
+            decode_bytes("DQAFAAAAAAAAQAgAAAAAAD8AAAAAAA")
+                .unwrap()
+                .len(),
+            22
+        );
+        assert_eq!(decode_bytes("DQAFAAAAAAAAQAgAAAAAAD8=").unwrap().len(), 17);
+        assert_eq!(encode_bytes(&decode_bytes(MOUNTAINS).unwrap()), MOUNTAINS);
+    }
+
+    #[test]
+    fn test_decode_bytes_errors() {
+        assert_eq!(decode_bytes(""), Err(DecodeError::Empty));
+        assert_eq!(
+            decode_bytes("DQAF AAAA"),
+            Err(DecodeError::InvalidCharacter {
+                character: ' ',
+                index: 4
+            })
+        );
+        assert_eq!(
+            decode_bytes("DQAF\n").unwrap_err().to_string(),
+            "invalid character '\\n' at 4 in encoded node tree"
+        );
+        assert_eq!(
+            decode_bytes("DQ=AF"),
+            Err(DecodeError::MisplacedPadding { index: 2 })
+        );
+        assert_eq!(decode_bytes("DQAF==="), Err(DecodeError::TooMuchPadding));
+        assert_eq!(
+            decode_bytes("DQAFA").unwrap_err().to_string(),
+            "encoded node tree is truncated: 5 base64 digits and 0 `=` don't make whole bytes"
+        );
+        assert_eq!(
+            decode_bytes("DQAFAA="),
+            Err(DecodeError::Truncated {
+                digits: 6,
+                padding: 1
+            })
+        );
+        assert_eq!(
+            decode_bytes("E@BBZEG@"),
+            Err(DecodeError::UnfinishedRun { index: 7 })
+        );
+        assert_eq!(
+            decode_bytes("E@@BBZ"),
+            Err(DecodeError::InvalidRunLength {
+                character: '@',
+                index: 2
+            })
+        );
+    }
+}
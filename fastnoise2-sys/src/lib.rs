@@ -24,6 +24,23 @@
 //!
 //! - If you prefer not to build from source, precompiled binaries are available for download from the [FastNoise2 Releases](https://github.com/Auburn/FastNoise2/releases).
 //! - The `FASTNOISE2_SOURCE_DIR` environment variable is generally not needed as fastnoise2-sys includes the FastNoise2 source code as a Git submodule. If you need to use a different source directory, set `FASTNOISE2_SOURCE_DIR` to point to the root of the FastNoise2 source code.
+//! - A precompiled library can't be inspected, set `FASTNOISE2_LIB_COMMIT` to the FastNoise2 commit it was built from so that it is reported by [`FASTNOISE2_COMMIT`].
 //!
 #![allow(non_upper_case_globals, non_camel_case_types, non_snake_case)]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+pub mod encoded;
+
+/// How the linked FastNoise2 library was obtained: `source`, `prebuilt`, `wasm` or `docs.rs` (nothing linked).
+pub const FASTNOISE2_BUILD_KIND: &str = env!("FASTNOISE2_BUILD_KIND");
+
+/// Commit of the FastNoise2 sources the linked library was built from, or empty if unknown.
+///
+/// For a precompiled library, this is the value of `FASTNOISE2_LIB_COMMIT` when building.
+pub const FASTNOISE2_COMMIT: &str = env!("FASTNOISE2_COMMIT");
+
+/// Release compiler flags the linked library was built with, or empty for a precompiled library.
+pub const FASTNOISE2_BUILD_FLAGS: &str = env!("FASTNOISE2_BUILD_FLAGS");
+
+/// Commit of the FastNoise2 submodule these bindings are written against.
+pub const FASTNOISE2_BUNDLED_COMMIT: &str = env!("FASTNOISE2_BUNDLED_COMMIT");